@@ -0,0 +1,138 @@
+use crate::crypto::Ledger;
+use crate::feed::PriceSource;
+use crate::ltl::{SafetyMonitor, SentinelEvent, ViolationInfo};
+use crate::manager::QuantumManager;
+use crate::scheduler::CycleScheduler;
+use crate::VOL_SPIKE_THRESHOLD;
+use std::collections::BTreeMap;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+/// What a replay produced. `ledger_entries` counts verified entries added during the run.
+#[derive(Debug, Clone)]
+pub struct BacktestReport {
+    pub ticks: u64,
+    pub violations: Vec<ViolationInfo>,
+    pub jobs_submitted: u64,
+    pub ledger_entries: usize,
+}
+
+impl BacktestReport {
+    /// Totals line, then one line per violated property in name order
+    pub fn summary(&self) -> String {
+        let mut by_property: BTreeMap<&str, usize> = BTreeMap::new();
+        for violation in &self.violations {
+            *by_property.entry(violation.property.as_str()).or_default() += 1;
+        }
+        let mut summary = format!("{} ticks, {} violations, {} jobs submitted, {} ledger entries",
+                                  self.ticks, self.violations.len(), self.jobs_submitted, self.ledger_entries);
+        for (property, count) in by_property {
+            summary.push_str(&format!("\n  {}: {}", property, count));
+        }
+        summary
+    }
+}
+
+/// Replays a price path through the same monitor -> manager -> ledger pipeline as the live loop,
+/// minus wall-clock pacing. The manager is forced into dry-run (digital twin), so with a seeded
+/// source the report is deterministic and no network is touched.
+pub struct Backtest {
+    source: Box<dyn PriceSource>,
+    steps: u64,
+    monitor: SafetyMonitor,
+    manager: QuantumManager,
+    ledger: Ledger,
+    scheduler: CycleScheduler,
+}
+
+impl Backtest {
+    pub fn new(source: Box<dyn PriceSource>, steps: u64, monitor: SafetyMonitor, manager: QuantumManager, ledger: Ledger) -> Self {
+        Self {
+            source,
+            steps,
+            monitor,
+            manager: manager.with_dry_run(true),
+            ledger,
+            scheduler: CycleScheduler::new(50),
+        }
+    }
+
+    /// Builder: Ticks between optimization cycles (the `[cadence] optimize_every` of the live loop)
+    pub fn with_optimize_every(mut self, optimize_every: u64) -> Self {
+        self.scheduler = CycleScheduler::new(optimize_every);
+        self
+    }
+
+    /// Builder: Replay with the live loop's cycle policy, e.g. `CycleScheduler::from_config(&config.cadence)`
+    pub fn with_scheduler(mut self, scheduler: CycleScheduler) -> Self {
+        self.scheduler = scheduler;
+        self
+    }
+
+    /// Runs until `steps` ticks were consumed or the source ran dry
+    pub async fn run(mut self) -> BacktestReport {
+        let entries_before = self.ledger.verify_log().map(|e| e.len()).unwrap_or(0);
+        let mut report = BacktestReport { ticks: 0, violations: Vec::new(), jobs_submitted: 0, ledger_entries: 0 };
+
+        for step in 1..=self.steps {
+            let Some(tick) = self.source.tick() else { break };
+            report.ticks += 1;
+
+            if tick.vol > VOL_SPIKE_THRESHOLD {
+                report.violations.extend(self.monitor.check(&SentinelEvent::VolatilitySpike(tick.vol)));
+            }
+            let violations = self.monitor.check(&SentinelEvent::PriceUpdate(tick.price));
+            if !violations.is_empty() {
+                report.violations.extend(violations);
+                continue; // Same as the live loop: no optimization on a violating tick
+            }
+
+            if self.scheduler.should_run(&tick) {
+                let outcome = self.manager.run_optimization_cycle(step, tick, &mut self.ledger, &CancellationToken::new()).await;
+                if let Some(job_id) = outcome.job_id {
+                    report.jobs_submitted += 1;
+                    report.violations.extend(self.monitor.check(&SentinelEvent::JobSubmitted(job_id)));
+                }
+            }
+        }
+
+        if let Err(e) = self.ledger.flush() {
+            warn!("Backtest: Ledger flush failed: {}", e);
+        }
+        report.ledger_entries = match self.ledger.verify_log() {
+            Ok(entries) => entries.len().saturating_sub(entries_before),
+            Err(e) => {
+                warn!("Backtest: Ledger verification failed: {}", e);
+                0
+            }
+        };
+        info!("Backtest: {} ticks, {} violations, {} jobs, {} ledger entries",
+              report.ticks, report.violations.len(), report.jobs_submitted, report.ledger_entries);
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feed::{HestonParams, SentinelFeed};
+    use crate::interop::InteropCapabilities;
+
+    #[tokio::test]
+    async fn a_seeded_backtest_pins_its_summary() {
+        let dir = std::env::temp_dir().join(format!("sentinel-backtest-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let run = || {
+            let ledger = Ledger::new(dir.join(format!("ledger-{}.log", rand::random::<u64>())).to_str().unwrap()).unwrap();
+            // No Python stack: the classical path, whatever this machine has installed
+            let manager = QuantumManager::new("/nonexistent/knowledge.json").with_capabilities(InteropCapabilities::default());
+            let source = Box::new(SentinelFeed::with_seed(HestonParams::default(), 7));
+            Backtest::new(source, 500, SafetyMonitor::new(10), manager, ledger).with_optimize_every(25).run()
+        };
+
+        let summary = run().await.summary();
+        assert_eq!(summary, run().await.summary());
+        assert_eq!(summary, "500 ticks, 45 violations, 0 jobs submitted, 0 ledger entries\n  hedge_liveness: 45");
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
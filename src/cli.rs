@@ -0,0 +1,247 @@
+use crate::backtest::Backtest;
+use crate::config::{FeedModel, SentinelConfig, DEFAULT_CONFIG_PATH};
+use crate::crypto::{self, Ledger, LedgerEntry, LedgerError, LedgerRecord};
+use crate::feed::{PriceSource, SentinelFeed};
+use crate::feed::sabr::SabrFeed;
+use crate::ltl::SafetyMonitor;
+use crate::manager::QuantumManager;
+use crate::payoff;
+use crate::scheduler::CycleScheduler;
+use crate::sre::SentinelSRE;
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+/// Sentinel Hypervisor. Without a subcommand, runs the hypervisor event loop.
+#[derive(Debug, Parser)]
+#[command(name = "sentinel", version)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Re-verify every signature in a ledger file and print a pass/fail summary
+    VerifyLedger {
+        /// Ledger to audit
+        #[arg(long, default_value = "sentinel_ledger.log")]
+        file: String,
+        /// Audit every rotated ledger in this directory instead: each segment and active file,
+        /// with its chain link to the one before, reported pass/fail
+        #[arg(long, conflicts_with = "file")]
+        dir: Option<PathBuf>,
+        /// Trusted public keys, hex-encoded one per line (the `pk=` of each session header).
+        /// Without it, the keys embedded in the ledger are taken at face value.
+        #[arg(long)]
+        pubkey: Option<PathBuf>,
+    },
+    /// Value the `[basket]` portfolio by Monte-Carlo over the multi-asset feed
+    PriceBasket {
+        /// Settings file; defaults to $SENTINEL_CONFIG, then sentinel.toml
+        #[arg(long)]
+        config: Option<String>,
+    },
+    /// Replay the configured feed through the monitor -> manager -> ledger pipeline in dry-run
+    /// and print the report
+    Backtest {
+        /// Settings file; defaults to $SENTINEL_CONFIG, then sentinel.toml
+        #[arg(long)]
+        config: Option<String>,
+        /// Ticks to replay (fewer if a replay file runs out first)
+        #[arg(long, default_value_t = 1000)]
+        steps: u64,
+        /// Seed for a heston or sabr feed, for a reproducible report; a replay is deterministic already
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Ticks between optimization cycles; defaults to the `[cadence]` policy
+        #[arg(long)]
+        optimize_every: Option<u64>,
+        /// Ledger the replayed cycles are signed into, kept apart from the live one
+        #[arg(long, default_value = "backtest_ledger.log")]
+        ledger: String,
+    },
+}
+
+/// Runs an operator subcommand and returns the process exit code
+pub async fn run(command: Command) -> i32 {
+    match command {
+        Command::VerifyLedger { file, dir, pubkey } => verify_ledger(&file, dir.as_deref(), pubkey.as_deref()),
+        Command::PriceBasket { config } => price_basket(config),
+        Command::Backtest { config, steps, seed, optimize_every, ledger } =>
+            backtest(config, steps, seed, optimize_every, &ledger).await,
+    }
+}
+
+/// Exit codes: 0 = every entry verified, 1 = verification failed, 2 = unusable key file
+fn verify_ledger(file: &str, dir: Option<&std::path::Path>, pubkey: Option<&std::path::Path>) -> i32 {
+    let trusted = match pubkey.map(load_keys).transpose() {
+        Ok(keys) => keys.unwrap_or_default(),
+        Err(e) => {
+            eprintln!("verify-ledger: {}", e);
+            return 2;
+        }
+    };
+    let target = dir.map_or_else(|| file.to_string(), |d| d.display().to_string());
+    if trusted.is_empty() {
+        println!("WARNING: no --pubkey given; trusting the keys embedded in {}", target);
+    }
+    if let Some(dir) = dir {
+        return verify_segments(dir, &trusted);
+    }
+
+    match crypto::verify_file(file, &trusted) {
+        Ok(entries) => {
+            println!("PASS: {} entries verified in {} ({} trusted keys)", entries.len(), file, trusted.len());
+            print_summary(&entries);
+            0
+        }
+        Err(LedgerError::Io(e)) => {
+            println!("FAIL: cannot read {}: {}", file, e);
+            1
+        }
+        Err(e) => {
+            println!("FAIL: {}: {}", file, e);
+            1
+        }
+    }
+}
+
+/// `--dir` mode: one PASS/FAIL line per file of every rotation chain, then the verdict
+fn verify_segments(dir: &std::path::Path, trusted: &[fips204::ml_dsa_65::PublicKey]) -> i32 {
+    let reports = match Ledger::verify_all(dir, trusted) {
+        Ok(reports) => reports,
+        Err(e) => {
+            println!("FAIL: cannot read {}: {}", dir.display(), e);
+            return 1;
+        }
+    };
+    if reports.is_empty() {
+        println!("FAIL: no rotated ledgers in {}", dir.display());
+        return 1;
+    }
+
+    let mut entries = Vec::new();
+    let mut failed = 0;
+    for report in reports {
+        match report.result {
+            Ok(segment) => {
+                println!("  PASS {}: {} entries", report.path, segment.len());
+                entries.extend(segment);
+            }
+            Err(e) => {
+                match e {
+                    // The error names the segment already
+                    LedgerError::BrokenChain { .. } => println!("  FAIL {}", e),
+                    e => println!("  FAIL {}: {}", report.path, e),
+                }
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        println!("FAIL: {} segments failed in {} ({} entries verified elsewhere)", failed, dir.display(), entries.len());
+        return 1;
+    }
+    println!("PASS: {} entries verified in {} ({} trusted keys)", entries.len(), dir.display(), trusted.len());
+    print_summary(&entries);
+    0
+}
+
+fn print_summary(entries: &[LedgerEntry]) {
+    let pricing = entries.iter().filter(|e| matches!(e.record, LedgerRecord::Pricing { .. })).count();
+    let aborted = entries.iter().filter(|e| matches!(e.record, LedgerRecord::Aborted { .. })).count();
+    println!("  records: {} transactions, {} pricing, {} aborted", entries.len() - pricing - aborted, pricing, aborted);
+    if let (Some(first), Some(last)) = (entries.first(), entries.last()) {
+        println!("  span: {} .. {}", first.timestamp, last.timestamp);
+    }
+}
+
+/// Exit codes: 0 = priced, 2 = unreadable settings or no usable `[basket]` in them
+fn price_basket(config: Option<String>) -> i32 {
+    let path = config_path(config);
+    let config = match SentinelConfig::load_or_default(&path) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("price-basket: {}", e);
+            return 2;
+        }
+    };
+    let (basket, option) = (&config.basket, &config.option);
+    match payoff::monte_carlo(&basket.positions, &basket.assets, basket.correlation_matrix(),
+                              option.risk_free_rate, option.time_to_maturity, basket.paths, basket.seed) {
+        Ok(estimate) => {
+            println!("Portfolio of {} positions on {} assets, maturity {}y at r = {}",
+                     basket.positions.len(), basket.assets.len(), option.time_to_maturity, option.risk_free_rate);
+            println!("  value: {:.4} +/- {:.4} (95%, {} paths)", estimate.price, estimate.half_width(), estimate.paths);
+            0
+        }
+        Err(e) => {
+            eprintln!("price-basket: [basket] in {}: {}", path, e);
+            2
+        }
+    }
+}
+
+/// Exit codes: 0 = replayed, 2 = unreadable settings, a live feed, or a feed or ledger that cannot be opened
+async fn backtest(config: Option<String>, steps: u64, seed: Option<u64>, optimize_every: Option<u64>, ledger: &str) -> i32 {
+    let path = config_path(config);
+    let mut config = match SentinelConfig::load_or_default(&path) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("backtest: {}", e);
+            return 2;
+        }
+    };
+    // Replays run as fast as the pipeline allows
+    config.replay.paced = false;
+    let source: Box<dyn PriceSource> = match (config.feed_model, seed) {
+        (FeedModel::Live, _) => {
+            eprintln!("backtest: a live feed cannot be replayed; set feed_model to heston, sabr or replay in {}", path);
+            return 2;
+        }
+        (FeedModel::Heston, Some(seed)) => Box::new(SentinelFeed::with_seed(config.feed, seed)),
+        (FeedModel::Sabr, Some(seed)) => Box::new(SabrFeed::with_seed(config.sabr, seed)),
+        _ => match config.price_source(&SentinelSRE::new()) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("backtest: {}", e);
+                return 2;
+            }
+        },
+    };
+    let ledger = match Ledger::new(ledger) {
+        Ok(ledger) => ledger.with_format(config.ledger_format),
+        Err(e) => {
+            eprintln!("backtest: cannot open ledger {}: {}", ledger, e);
+            return 2;
+        }
+    };
+    let manager = QuantumManager::new(&config.knowledge_path)
+        .with_gate_model(config.coherence)
+        .with_simulated_pricing(config.simulate_pricing);
+
+    let mut run = Backtest::new(source, steps, SafetyMonitor::new(10), manager, ledger)
+        .with_scheduler(CycleScheduler::from_config(&config.cadence));
+    if let Some(optimize_every) = optimize_every {
+        run = run.with_optimize_every(optimize_every);
+    }
+    println!("{}", run.run().await.summary());
+    0
+}
+
+/// `--config`, then $SENTINEL_CONFIG, then sentinel.toml
+fn config_path(config: Option<String>) -> String {
+    config
+        .or_else(|| std::env::var("SENTINEL_CONFIG").ok())
+        .unwrap_or_else(|| DEFAULT_CONFIG_PATH.to_string())
+}
+
+fn load_keys(path: &std::path::Path) -> Result<Vec<fips204::ml_dsa_65::PublicKey>, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+    text.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .enumerate()
+        .map(|(i, key)| crypto::parse_public_key(key).map_err(|e| format!("{} key {}: {}", path.display(), i + 1, e)))
+        .collect()
+}
@@ -0,0 +1,55 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Time source for time-dependent logic (breaker windows and cooldowns, uptime, LTL deadlines),
+/// injectable so that logic can be driven without real sleeps
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+
+    fn elapsed_since(&self, earlier: Instant) -> Duration {
+        self.now().saturating_duration_since(earlier)
+    }
+}
+
+/// Wall clock: `Instant::now()`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Manually advanced clock. Time stands still until `advance` is called; clones share the same
+/// time, so a test keeps one handle and gives another to the code under test.
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub struct MockClock {
+    start: Instant,
+    offset: Arc<Mutex<Duration>>,
+}
+
+impl MockClock {
+    #[allow(dead_code)]
+    pub fn new() -> Self {
+        Self { start: Instant::now(), offset: Arc::new(Mutex::new(Duration::ZERO)) }
+    }
+
+    #[allow(dead_code)]
+    pub fn advance(&self, by: Duration) {
+        *self.offset.lock().unwrap() += by;
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.start + *self.offset.lock().unwrap()
+    }
+}
@@ -0,0 +1,402 @@
+use crate::crypto::{LedgerFormat, DEFAULT_RETRY_BUDGET};
+use crate::feed::live::{LiveFeed, LiveFeedError};
+use crate::manager::DEFAULT_MAX_CONCURRENT_JOBS;
+use crate::metrics::DEFAULT_JOB_DURATION_BUCKETS;
+use crate::payoff::Position;
+use crate::qpu::{TokenSource, DEFAULT_REQUESTS_PER_SECOND};
+use crate::sre::{GateModel, SentinelSRE};
+use crate::feed::replay::{ReplayError, ReplayFeed};
+use crate::feed::{HestonParams, OverflowPolicy, PriceSource, SabrFeed, SabrParams, SentinelFeed, DEFAULT_TICK_BUFFER};
+use log::{info, warn};
+use serde::Deserialize;
+use std::path::Path;
+use thiserror::Error;
+
+pub const DEFAULT_CONFIG_PATH: &str = "sentinel.toml";
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("cannot read {path}: {source}")]
+    Io { path: String, source: std::io::Error },
+    #[error("invalid {path}: {source}")]
+    Parse { path: String, source: toml::de::Error },
+    #[error("live feed: {0}")]
+    LiveFeed(#[from] LiveFeedError),
+    #[error("replay feed: {0}")]
+    Replay(#[from] ReplayError),
+    #[error("{path}: a {feed:?} feed needs [option] volatility to price at")]
+    MissingVolatility { path: String, feed: FeedModel },
+}
+
+/// Market model driving the hypervisor
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedModel {
+    #[default]
+    Heston,
+    Sabr,
+    Live,
+    Replay,
+}
+
+/// Tick and workflow cadence (the `[cadence]` table)
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct CadenceConfig {
+    pub tick_interval_ms: u64, // Feed step period
+    pub optimize_every: u64,   // Max ticks between pricing + QAOA cycles
+    pub report_every: u64,     // Ticks between price / LTL metric reports
+    /// Run a cycle as soon as tick volatility rises above this level; unset = fixed cadence
+    pub vol_trigger: Option<f64>,
+    pub tick_buffer: usize, // Ticks queued between the feed and the main loop
+    /// What the feed does when the buffer is full: "block", "drop_oldest" or "drop_newest"
+    pub overflow: OverflowPolicy,
+}
+
+impl Default for CadenceConfig {
+    fn default() -> Self {
+        Self {
+            tick_interval_ms: 50,
+            optimize_every: 50,
+            report_every: 10,
+            vol_trigger: None,
+            tick_buffer: DEFAULT_TICK_BUFFER,
+            overflow: OverflowPolicy::default(),
+        }
+    }
+}
+
+/// Exchange WebSocket feed (the `[live]` table), used when `feed_model = "live"`
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(default)]
+pub struct LiveConfig {
+    pub url: String,
+    /// Raw message sent after each (re)connect to subscribe to the trade channel
+    pub subscribe: Option<String>,
+}
+
+/// Recorded price path (the `[replay]` table), used when `feed_model = "replay"`
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(default)]
+pub struct ReplayConfig {
+    pub path: String, // CSV of `price` or `ts,price`
+    pub paced: bool,  // Reproduce the recorded inter-tick gaps instead of the cadence interval
+}
+
+/// Option contract priced each optimization cycle (the `[option]` table). Rates are continuously
+/// compounded and annualized; maturity is in years.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct OptionConfig {
+    pub strike: f64,
+    pub risk_free_rate: f64,
+    pub dividend_yield: f64,
+    pub time_to_maturity: f64,
+    /// Annualized volatility to price at; unset = the volatility of the tick that triggered the cycle.
+    /// Required with a live or replay feed, whose ticks only carry a realized-vol estimate.
+    pub volatility: Option<f64>,
+}
+
+impl Default for OptionConfig {
+    fn default() -> Self {
+        Self {
+            strike: 105.0,
+            risk_free_rate: 0.05,
+            dividend_yield: 0.0,
+            time_to_maturity: 0.1,
+            volatility: None,
+        }
+    }
+}
+
+/// Multi-asset portfolio valued by `sentinel price-basket` (the `[basket]` table): Heston
+/// underlyings, their price correlation and the positions held. Rate and maturity come from
+/// `[option]`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct BasketConfig {
+    pub assets: Vec<HestonParams>,
+    /// Asset price correlation; empty = uncorrelated
+    pub correlation: Vec<Vec<f64>>,
+    pub positions: Vec<Position>,
+    /// Monte-Carlo paths
+    pub paths: usize,
+    pub seed: u64,
+}
+
+impl Default for BasketConfig {
+    fn default() -> Self {
+        Self { assets: Vec::new(), correlation: Vec::new(), positions: Vec::new(), paths: 10_000, seed: 0 }
+    }
+}
+
+impl BasketConfig {
+    /// The configured correlation matrix, or the identity when none is given
+    pub fn correlation_matrix(&self) -> Vec<Vec<f64>> {
+        if !self.correlation.is_empty() {
+            return self.correlation.clone();
+        }
+        let n = self.assets.len();
+        (0..n).map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect()).collect()
+    }
+}
+
+/// Runtime submission target (the `[runtime]` table)
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct RuntimeConfig {
+    pub program_id: String,
+    /// Backend to open sessions on; unset = the hardware inferred from the knowledge graph.
+    /// "pasqal" / "quera" submit to that neutral-atom provider instead of IBM Runtime
+    pub backend: Option<String>,
+    /// Open session id, kept across restarts so the billed session is resumed or closed; "" = off
+    pub session_file: String,
+    /// Where the API token is read from, in order (see `TokenSource`); default: the environment
+    pub token_sources: Vec<TokenSource>,
+    /// Jobs allowed in flight at once; further cycles are skipped until one finishes
+    pub max_concurrent_jobs: usize,
+    /// Outbound IBM API requests per second; bursts beyond it wait rather than fail. 0 = unlimited
+    pub requests_per_second: f64,
+    /// Upper bounds (seconds) of the job turnaround histogram on /metrics
+    pub job_duration_buckets: Vec<f64>,
+    /// Run a cycle on the digital twin when the device has more pending jobs than this; unset = always submit
+    pub max_pending_jobs: Option<u64>,
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self {
+            program_id: "hedge_qaoa_v1".to_string(),
+            backend: None,
+            session_file: "sentinel_session.json".to_string(),
+            token_sources: vec![TokenSource::Env],
+            max_concurrent_jobs: DEFAULT_MAX_CONCURRENT_JOBS,
+            requests_per_second: DEFAULT_REQUESTS_PER_SECOND,
+            job_duration_buckets: DEFAULT_JOB_DURATION_BUCKETS.to_vec(),
+            max_pending_jobs: None,
+        }
+    }
+}
+
+/// Operator-editable settings loaded from sentinel.toml. Every field has a default, so a partial
+/// file (or none at all) is valid:
+///
+/// ```toml
+/// ledger_path = "sentinel_ledger.log"
+/// ledger_max_bytes = 67108864 # rotate at 64 MiB
+/// ledger_format = "jsonl" # or "pipe" (default)
+/// ledger_retry_budget = 3 # failed writes before buffered entries go to <ledger_path>.dead
+/// dry_run = true # hermetic: no IBM / neutral-atom network calls, deterministic synthetic results
+/// require_knowledge_graph = true # refuse to start if knowledge_path fails to load
+/// job_outcomes_path = "job_outcomes.json" # learned per-depth job outcomes; the graph file is left as is
+/// simulate_pricing = true # price by IQAE on the digital twin, even without quantum_pricing
+/// feed_model = "heston" # or "sabr" / "live" / "replay", parameterised by the matching table
+/// allow_simulated_fallback = true # a live/replay feed that fails to start is replaced by Heston
+///
+/// [feed]
+/// s0 = 100.0
+/// xi = 0.3
+/// vol_clip = 10.0 # clamp variance at 10 x theta
+/// measure = "real_world" # drift mu; "risk_neutral" drifts at risk_free_rate
+/// burn_in = 500 # steps run before the first tick so the variance starts near stationarity
+///
+/// [cadence]
+/// tick_interval_ms = 50
+/// optimize_every = 50
+/// vol_trigger = 0.3 # also cycle early when volatility spikes past this
+/// overflow = "drop_oldest" # a stalled loop resumes on the freshest ticks
+///
+/// [option]
+/// strike = 105.0
+/// risk_free_rate = 0.05
+/// dividend_yield = 0.01
+/// time_to_maturity = 0.25
+/// volatility = 0.2 # omit to price at each tick's model volatility (heston / sabr feeds only)
+///
+/// [runtime]
+/// backend = "ibm_torino" # or "pasqal" / "quera" for a neutral-atom provider
+/// token_sources = [{ file = "/run/secrets/ibm_token" }, "env"]
+/// max_concurrent_jobs = 4
+/// requests_per_second = 5.0
+/// job_duration_buckets = [1.0, 5.0, 30.0, 120.0, 600.0] # seconds
+/// max_pending_jobs = 500 # deeper queues send the cycle to the digital twin
+///
+/// [basket]
+/// paths = 20000
+/// correlation = [[1.0, 0.6], [0.6, 1.0]]
+/// assets = [{ s0 = 100.0, v0 = 0.04, theta = 0.04 }, { s0 = 50.0, v0 = 0.09, theta = 0.09 }]
+/// positions = [
+///     { kind = "european_call", asset = 0, strike = 105.0 },
+///     { kind = "european_put", asset = 1, strike = 45.0, quantity = -2.0 },
+///     { kind = "basket_call", weights = [0.5, 1.0], strike = 100.0 },
+///     { kind = "digital", asset = 1, strike = 55.0 },
+/// ]
+///
+/// [coherence]
+/// single_qubit_ns = 35.0 # gate times for hardware whose graph node records none
+/// two_qubit_ns = 300.0
+/// safety_fraction = 0.5 # share of the coherence limit a circuit may use
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct SentinelConfig {
+    pub ledger_path: String,
+    /// Rotate the ledger once it reaches this many bytes; 0 = a single ever-growing file
+    pub ledger_max_bytes: u64,
+    pub ledger_format: LedgerFormat,
+    /// Consecutive failed ledger writes before buffered entries are also dead-lettered
+    pub ledger_retry_budget: u32,
+    pub knowledge_path: String,
+    /// Startup fails when the knowledge graph cannot be loaded, instead of running on defaults
+    pub require_knowledge_graph: bool,
+    /// Job outcomes learned per device and depth, restored at startup and saved as jobs finish
+    pub job_outcomes_path: String,
+    pub metrics_addr: String,
+    pub dry_run: bool,
+    /// Run the IQAE pricing loop end to end on the digital twin (development, CI)
+    pub simulate_pricing: bool,
+    /// Run on the Heston simulator when the live or replay feed cannot start, instead of refusing to start
+    pub allow_simulated_fallback: bool,
+    pub feed_model: FeedModel,
+    pub feed: HestonParams,
+    pub sabr: SabrParams,
+    pub live: LiveConfig,
+    pub replay: ReplayConfig,
+    pub cadence: CadenceConfig,
+    pub option: OptionConfig,
+    pub runtime: RuntimeConfig,
+    pub coherence: GateModel,
+    pub basket: BasketConfig,
+}
+
+impl Default for SentinelConfig {
+    fn default() -> Self {
+        Self {
+            ledger_path: "sentinel_ledger.log".to_string(),
+            ledger_max_bytes: 0,
+            ledger_format: LedgerFormat::default(),
+            ledger_retry_budget: DEFAULT_RETRY_BUDGET,
+            knowledge_path: "./knowledge_data/quantum_kg.json".to_string(),
+            require_knowledge_graph: false,
+            job_outcomes_path: "job_outcomes.json".to_string(),
+            metrics_addr: "127.0.0.1:9184".to_string(),
+            dry_run: false,
+            simulate_pricing: false,
+            allow_simulated_fallback: false,
+            feed_model: FeedModel::default(),
+            feed: HestonParams::default(),
+            sabr: SabrParams::default(),
+            live: LiveConfig::default(),
+            replay: ReplayConfig::default(),
+            cadence: CadenceConfig::default(),
+            option: OptionConfig::default(),
+            runtime: RuntimeConfig::default(),
+            coherence: GateModel::default(),
+            basket: BasketConfig::default(),
+        }
+    }
+}
+
+impl SentinelConfig {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let display = path.display().to_string();
+        let text = std::fs::read_to_string(path).map_err(|source| ConfigError::Io { path: display.clone(), source })?;
+        let config: Self = toml::from_str(&text).map_err(|source| ConfigError::Parse { path: display.clone(), source })?;
+        if config.option.volatility.is_none() && matches!(config.feed_model, FeedModel::Live | FeedModel::Replay) {
+            return Err(ConfigError::MissingVolatility { path: display, feed: config.feed_model });
+        }
+        Ok(config)
+    }
+
+    /// Startup loader: a missing file means defaults. A file that exists but cannot be read or
+    /// parsed is an error: running on defaults would silently drop settings such as `dry_run`.
+    pub fn load_or_default(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        if !path.exists() {
+            info!("Config: {} not found, using defaults.", path.display());
+            return Ok(Self::default());
+        }
+        let config = Self::load(path)?;
+        info!("Config: Loaded {}", path.display());
+        Ok(config)
+    }
+
+    /// Instantiates the configured market model. A live or replay feed that cannot start is an
+    /// error, since prices made up by a simulator must never pass for market data; with
+    /// `allow_simulated_fallback` the Heston simulator takes its place instead.
+    /// The Heston feed reports variance clips to `sre`.
+    pub fn price_source(&self, sre: &SentinelSRE) -> Result<Box<dyn PriceSource>, ConfigError> {
+        let heston = || Box::new(SentinelFeed::with_params(self.feed).with_sre(sre.clone()));
+        let source: Result<Box<dyn PriceSource>, ConfigError> = match self.feed_model {
+            FeedModel::Heston => return Ok(heston()),
+            FeedModel::Sabr => return Ok(Box::new(SabrFeed::new(self.sabr))),
+            FeedModel::Live => LiveFeed::connect_with_subscription(&self.live.url, self.live.subscribe.clone())
+                .map(|feed| Box::new(feed) as Box<dyn PriceSource>)
+                .map_err(ConfigError::from),
+            FeedModel::Replay => ReplayFeed::from_csv(&self.replay.path)
+                .map(|feed| Box::new(feed.paced(self.replay.paced)) as Box<dyn PriceSource>)
+                .map_err(ConfigError::from),
+        };
+        match source {
+            Err(e) if self.allow_simulated_fallback => {
+                warn!("Config: {}. Falling back to the Heston feed (allow_simulated_fallback).", e);
+                Ok(heston())
+            }
+            source => source,
+        }
+    }
+
+    /// Pause between feed ticks; live ticks are paced by the exchange, paced replays by their timestamps
+    pub fn tick_interval_ms(&self) -> u64 {
+        match self.feed_model {
+            FeedModel::Live => 0,
+            FeedModel::Replay if self.replay.paced => 0,
+            _ => self.cadence.tick_interval_ms,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_malformed_file_is_an_error_and_a_missing_one_gives_defaults() {
+        let dir = std::env::temp_dir().join(format!("sentinel-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("sentinel.toml");
+        std::fs::write(&path, "dry_run = tru\n").unwrap();
+        assert!(matches!(SentinelConfig::load_or_default(&path), Err(ConfigError::Parse { .. })));
+
+        std::fs::remove_file(&path).unwrap();
+        let config = SentinelConfig::load_or_default(&path).unwrap();
+        assert_eq!(config.ledger_path, SentinelConfig::default().ledger_path);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn a_feed_that_cannot_start_is_an_error_unless_fallback_is_allowed() {
+        let mut config = SentinelConfig { feed_model: FeedModel::Replay, ..SentinelConfig::default() };
+        config.replay.path = "/nonexistent/sentinel-replay.csv".into();
+        assert!(matches!(config.price_source(&SentinelSRE::new()), Err(ConfigError::Replay(_))));
+
+        config.allow_simulated_fallback = true;
+        assert!(config.price_source(&SentinelSRE::new()).unwrap().tick().is_some());
+    }
+
+    #[test]
+    fn an_observed_feed_needs_a_pricing_volatility() {
+        let dir = std::env::temp_dir().join(format!("sentinel-config-vol-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("sentinel.toml");
+        std::fs::write(&path, "feed_model = \"replay\"\n").unwrap();
+        assert!(matches!(SentinelConfig::load(&path), Err(ConfigError::MissingVolatility { feed: FeedModel::Replay, .. })));
+
+        std::fs::write(&path, "feed_model = \"replay\"\n[option]\nvolatility = 0.2\n").unwrap();
+        assert_eq!(SentinelConfig::load(&path).unwrap().option.volatility, Some(0.2));
+        std::fs::write(&path, "feed_model = \"sabr\"\n").unwrap();
+        assert!(SentinelConfig::load(&path).is_ok());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
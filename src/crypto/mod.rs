@@ -1,61 +1,807 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
 use fips204::ml_dsa_65; // Matches Dilithium3 security level (approx)
 use fips204::traits::{KeyGen, Signer, Verifier, SerDes};
+use sha3::{Digest, Sha3_256};
+use std::collections::{BTreeMap, VecDeque};
 use std::fs::OpenOptions;
 use std::io::Write;
+use std::path::{Path, PathBuf};
 use chrono::Utc;
-use log::{info, warn};
+use log::{error, info, warn};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use thiserror::Error;
+use crate::feed::MarketTick;
+use crate::sre::SentinelSRE;
+
+/// Current entry layouts, told apart by their leading type tag:
+/// `tx|timestamp|step|price|vol|theta|job_id|sig_hex`,
+/// `pricing|timestamp|price|vol|estimate|ci_half_width|latency_ms|job_id|sig_hex`,
+/// `aborted|timestamp|step|price|vol|reason|sig_hex` for a cycle rolled back before its job was
+/// recorded and, first in a file started by rotation, `genesis|timestamp|prev_hash|sig_hex`
+/// linking it to the rotated file. v4 had no aborted records, v3 no genesis lines; v2 was the untagged `timestamp|step|price|vol|theta|job_id|sig_hex`;
+/// v1 (no header line) was `timestamp|price|theta|job_id|sig_hex`.
+/// JSON-lines sessions carry the same records as objects (see `LedgerFormat::JsonLines`).
+pub const LEDGER_FORMAT_VERSION: u32 = 5;
+// Section header written once per session: `#sentinel-ledger v2 pk=<hex>`
+const HEADER_PREFIX: &str = "#sentinel-ledger v";
+// JSON-lines header key: `{"sentinel_ledger":5,"format":"jsonl","pk":"<hex>"}`
+const JSON_HEADER_KEY: &str = "sentinel_ledger";
+// Trailing member of a JSON-lines entry; the signature covers the object text before it
+const JSON_SIG_FIELD: &str = ",\"sig\":\"";
+const SIGNING_CTX: &[u8] = b"sentinel-ctx"; // Context string required by FIPS 204 standard
+// Rotated segments are renamed to `<ledger>.seg-<UTC timestamp>`, which sorts chronologically
+const SEGMENT_MARKER: &str = ".seg-";
+// Copies of entries the ledger file would not take land in `<ledger>.dead`
+const DEAD_LETTER_SUFFIX: &str = ".dead";
+// Signed entries held in memory while the ledger file is unwritable; past this, appends are refused
+const MAX_PENDING_ENTRIES: usize = 1024;
+/// Consecutive failed writes before buffered entries are also dead-lettered, unless configured otherwise
+pub const DEFAULT_RETRY_BUDGET: u32 = 3;
+
+#[derive(Debug, Error)]
+pub enum LedgerError {
+    #[error("ledger I/O: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("line {line}: {reason}")]
+    Malformed { line: usize, reason: String },
+    #[error("line {line}: signature does not verify")]
+    BadSignature { line: usize },
+    #[error("line {line}: signed with a key that is not trusted")]
+    UntrustedKey { line: usize },
+    #[error("{segment}: genesis does not chain from the previous segment")]
+    BrokenChain { segment: String },
+    #[error("{pending} entries already waiting for an unwritable ledger file")]
+    Backlogged { pending: usize },
+    #[error("ML-DSA: {0}")]
+    Crypto(String),
+    #[error("{field} {value:?} contains a delimiter the pipe format cannot carry")]
+    Unrepresentable { field: &'static str, value: String },
+}
+
+/// How entries are written. Both are verified by `verify_log` / `verify_file`, which tell
+/// them apart by each session's header, so sessions of either format can share a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+pub enum LedgerFormat {
+    /// `payload|sig_hex` lines under a `#sentinel-ledger` header
+    #[default]
+    #[serde(rename = "pipe")]
+    Pipe,
+    /// One JSON object per line with named fields and a trailing base64 `sig`, e.g.
+    /// `{"job_id":"j1","price":99.5,...,"type":"tx","sig":"..."}`; the header is a JSON line too,
+    /// so the whole file can be fed to standard log pipelines
+    #[serde(rename = "jsonl")]
+    JsonLines,
+}
+
+/// A verified ledger record. `step` is absent in v1 and pricing entries, `vol` in v1 entries.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LedgerEntry {
+    pub timestamp: String,
+    pub step: Option<u64>,
+    pub price: f64,
+    pub vol: Option<f64>,
+    pub job_id: String,
+    pub record: LedgerRecord,
+}
+
+/// What the entry attests to
+#[derive(Debug, Clone, PartialEq)]
+pub enum LedgerRecord {
+    /// A hedge circuit submitted with market parameter `theta`
+    Transaction { theta: f64 },
+    /// A quantum price estimate, how precise it was and how long the QPU took
+    Pricing { estimate: f64, ci_half_width: f64, latency_ms: f64 },
+    /// Compensating record for a cycle that failed before its job was committed; `job_id` is empty
+    Aborted { reason: String },
+}
 
 pub struct Ledger {
     log_file: String,
     // Holding keys in memory for this session
     sk: ml_dsa_65::PrivateKey, 
     pk: ml_dsa_65::PublicKey,
+    header_written: bool, // This session's `#sentinel-ledger` header is on disk
+    max_bytes: Option<u64>, // Rotate once the active file reaches this size
+    genesis: Option<String>, // Hash of the rotated file's last line, owed to the next file
+    format: LedgerFormat,
+    pending: VecDeque<String>, // Signed entries (with their header/genesis lines) not yet in the file, oldest first
+    max_pending: usize, // Backlog size past which appends are refused
+    dead_lettered: usize, // Leading `pending` entries already copied to the dead-letter file
+    failed_writes: u32, // Consecutive failed attempts at writing `pending`
+    retry_budget: u32,
+    dead_letter: String,
+    dead_letter_header: bool, // This session's header is in the dead-letter file
+    sre: Option<SentinelSRE>,
 }
 
 impl Ledger {
-    pub fn new(filename: &str) -> Self {
+    /// Opens a session on `filename` with a freshly generated ML-DSA key pair. Fails only if the
+    /// system RNG cannot seed key generation.
+    pub fn new(filename: &str) -> Result<Self, LedgerError> {
         // Generate Real Post-Quantum Keys
-        let (pk, sk) = ml_dsa_65::KG::try_keygen().expect("Failed to generate FIPS 204 keys");
+        let (pk, sk) = ml_dsa_65::KG::try_keygen()
+            .map_err(|e| LedgerError::Crypto(format!("key generation failed: {}", e)))?;
         
         info!("FIPS 204/ML-DSA Keys Generated.");
         // pk.clone() to avoid move, or just omit logging the bytes if too expensive. 
         // For debugging, we clone.
         info!("Public Key (First 16 bytes): {}", hex::encode(&pk.clone().into_bytes()[0..16]));
 
-        Self {
+        Ok(Self {
             log_file: filename.to_string(),
             sk,
             pk,
+            header_written: false,
+            max_bytes: None,
+            genesis: None,
+            format: LedgerFormat::Pipe,
+            pending: VecDeque::new(),
+            max_pending: MAX_PENDING_ENTRIES,
+            dead_lettered: 0,
+            failed_writes: 0,
+            retry_budget: DEFAULT_RETRY_BUDGET,
+            dead_letter: format!("{}{}", filename, DEAD_LETTER_SUFFIX),
+            dead_letter_header: false,
+            sre: None,
+        })
+    }
+
+    /// Builder: Write entries as `format` (pipe-delimited unless set)
+    pub fn with_format(mut self, format: LedgerFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Builder: Dead-letter buffered entries after `attempts` consecutive failed writes (at least 1)
+    pub fn with_retry_budget(mut self, attempts: u32) -> Self {
+        self.retry_budget = attempts.max(1);
+        self
+    }
+
+    /// Builder: Report unwritable-ledger outages and the ("ledger", "pending_entries") backlog
+    pub fn with_sre(mut self, sre: SentinelSRE) -> Self {
+        self.sre = Some(sre);
+        self
+    }
+
+    /// Builder: Rotate the log once it reaches `max_bytes` (0 = never). The full file is renamed
+    /// to `<ledger>.seg-<timestamp>` and a fresh one is started whose signed genesis line carries
+    /// the hash of the rotated file's last line, so `verify_all` can check the segments as one chain.
+    pub fn with_max_size(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = (max_bytes > 0).then_some(max_bytes);
+        self
+    }
+
+    /// Signs and appends `tx|timestamp|step|price|vol|theta|job_id`, giving auditors the market
+    /// state at decision time
+    pub fn record_transaction(&mut self, step: u64, tick: &MarketTick, theta: f64, job_id: &str) -> Result<(), LedgerError> {
+        self.append(&LedgerEntry {
+            timestamp: Utc::now().to_rfc3339(),
+            step: Some(step),
+            price: tick.price,
+            vol: Some(tick.vol),
+            job_id: job_id.to_string(),
+            record: LedgerRecord::Transaction { theta },
+        })
+    }
+
+    /// Signs and appends `pricing|timestamp|price|vol|estimate|ci_half_width|latency_ms|job_id`,
+    /// so auditors can see how precise each quantum pricing decision was
+    pub fn record_pricing(&mut self, tick: &MarketTick, estimate: f64, ci_half_width: f64, latency_ms: f64, job_id: &str) -> Result<(), LedgerError> {
+        self.append(&LedgerEntry {
+            timestamp: Utc::now().to_rfc3339(),
+            step: None,
+            price: tick.price,
+            vol: Some(tick.vol),
+            job_id: job_id.to_string(),
+            record: LedgerRecord::Pricing { estimate, ci_half_width, latency_ms },
+        })
+    }
+
+    /// Signs and appends `aborted|timestamp|step|price|vol|reason`: the cycle's decision was
+    /// rolled back, so the audit trail shows why instead of a transaction that never happened
+    pub fn record_aborted(&mut self, step: u64, tick: &MarketTick, reason: &str) -> Result<(), LedgerError> {
+        self.append(&LedgerEntry {
+            timestamp: Utc::now().to_rfc3339(),
+            step: Some(step),
+            price: tick.price,
+            vol: Some(tick.vol),
+            job_id: String::new(),
+            record: LedgerRecord::Aborted { reason: reason.to_string() },
+        })
+    }
+
+    /// Signs and persists one entry. The first record of a session (or of a freshly rotated
+    /// file) is preceded by a version header carrying the session's public key and format, so
+    /// later sessions (with fresh keys) can share one file.
+    ///
+    /// An entry the file will not take is kept, in order, behind any earlier ones and retried on
+    /// the next append or `flush`, so the file never has gaps. Once `MAX_PENDING_ENTRIES` are
+    /// waiting, new entries are refused with `Backlogged` rather than dropping buffered ones: the
+    /// caller must treat the decision as unrecorded.
+    fn append(&mut self, record: &LedgerEntry) -> Result<(), LedgerError> {
+        if self.pending.len() >= self.max_pending {
+            let _ = self.write_pending(); // The file may be writable again
+            if self.pending.len() >= self.max_pending {
+                return Err(LedgerError::Backlogged { pending: self.pending.len() });
+            }
+        }
+        // Rotating under a backlog would split it across files
+        if self.pending.is_empty() {
+            self.rotate_if_full();
+        }
+
+        let mut entry = String::new();
+        if !self.header_written {
+            entry.push_str(&self.header());
+            if let Some(prev_hash) = &self.genesis {
+                let timestamp = Utc::now().to_rfc3339();
+                entry.push_str(&match self.format {
+                    LedgerFormat::Pipe => self.signed_line(&format!("genesis|{}|{}", timestamp, prev_hash))?,
+                    LedgerFormat::JsonLines => {
+                        self.signed_json(&json!({ "type": "genesis", "timestamp": timestamp, "prev_hash": prev_hash }))?
+                    }
+                });
+            }
         }
+        entry.push_str(&match self.format {
+            LedgerFormat::Pipe => self.signed_line(&pipe_payload(record)?)?,
+            LedgerFormat::JsonLines => self.signed_json(&json_payload(record))?,
+        });
+
+        // The header and genesis travel with the entry, whichever file it ends up in
+        self.pending.push_back(entry);
+        self.header_written = true;
+        self.genesis = None;
+        let _ = self.write_pending(); // Failures are logged and the entry stays queued
+        Ok(())
     }
 
-    pub fn record_transaction(&self, price: f64, theta: f64, job_id: &str) {
-        let timestamp = Utc::now().to_rfc3339();
-        let payload = format!("{}|{}|{}|{}", timestamp, price, theta, job_id);
+    /// `#sentinel-ledger` (or JSON) header line carrying this session's key and format
+    fn header(&self) -> String {
+        let pk = hex::encode(self.pk.clone().into_bytes());
+        match self.format {
+            LedgerFormat::Pipe => format!("{}{} pk={}\n", HEADER_PREFIX, LEDGER_FORMAT_VERSION, pk),
+            LedgerFormat::JsonLines => format!("{}\n", json!({ JSON_HEADER_KEY: LEDGER_FORMAT_VERSION, "format": "jsonl", "pk": pk })),
+        }
+    }
+
+    /// Writes the backlog oldest first, dropping each entry once it is on disk. After
+    /// `retry_budget` consecutive failures the backlog is also copied to the dead-letter file so
+    /// a crash cannot lose it; it stays queued and reaches the ledger once the file is writable.
+    fn write_pending(&mut self) -> std::io::Result<()> {
+        let mut written = 0;
+        let result = OpenOptions::new().create(true).append(true).open(&self.log_file).and_then(|mut file| {
+            while let Some(entry) = self.pending.front() {
+                file.write_all(entry.as_bytes())?;
+                self.pending.pop_front();
+                self.dead_lettered = self.dead_lettered.saturating_sub(1);
+                written += 1;
+            }
+            Ok(())
+        });
+        match &result {
+            Ok(()) if self.failed_writes > 0 => {
+                info!("Ledger: {} writable again after {} failed attempts, flushed {} buffered entries.",
+                      self.log_file, self.failed_writes, written);
+                self.failed_writes = 0;
+            }
+            Ok(()) => {}
+            Err(e) => {
+                self.failed_writes += 1;
+                warn!("Ledger: Write to {} failed (attempt {}), {} entries buffered: {}",
+                      self.log_file, self.failed_writes, self.pending.len(), e);
+                if self.failed_writes == self.retry_budget {
+                    let reason = format!("{} unwritable after {} attempts: {}", self.log_file, self.failed_writes, e);
+                    error!("Ledger: {}", reason);
+                    if let Some(sre) = &self.sre {
+                        sre.report_failure("ledger", &reason);
+                    }
+                }
+                if self.failed_writes >= self.retry_budget {
+                    self.write_dead_letter();
+                }
+            }
+        }
+        if let Some(sre) = &self.sre {
+            sre.record_metric("ledger", "pending_entries", self.pending.len() as f64);
+        }
+        result
+    }
+
+    /// Appends the buffered entries not yet dead-lettered to the dead-letter file, under this
+    /// session's header so the file verifies on its own
+    fn write_dead_letter(&mut self) {
+        if self.dead_lettered == self.pending.len() {
+            return;
+        }
+        let mut text = if self.dead_letter_header { String::new() } else { self.header() };
+        self.pending.iter().skip(self.dead_lettered).for_each(|entry| text.push_str(entry));
+        let written = OpenOptions::new().create(true).append(true).open(&self.dead_letter)
+            .and_then(|mut file| file.write_all(text.as_bytes()));
+        match written {
+            Ok(()) => {
+                warn!("Ledger: {} entries dead-lettered to {}.", self.pending.len() - self.dead_lettered, self.dead_letter);
+                self.dead_lettered = self.pending.len();
+                self.dead_letter_header = true;
+            }
+            Err(e) => {
+                let reason = format!("dead-letter {} unwritable too, {} entries only in memory: {}", self.dead_letter, self.pending.len(), e);
+                error!("Ledger: {}", reason);
+                if let Some(sre) = &self.sre {
+                    sre.report_failure("ledger", &reason);
+                }
+            }
+        }
+    }
+
+    /// `payload|sig_hex\n`, self-checked before it is persisted
+    fn signed_line(&self, payload: &str) -> Result<String, LedgerError> {
+        Ok(format!("{}|{}\n", payload, hex::encode(self.sign(payload)?)))
+    }
+
+    /// The object's JSON text with a base64 `sig` member appended; the signature covers the text
+    /// as it was before the member, so verification needs no canonical re-serialization
+    fn signed_json(&self, payload: &Value) -> Result<String, LedgerError> {
+        let payload = payload.to_string();
+        let signature = BASE64.encode(self.sign(&payload)?);
+        Ok(format!("{}{}{}\"}}\n", &payload[..payload.len() - 1], JSON_SIG_FIELD, signature))
+    }
+
+    /// Signs `payload` and checks the signature before handing it out; an entry whose signature
+    /// would not verify is never written
+    fn sign(&self, payload: &str) -> Result<[u8; ml_dsa_65::SIG_LEN], LedgerError> {
         let payload_bytes = payload.as_bytes();
-        let ctx = b"sentinel-ctx"; // Context string required by FIPS 204 standard
         
         // 1. Sign (Real Math)
-        let signature = self.sk.try_sign(payload_bytes, ctx).expect("Signing failed");
+        let signature = self.sk.try_sign(payload_bytes, SIGNING_CTX)
+            .map_err(|e| LedgerError::Crypto(format!("signing failed: {}", e)))?;
         
         // 2. Verify (Immediate Correctness Check)
-        let valid = self.pk.verify(payload_bytes, &signature, ctx);
-        if !valid {
-             warn!("CRITICAL: FIPS 204 Signature Verification Failed internally!");
+        if !self.pk.verify(payload_bytes, &signature, SIGNING_CTX) {
+            return Err(LedgerError::Crypto("signature failed its own verification".to_string()));
         }
 
         // Signature is an array [u8; N], not a struct with into_bytes() in some versions, 
         // or it implements generic trait. fips204 0.4.6 Signature is likely a byte array or has to_vec.
         // The error said `into_bytes` not found for array `[u8; 3309]`. So it returned an array directly.
-        let sig_hex = hex::encode(signature); 
+        Ok(signature)
+    }
+
+    /// Renames a full log to its segment name and owes the next file a genesis link
+    fn rotate_if_full(&mut self) {
+        let Some(max_bytes) = self.max_bytes else { return };
+        let size = std::fs::metadata(&self.log_file).map(|m| m.len()).unwrap_or(0);
+        if size < max_bytes {
+            return;
+        }
+        let prev_hash = match std::fs::read_to_string(&self.log_file) {
+            Ok(text) => last_line_hash(&text),
+            Err(e) => {
+                warn!("Ledger: Cannot read {} for rotation: {}", self.log_file, e);
+                return;
+            }
+        };
+        let segment = format!("{}{}{}", self.log_file, SEGMENT_MARKER, Utc::now().format("%Y%m%dT%H%M%S%.6fZ"));
+        match std::fs::rename(&self.log_file, &segment) {
+            Ok(()) => {
+                info!("Ledger: Rotated {} ({} bytes) to {}", self.log_file, size, segment);
+                self.header_written = false;
+                self.genesis = prev_hash;
+            }
+            Err(e) => warn!("Ledger: Rotation of {} failed, still appending: {}", self.log_file, e),
+        }
+    }
+
+    /// Re-verifies every signature in this ledger's file and returns the parsed entries.
+    /// Each `#sentinel-ledger vN pk=...` header selects the layout and key for the lines after it;
+    /// headerless lines are legacy v1 entries, checked against this session's key.
+    pub fn verify_log(&self) -> Result<Vec<LedgerEntry>, LedgerError> {
+        verify_entries(&self.log_file, Some(&self.pk), &[])
+    }
 
-        // 3. Persist
-        let entry = format!("{}|{}\n", payload, sig_hex);
+    /// Verifies every rotated segment in `dir` plus its active file, oldest first, checking that
+    /// each file's genesis line chains from the last line of the segment before it. Returns one
+    /// report per file of every rotated ledger found in `dir`, in chain order; a failed file does
+    /// not stop the audit of the ones after it. Keys are handled as in `verify_file`.
+    pub fn verify_all(dir: impl AsRef<Path>, trusted: &[ml_dsa_65::PublicKey]) -> Result<Vec<SegmentReport>, LedgerError> {
+        let mut chains: BTreeMap<PathBuf, Vec<PathBuf>> = BTreeMap::new(); // Active path -> its segments
+        for dir_entry in std::fs::read_dir(dir)? {
+            let path = dir_entry?.path();
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            if let Some((active, _)) = name.split_once(SEGMENT_MARKER) {
+                chains.entry(path.with_file_name(active)).or_default().push(path.clone());
+            }
+        }
 
-        if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(&self.log_file) {
-            if let Err(e) = file.write_all(entry.as_bytes()) {
-                eprintln!("Failed to write to ledger: {}", e);
+        let mut reports = Vec::new();
+        for (active, mut files) in chains {
+            files.sort();
+            if active.exists() {
+                files.push(active);
+            }
+            let mut prev_hash: Option<String> = None;
+            for path in files {
+                let path = path.to_string_lossy().into_owned();
+                let result = match verify_segment(&path, None, trusted) {
+                    // The oldest surviving segment may itself be a continuation whose predecessor was pruned
+                    Ok(segment) if prev_hash.is_some() && segment.genesis != prev_hash => {
+                        prev_hash = segment.tail_hash;
+                        Err(LedgerError::BrokenChain { segment: path.clone() })
+                    }
+                    Ok(segment) => {
+                        prev_hash = segment.tail_hash;
+                        Ok(segment.entries)
+                    }
+                    Err(e) => {
+                        // Still link the next file to this one as it stands on disk
+                        prev_hash = std::fs::read_to_string(&path).ok().and_then(|text| last_line_hash(&text));
+                        Err(e)
+                    }
+                };
+                reports.push(SegmentReport { path, result });
             }
         }
+        Ok(reports)
+    }
+
+    /// Writes any buffered entries and forces the file to disk; call before exiting so no signed
+    /// record is lost. Entries that still cannot be written are dead-lettered regardless of the
+    /// retry budget, and the write error returned.
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        if let Err(e) = self.write_pending() {
+            self.write_dead_letter();
+            return Err(e);
+        }
+        match OpenOptions::new().append(true).open(&self.log_file) {
+            Ok(file) => file.sync_all(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()), // Nothing recorded yet
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Signed entries waiting for the ledger file to become writable
+    #[allow(dead_code)]
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+/// Offline audit of a ledger file against pinned keys (hex, as in the `pk=` headers). Every
+/// header's key must be one of `trusted`, and headerless v1 lines must verify under one of them.
+/// With no trusted keys the header keys are taken at face value: this proves the file is
+/// internally consistent, not who wrote it.
+pub fn verify_file(path: &str, trusted: &[ml_dsa_65::PublicKey]) -> Result<Vec<LedgerEntry>, LedgerError> {
+    verify_entries(path, None, trusted)
+}
+
+/// Audit outcome of one file of a rotation chain: its verified entries, or why it failed
+#[derive(Debug)]
+pub struct SegmentReport {
+    pub path: String,
+    pub result: Result<Vec<LedgerEntry>, LedgerError>,
+}
+
+/// One verified ledger file and its links to its neighbours in a rotation chain
+struct Segment {
+    entries: Vec<LedgerEntry>,
+    genesis: Option<String>,   // Hash of the previous segment's last line, when this file continues one
+    tail_hash: Option<String>, // Hash of this file's last line, which the next segment must carry
+}
+
+/// SHA3-256 (hex) of the last non-empty line: the signed entry a following segment chains from
+fn last_line_hash(text: &str) -> Option<String> {
+    let last = text.lines().rev().find(|l| !l.trim().is_empty())?;
+    Some(hex::encode(Sha3_256::digest(last.as_bytes())))
+}
+
+/// Decodes a hex-encoded ML-DSA-65 public key
+pub fn parse_public_key(hex_key: &str) -> Result<ml_dsa_65::PublicKey, String> {
+    let bytes: [u8; ml_dsa_65::PK_LEN] = hex::decode(hex_key.trim()).ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| "bad public key".to_string())?;
+    ml_dsa_65::PublicKey::try_from_bytes(bytes).map_err(|e| e.to_string())
+}
+
+/// `legacy_pk` verifies headerless v1 lines when no trusted key is pinned
+fn verify_entries(path: &str, legacy_pk: Option<&ml_dsa_65::PublicKey>, trusted: &[ml_dsa_65::PublicKey]) -> Result<Vec<LedgerEntry>, LedgerError> {
+    verify_segment(path, legacy_pk, trusted).map(|segment| segment.entries)
+}
+
+fn verify_segment(path: &str, legacy_pk: Option<&ml_dsa_65::PublicKey>, trusted: &[ml_dsa_65::PublicKey]) -> Result<Segment, LedgerError> {
+    let text = std::fs::read_to_string(path)?;
+    let trusted_bytes: Vec<_> = trusted.iter().map(|pk| pk.clone().into_bytes()).collect();
+    let mut version = 1;
+    let mut format = LedgerFormat::Pipe;
+    let mut keys: Vec<ml_dsa_65::PublicKey> = match legacy_pk {
+        Some(pk) if trusted.is_empty() => vec![pk.clone()],
+        _ => trusted.to_vec(),
+    };
+    let mut entries = Vec::new();
+    let mut genesis = None;
+
+    for (idx, line) in text.lines().enumerate() {
+        let line_no = idx + 1;
+        let malformed = |reason: &str| LedgerError::Malformed { line: line_no, reason: reason.to_string() };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let json_line = if line.starts_with('{') {
+            Some(serde_json::from_str::<Value>(line).map_err(|e| malformed(&e.to_string()))?)
+        } else {
+            None
+        };
+        let header = match &json_line {
+            Some(object) if object.get(JSON_HEADER_KEY).is_some() => {
+                let v = object[JSON_HEADER_KEY].as_u64().ok_or_else(|| malformed("unreadable format version"))?;
+                let key = object["pk"].as_str().ok_or_else(|| malformed("header without public key"))?;
+                Some((v as u32, key.to_string(), LedgerFormat::JsonLines))
+            }
+            Some(_) => None,
+            None => match line.strip_prefix(HEADER_PREFIX) {
+                Some(header) => {
+                    let (v, key) = header.split_once(" pk=").ok_or_else(|| malformed("header without public key"))?;
+                    let v = v.parse().map_err(|_| malformed("unreadable format version"))?;
+                    Some((v, key.to_string(), LedgerFormat::Pipe))
+                }
+                None => None,
+            },
+        };
+        if let Some((v, key, header_format)) = header {
+            version = v;
+            format = header_format;
+            if version > LEDGER_FORMAT_VERSION {
+                return Err(malformed(&format!("unsupported format version {}", version)));
+            }
+            let pk = parse_public_key(&key).map_err(|e| malformed(&e))?;
+            if !trusted.is_empty() && !trusted_bytes.contains(&pk.clone().into_bytes()) {
+                return Err(LedgerError::UntrustedKey { line: line_no });
+            }
+            keys = vec![pk];
+            continue;
+        }
+
+        let (payload, signature) = match (format, &json_line) {
+            (LedgerFormat::JsonLines, Some(object)) => {
+                let sig_at = line.rfind(JSON_SIG_FIELD).ok_or_else(|| malformed("missing signature"))?;
+                let sig = object["sig"].as_str().ok_or_else(|| malformed("missing signature"))?;
+                if line[sig_at + JSON_SIG_FIELD.len()..] != format!("{}\"}}", sig) {
+                    return Err(malformed("signature is not the last member"));
+                }
+                (format!("{}}}", &line[..sig_at]), BASE64.decode(sig).ok())
+            }
+            (LedgerFormat::Pipe, None) => {
+                let (payload, sig_hex) = line.rsplit_once('|').ok_or_else(|| malformed("missing signature"))?;
+                (payload.to_string(), hex::decode(sig_hex).ok())
+            }
+            _ => return Err(malformed("entry does not match the header's format")),
+        };
+        let signature: [u8; ml_dsa_65::SIG_LEN] = signature
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| malformed("bad signature encoding"))?;
+        if keys.is_empty() {
+            return Err(malformed("legacy entry but no key to check it against"));
+        }
+        if !keys.iter().any(|pk| pk.verify(payload.as_bytes(), &signature, SIGNING_CTX)) {
+            return Err(LedgerError::BadSignature { line: line_no });
+        }
+        if let Some(object) = &json_line {
+            if object["type"] == "genesis" {
+                if !entries.is_empty() || genesis.is_some() {
+                    return Err(malformed("genesis after the first entry"));
+                }
+                let prev_hash = object["prev_hash"].as_str().ok_or_else(|| malformed("genesis without previous hash"))?;
+                genesis = Some(prev_hash.to_string());
+            } else {
+                entries.push(parse_json_payload(object).ok_or_else(|| malformed("unexpected field layout"))?);
+            }
+            continue;
+        }
+        if let Some(link) = payload.strip_prefix("genesis|") {
+            if !entries.is_empty() || genesis.is_some() {
+                return Err(malformed("genesis after the first entry"));
+            }
+            let (_timestamp, prev_hash) = link.split_once('|').ok_or_else(|| malformed("genesis without previous hash"))?;
+            genesis = Some(prev_hash.to_string());
+            continue;
+        }
+        entries.push(parse_payload(&payload, version).ok_or_else(|| malformed("unexpected field layout"))?);
+    }
+    info!("Ledger: {} entries verified in {}", entries.len(), path);
+    Ok(Segment { entries, genesis, tail_hash: last_line_hash(&text) })
+}
+
+/// Current pipe layout of `entry` (see `LEDGER_FORMAT_VERSION`); delimiters in a reason are blanked.
+/// A job id with a delimiter is refused: it would shift the fields after it, so two different
+/// entries could share one signed payload.
+fn pipe_payload(entry: &LedgerEntry) -> Result<String, LedgerError> {
+    if entry.job_id.contains(['|', '\n', '\r']) {
+        return Err(LedgerError::Unrepresentable { field: "job_id", value: entry.job_id.clone() });
+    }
+    let step = entry.step.unwrap_or_default();
+    let vol = entry.vol.unwrap_or_default();
+    Ok(match &entry.record {
+        LedgerRecord::Transaction { theta } => {
+            format!("tx|{}|{}|{}|{}|{}|{}", entry.timestamp, step, entry.price, vol, theta, entry.job_id)
+        }
+        LedgerRecord::Pricing { estimate, ci_half_width, latency_ms } => format!("pricing|{}|{}|{}|{}|{}|{}|{}",
+            entry.timestamp, entry.price, vol, estimate, ci_half_width, latency_ms, entry.job_id),
+        LedgerRecord::Aborted { reason } => format!("aborted|{}|{}|{}|{}|{}",
+            entry.timestamp, step, entry.price, vol, reason.replace(['|', '\n', '\r'], " ")),
+    })
+}
+
+/// `entry` as a JSON-lines object: the pipe layout's fields by name, with the tag under "type"
+fn json_payload(entry: &LedgerEntry) -> Value {
+    match &entry.record {
+        LedgerRecord::Transaction { theta } => json!({
+            "type": "tx", "timestamp": entry.timestamp, "step": entry.step, "price": entry.price,
+            "vol": entry.vol, "theta": theta, "job_id": entry.job_id,
+        }),
+        LedgerRecord::Pricing { estimate, ci_half_width, latency_ms } => json!({
+            "type": "pricing", "timestamp": entry.timestamp, "price": entry.price, "vol": entry.vol,
+            "estimate": estimate, "ci_half_width": ci_half_width, "latency_ms": latency_ms, "job_id": entry.job_id,
+        }),
+        LedgerRecord::Aborted { reason } => json!({
+            "type": "aborted", "timestamp": entry.timestamp, "step": entry.step, "price": entry.price,
+            "vol": entry.vol, "reason": reason,
+        }),
+    }
+}
+
+fn parse_json_payload(object: &Value) -> Option<LedgerEntry> {
+    let number = |key: &str| object[key].as_f64();
+    let record = match object["type"].as_str()? {
+        "tx" => LedgerRecord::Transaction { theta: number("theta")? },
+        "pricing" => LedgerRecord::Pricing {
+            estimate: number("estimate")?,
+            ci_half_width: number("ci_half_width")?,
+            latency_ms: number("latency_ms")?,
+        },
+        "aborted" => LedgerRecord::Aborted { reason: object["reason"].as_str()?.to_string() },
+        _ => return None,
+    };
+    Some(LedgerEntry {
+        timestamp: object["timestamp"].as_str()?.to_string(),
+        step: object["step"].as_u64(),
+        price: number("price")?,
+        vol: number("vol"),
+        job_id: object["job_id"].as_str().unwrap_or_default().to_string(),
+        record,
+    })
+}
+
+fn parse_payload(payload: &str, version: u32) -> Option<LedgerEntry> {
+    let fields: Vec<&str> = payload.split('|').collect();
+    match (version, fields.as_slice()) {
+        (1, [timestamp, price, theta, job_id]) => Some(LedgerEntry {
+            timestamp: timestamp.to_string(),
+            step: None,
+            price: price.parse().ok()?,
+            vol: None,
+            job_id: job_id.to_string(),
+            record: LedgerRecord::Transaction { theta: theta.parse().ok()? },
+        }),
+        (2, [timestamp, step, price, vol, theta, job_id]) | (3.., ["tx", timestamp, step, price, vol, theta, job_id]) => Some(LedgerEntry {
+            timestamp: timestamp.to_string(),
+            step: Some(step.parse().ok()?),
+            price: price.parse().ok()?,
+            vol: Some(vol.parse().ok()?),
+            job_id: job_id.to_string(),
+            record: LedgerRecord::Transaction { theta: theta.parse().ok()? },
+        }),
+        (5.., ["aborted", timestamp, step, price, vol, reason]) => Some(LedgerEntry {
+            timestamp: timestamp.to_string(),
+            step: Some(step.parse().ok()?),
+            price: price.parse().ok()?,
+            vol: Some(vol.parse().ok()?),
+            job_id: String::new(),
+            record: LedgerRecord::Aborted { reason: reason.to_string() },
+        }),
+        (3.., ["pricing", timestamp, price, vol, estimate, ci_half_width, latency_ms, job_id]) => Some(LedgerEntry {
+            timestamp: timestamp.to_string(),
+            step: None,
+            price: price.parse().ok()?,
+            vol: Some(vol.parse().ok()?),
+            job_id: job_id.to_string(),
+            record: LedgerRecord::Pricing {
+                estimate: estimate.parse().ok()?,
+                ci_half_width: ci_half_width.parse().ok()?,
+                latency_ms: latency_ms.parse().ok()?,
+            },
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fresh scratch directory for one test's ledger files
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("sentinel-ledger-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn tick() -> MarketTick {
+        MarketTick { price: 100.0, vol: 0.2, ts: 0.0 }
+    }
+
+    #[test]
+    fn a_job_id_with_a_delimiter_is_refused_in_pipe_format_only() {
+        let dir = scratch_dir("delimiter");
+        let path = dir.join("ledger.log").to_string_lossy().into_owned();
+        let mut ledger = Ledger::new(&path).unwrap();
+        let forged = "job-1|0.5";
+        assert!(matches!(ledger.record_transaction(1, &tick(), 0.1, forged), Err(LedgerError::Unrepresentable { .. })));
+        assert!(ledger.verify_log().is_err(), "nothing should have been written");
+
+        let path = dir.join("ledger.jsonl").to_string_lossy().into_owned();
+        let mut ledger = Ledger::new(&path).unwrap().with_format(LedgerFormat::JsonLines);
+        ledger.record_transaction(1, &tick(), 0.1, forged).unwrap();
+        assert_eq!(ledger.verify_log().unwrap()[0].job_id, forged);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn a_full_backlog_refuses_entries_until_the_file_is_writable() {
+        let dir = scratch_dir("backlog");
+        let missing = dir.join("not-yet");
+        let path = missing.join("ledger.log").to_string_lossy().into_owned();
+        let mut ledger = Ledger::new(&path).unwrap();
+        ledger.max_pending = 2;
+        ledger.record_transaction(1, &tick(), 0.1, "j1").unwrap();
+        ledger.record_transaction(2, &tick(), 0.1, "j2").unwrap();
+        assert!(matches!(ledger.record_transaction(3, &tick(), 0.1, "j3"), Err(LedgerError::Backlogged { pending: 2 })));
+
+        std::fs::create_dir_all(&missing).unwrap();
+        ledger.record_transaction(4, &tick(), 0.1, "j4").unwrap();
+        assert_eq!(ledger.pending(), 0);
+        let jobs: Vec<String> = ledger.verify_log().unwrap().into_iter().map(|e| e.job_id).collect();
+        assert_eq!(jobs, ["j1", "j2", "j4"]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn an_unwritable_file_dead_letters_after_the_retry_budget() {
+        let dir = scratch_dir("dead-letter");
+        // A directory where the ledger file should be: every write fails, its `.dead` sibling works
+        let path = dir.join("ledger.log");
+        std::fs::create_dir(&path).unwrap();
+        let path = path.to_string_lossy().into_owned();
+        let mut ledger = Ledger::new(&path).unwrap().with_retry_budget(2);
+        ledger.record_transaction(1, &tick(), 0.1, "j1").unwrap();
+        let dead_letter = format!("{}{}", path, DEAD_LETTER_SUFFIX);
+        assert!(!Path::new(&dead_letter).exists(), "one failure is within the budget");
+
+        ledger.record_transaction(2, &tick(), 0.1, "j2").unwrap();
+        let jobs: Vec<String> = verify_file(&dead_letter, &[]).unwrap().into_iter().map(|e| e.job_id).collect();
+        assert_eq!(jobs, ["j1", "j2"]);
+        assert_eq!(ledger.pending(), 2, "dead-lettered entries stay queued for the ledger");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn rotated_segments_verify_as_one_chain() {
+        let dir = scratch_dir("rotation");
+        let path = dir.join("ledger.log").to_string_lossy().into_owned();
+        let mut ledger = Ledger::new(&path).unwrap().with_max_size(1); // Every entry starts a new file
+        for step in 1..=3 {
+            ledger.record_transaction(step, &tick(), 0.1, &format!("j{}", step)).unwrap();
+        }
+        let reports = Ledger::verify_all(&dir, &[]).unwrap();
+        assert_eq!(reports.len(), 3);
+        let jobs: Vec<String> = reports
+            .into_iter()
+            .flat_map(|report| report.result.unwrap())
+            .map(|e| e.job_id)
+            .collect();
+        assert_eq!(jobs, ["j1", "j2", "j3"]);
+        std::fs::remove_dir_all(dir).unwrap();
     }
 }
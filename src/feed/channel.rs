@@ -0,0 +1,210 @@
+use crate::sre::SentinelSRE;
+use log::warn;
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+/// Ticks buffered between the feed task and the main loop unless configured otherwise
+pub const DEFAULT_TICK_BUFFER: usize = 32;
+
+/// What the feed does when the main loop has fallen `capacity` ticks behind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverflowPolicy {
+    /// Wait for the consumer; the feed is paced by the slowest cycle
+    Block,
+    /// Evict the oldest buffered tick, so the consumer always resumes on the freshest prices
+    #[default]
+    DropOldest,
+    /// Discard the incoming tick and keep the backlog
+    DropNewest,
+}
+
+struct Shared<T> {
+    queue: Mutex<VecDeque<T>>,
+    capacity: usize,
+    policy: OverflowPolicy,
+    not_empty: Notify,
+    not_full: Notify,
+    sender_closed: AtomicBool,
+    receiver_closed: AtomicBool,
+    dropped: AtomicU64,
+}
+
+/// Bounded single-producer, single-consumer channel whose overflow behaviour is an
+/// `OverflowPolicy`. Dropped items are counted and, with an SRE attached, reported as
+/// ("feed", "dropped_ticks").
+pub fn tick_channel<T>(capacity: usize, policy: OverflowPolicy) -> (TickSender<T>, TickReceiver<T>) {
+    let capacity = capacity.max(1);
+    let shared = Arc::new(Shared {
+        queue: Mutex::new(VecDeque::with_capacity(capacity)),
+        capacity,
+        policy,
+        not_empty: Notify::new(),
+        not_full: Notify::new(),
+        sender_closed: AtomicBool::new(false),
+        receiver_closed: AtomicBool::new(false),
+        dropped: AtomicU64::new(0),
+    });
+    (TickSender { shared: shared.clone(), sre: None }, TickReceiver { shared })
+}
+
+pub struct TickSender<T> {
+    shared: Arc<Shared<T>>,
+    sre: Option<SentinelSRE>,
+}
+
+impl<T> TickSender<T> {
+    /// Builder: Report the dropped-tick count as a ("feed", "dropped_ticks") metric
+    pub fn with_sre(mut self, sre: SentinelSRE) -> Self {
+        self.sre = Some(sre);
+        self
+    }
+
+    /// Queues `item` under the channel's policy. Only `Block` ever waits. Errors with the item
+    /// once the receiver is gone.
+    pub async fn send(&self, item: T) -> Result<(), T> {
+        let mut item = Some(item);
+        loop {
+            if self.shared.receiver_closed.load(Ordering::Acquire) {
+                return Err(item.take().unwrap());
+            }
+            {
+                let mut queue = self.shared.queue.lock().unwrap();
+                if queue.len() < self.shared.capacity {
+                    queue.push_back(item.take().unwrap());
+                    self.shared.not_empty.notify_one();
+                    return Ok(());
+                }
+                match self.shared.policy {
+                    OverflowPolicy::Block => {}
+                    OverflowPolicy::DropOldest => {
+                        queue.pop_front();
+                        queue.push_back(item.take().unwrap());
+                        drop(queue);
+                        self.record_drop();
+                        return Ok(());
+                    }
+                    OverflowPolicy::DropNewest => {
+                        drop(queue);
+                        self.record_drop();
+                        return Ok(());
+                    }
+                }
+            }
+            self.shared.not_full.notified().await;
+        }
+    }
+
+    /// Items discarded so far because the buffer was full
+    #[allow(dead_code)]
+    pub fn dropped(&self) -> u64 {
+        self.shared.dropped.load(Ordering::Relaxed)
+    }
+
+    fn record_drop(&self) {
+        let dropped = self.shared.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+        if dropped == 1 || dropped.is_power_of_two() {
+            warn!("Feed: Consumer is {} ticks behind, {} dropped so far ({:?}).",
+                  self.shared.capacity, dropped, self.shared.policy);
+        }
+        if let Some(sre) = &self.sre {
+            sre.record_metric("feed", "dropped_ticks", dropped as f64);
+        }
+    }
+}
+
+impl<T> Drop for TickSender<T> {
+    fn drop(&mut self) {
+        self.shared.sender_closed.store(true, Ordering::Release);
+        self.shared.not_empty.notify_one();
+    }
+}
+
+pub struct TickReceiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> TickReceiver<T> {
+    /// Next buffered item, oldest first; None once the sender is gone and the buffer drained
+    pub async fn recv(&mut self) -> Option<T> {
+        loop {
+            if let Some(item) = self.shared.queue.lock().unwrap().pop_front() {
+                self.shared.not_full.notify_one();
+                return Some(item);
+            }
+            if self.shared.sender_closed.load(Ordering::Acquire) {
+                // The sender may have pushed its last item just before closing
+                return self.shared.queue.lock().unwrap().pop_front();
+            }
+            self.shared.not_empty.notified().await;
+        }
+    }
+
+    /// Items discarded so far because the buffer was full
+    #[allow(dead_code)]
+    pub fn dropped(&self) -> u64 {
+        self.shared.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl<T> Drop for TickReceiver<T> {
+    fn drop(&mut self) {
+        self.shared.receiver_closed.store(true, Ordering::Release);
+        self.shared.not_full.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// Sends 1..=5 into a 3-slot channel nobody is reading, then drains what was kept
+    async fn stalled(policy: OverflowPolicy) -> (Vec<u32>, u64) {
+        let (tx, mut rx) = tick_channel(3, policy);
+        for tick in 1..=5 {
+            tx.send(tick).await.unwrap();
+        }
+        let dropped = tx.dropped();
+        drop(tx);
+        let mut kept = Vec::new();
+        while let Some(tick) = rx.recv().await {
+            kept.push(tick);
+        }
+        (kept, dropped)
+    }
+
+    #[tokio::test]
+    async fn drop_oldest_keeps_the_freshest_ticks() {
+        assert_eq!(stalled(OverflowPolicy::DropOldest).await, (vec![3, 4, 5], 2));
+    }
+
+    #[tokio::test]
+    async fn drop_newest_keeps_the_backlog() {
+        assert_eq!(stalled(OverflowPolicy::DropNewest).await, (vec![1, 2, 3], 2));
+    }
+
+    #[tokio::test]
+    async fn block_waits_for_the_consumer() {
+        let (tx, mut rx) = tick_channel(3, OverflowPolicy::Block);
+        for tick in 1..=3 {
+            tx.send(tick).await.unwrap();
+        }
+        assert!(tokio::time::timeout(Duration::from_millis(50), tx.send(4)).await.is_err(), "a full channel accepted a tick");
+
+        let send = tokio::spawn(async move {
+            tx.send(4).await.unwrap();
+            tx.dropped()
+        });
+        assert_eq!(rx.recv().await, Some(1));
+        assert_eq!(send.await.unwrap(), 0);
+        let mut kept = Vec::new();
+        while let Some(tick) = rx.recv().await {
+            kept.push(tick);
+        }
+        assert_eq!(kept, vec![2, 3, 4]);
+    }
+}
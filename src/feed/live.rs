@@ -0,0 +1,125 @@
+use super::{MarketTick, PriceSource, RealizedVol};
+use futures_util::{SinkExt, StreamExt};
+use log::{debug, info, warn};
+use simd_json::prelude::*;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tokio::sync::mpsc;
+use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+use url::Url;
+
+const BUFFER_TICKS: usize = 1024;
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+// Fields commonly carrying the last trade price (Binance trades use "p", as a string)
+const PRICE_FIELDS: [&str; 4] = ["price", "p", "last", "c"];
+
+#[derive(Debug, Error)]
+pub enum LiveFeedError {
+    #[error("invalid feed URL '{0}': {1}")]
+    InvalidUrl(String, url::ParseError),
+    #[error("feed URL must use ws:// or wss://, got '{0}'")]
+    UnsupportedScheme(String),
+    #[error("LiveFeed::connect needs a running tokio runtime")]
+    NoRuntime,
+}
+
+/// Exchange WebSocket feed. A background task keeps the socket open (reconnecting with exponential
+/// backoff), parses trade messages and buffers ticks; `tick()` hands them out in arrival order.
+///
+/// `vol` in each tick is the realized volatility (EWMA of log returns, annualized by the arrival
+/// spacing), since trade messages carry no volatility of their own.
+pub struct LiveFeed {
+    rx: mpsc::Receiver<MarketTick>,
+}
+
+impl LiveFeed {
+    #[allow(dead_code)]
+    pub fn connect(url: &str) -> Result<Self, LiveFeedError> {
+        Self::connect_with_subscription(url, None)
+    }
+
+    /// `subscription` is sent verbatim after every (re)connect, e.g.
+    /// `{"method":"SUBSCRIBE","params":["btcusdt@trade"],"id":1}`
+    pub fn connect_with_subscription(url: &str, subscription: Option<String>) -> Result<Self, LiveFeedError> {
+        let parsed = Url::parse(url).map_err(|e| LiveFeedError::InvalidUrl(url.to_string(), e))?;
+        if !matches!(parsed.scheme(), "ws" | "wss") {
+            return Err(LiveFeedError::UnsupportedScheme(url.to_string()));
+        }
+        let handle = tokio::runtime::Handle::try_current().map_err(|_| LiveFeedError::NoRuntime)?;
+
+        let (tx, rx) = mpsc::channel(BUFFER_TICKS);
+        handle.spawn(run(parsed, subscription, tx));
+        Ok(Self { rx })
+    }
+}
+
+impl PriceSource for LiveFeed {
+    /// Blocks until the next trade arrives; None once the feed task has stopped. Must be called
+    /// off the async workers (e.g. from `spawn_blocking`), as the hypervisor's feed loop does.
+    fn tick(&mut self) -> Option<MarketTick> {
+        self.rx.blocking_recv()
+    }
+}
+
+/// Connection loop: connect, subscribe, stream, and on any disconnect back off and retry.
+/// Exits only when the consumer drops the LiveFeed.
+async fn run(url: Url, subscription: Option<String>, tx: mpsc::Sender<MarketTick>) {
+    let mut delay = Duration::from_millis(500);
+    let mut vol = RealizedVol::default();
+    let started = Instant::now(); // Tick timestamps are arrival times relative to this
+
+    while !tx.is_closed() {
+        match connect_async(url.as_str()).await {
+            Ok((mut socket, _)) => {
+                info!("LiveFeed: Connected to {}", url);
+                delay = Duration::from_millis(500);
+                if let Some(msg) = &subscription {
+                    if let Err(e) = socket.send(Message::Text(msg.clone())).await {
+                        warn!("LiveFeed: Subscription failed: {}", e);
+                        continue;
+                    }
+                }
+
+                while let Some(frame) = socket.next().await {
+                    let text = match frame {
+                        Ok(Message::Text(text)) => text,
+                        Ok(Message::Binary(bytes)) => String::from_utf8_lossy(&bytes).into_owned(),
+                        Ok(Message::Close(_)) => break,
+                        Ok(_) => continue, // Ping/Pong are answered by tungstenite
+                        Err(e) => {
+                            warn!("LiveFeed: Stream error: {}", e);
+                            break;
+                        }
+                    };
+                    let Some(price) = parse_price(text) else {
+                        debug!("LiveFeed: Ignoring non-trade message");
+                        continue;
+                    };
+                    let ts = started.elapsed().as_secs_f64();
+                    let tick = MarketTick { price, vol: vol.update(price, ts), ts };
+                    if tx.send(tick).await.is_err() {
+                        return; // Consumer gone
+                    }
+                }
+                warn!("LiveFeed: Disconnected from {}", url);
+            }
+            Err(e) => warn!("LiveFeed: Connect to {} failed: {}", url, e),
+        }
+
+        info!("LiveFeed: Reconnecting in {:?}", delay);
+        tokio::time::sleep(delay).await;
+        delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+    }
+}
+
+/// Pulls a positive price out of a trade message; numbers and numeric strings are both accepted
+fn parse_price(text: String) -> Option<f64> {
+    let mut bytes = text.into_bytes();
+    let value = simd_json::to_borrowed_value(&mut bytes).ok()?;
+    // Some venues wrap the payload: {"stream": ..., "data": {...}}
+    let payload = value.get("data").unwrap_or(&value);
+    PRICE_FIELDS.iter()
+        .filter_map(|field| payload.get(*field))
+        .find_map(|v| v.cast_f64().or_else(|| v.as_str().and_then(|s| s.parse().ok())))
+        .filter(|p: &f64| p.is_finite() && *p > 0.0)
+}
@@ -1,30 +1,108 @@
-use futures_util::{StreamExt, SinkExt};
-use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
-use url::Url;
+use crate::sre::SentinelSRE;
+use log::{debug, warn};
 use serde::Deserialize;
-use simd_json;
-use std::env;
-use log::{info, error, warn, debug};
-use rand_distr::{Distribution, Normal};
-use rand::thread_rng;
 
-// HESTON MODEL IMPLEMENTATION
-pub struct SentinelFeed {
-    // Heston Model Parameters
-    s0: f64,      // Initial Price
-    v0: f64,      // Initial Volatility
-    kappa: f64,   // Mean reversion speed
-    theta: f64,   // Long run variance
-    xi: f64,      // Vol of Vol
-    rho: f64,     // Correlation
-    dt: f64,      // Time step
-    
-    current_price: f64,
-    current_vol: f64,
+pub mod channel;
+pub mod greeks;
+pub mod live;
+pub mod multi_asset;
+pub mod replay;
+pub mod sabr;
+pub mod sampler;
+pub mod stats;
+
+pub use channel::{tick_channel, OverflowPolicy, TickReceiver, DEFAULT_TICK_BUFFER};
+pub use sabr::{SabrFeed, SabrParams};
+pub use sampler::{NormalSource, PseudoNormals, SobolNormals};
+pub use stats::FeedStats;
+
+/// Seconds per model year on the trading calendar, so `dt = 1/252` advances the clock one day
+pub const SECONDS_PER_YEAR: f64 = 252.0 * 86_400.0;
+
+/// One observation of the simulated market: spot price and instantaneous volatility sqrt(v_t).
+/// `ts` is the source's own clock in seconds since its start: model time (`dt` per step) for the
+/// simulators, wall-clock arrival for live data, the recorded timestamps for replays.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarketTick {
+    pub price: f64,
+    pub vol: f64,
+    pub ts: f64,
 }
 
-impl SentinelFeed {
-    pub fn new() -> Self {
+/// EWMA estimate of annualized log-return volatility, for sources that only observe prices. Each
+/// squared return is scaled by the observed spacing of its ticks (`ts`, seconds), so the reading is
+/// comparable with the simulators' sqrt(v_t) whatever the feed's cadence.
+#[derive(Debug, Default)]
+struct RealizedVol {
+    last: Option<(f64, f64)>, // (ts, price) of the previous tick
+    variance_rate: Option<f64>, // EWMA of squared log returns per second of spacing
+}
+
+impl RealizedVol {
+    // RiskMetrics decay for the EWMA of squared log returns
+    const LAMBDA: f64 = 0.94;
+
+    fn update(&mut self, price: f64, ts: f64) -> f64 {
+        match self.last {
+            // Ticks sharing a timestamp say nothing about the rate: fold them into the next interval
+            Some((last_ts, _)) if ts <= last_ts => {}
+            Some((last_ts, last_price)) => {
+                let r = (price / last_price).ln();
+                let sample = r * r / (ts - last_ts);
+                self.variance_rate = Some(match self.variance_rate {
+                    Some(rate) => Self::LAMBDA * rate + (1.0 - Self::LAMBDA) * sample,
+                    None => sample, // Seed with the first return rather than decaying up from zero
+                });
+                self.last = Some((ts, price));
+            }
+            None => self.last = Some((ts, price)),
+        }
+        (self.variance_rate.unwrap_or(0.0) * SECONDS_PER_YEAR).sqrt()
+    }
+}
+
+/// Any market model or data source the hypervisor can consume tick by tick.
+/// `None` means the source is exhausted (simulated models never are). `tick` may block until the
+/// next tick is due (live sockets, paced replays), so callers inside a runtime read it from a
+/// blocking thread.
+pub trait PriceSource: Send {
+    fn tick(&mut self) -> Option<MarketTick>;
+}
+
+/// Probability measure a simulated path is drawn under
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Measure {
+    /// Physical drift `mu`: for risk simulation and the live hypervisor feed
+    #[default]
+    RealWorld,
+    /// Drift equal to the risk-free rate `r`: required for pricing by discounted expectation
+    RiskNeutral,
+}
+
+/// Heston Model Parameters (the `[feed]` table of sentinel.toml)
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct HestonParams {
+    pub s0: f64,      // Initial Price
+    pub v0: f64,      // Initial Volatility
+    pub kappa: f64,   // Mean reversion speed
+    pub theta: f64,   // Long run variance
+    pub xi: f64,      // Vol of Vol
+    pub rho: f64,     // Correlation
+    pub mu: f64,      // Real-world drift
+    pub risk_free_rate: f64, // Drift under the risk-neutral measure, continuously compounded
+    pub measure: Measure,
+    pub dt: f64,      // Time step
+    /// Clamp v_t at this multiple of theta; Euler steps with large xi/kappa can otherwise explode
+    pub vol_clip: Option<f64>,
+    /// Steps the variance is run for at construction before the first tick, so it starts near
+    /// its stationary regime instead of at `v0`. The price stays at `s0` and the market clock at 0.
+    pub burn_in: usize,
+}
+
+impl Default for HestonParams {
+    fn default() -> Self {
         Self {
             s0: 100.0,
             v0: 0.04,
@@ -32,37 +110,272 @@ impl SentinelFeed {
             theta: 0.04,
             xi: 0.1,
             rho: -0.7, // Leverage effect
+            mu: 0.05,
+            risk_free_rate: 0.05,
+            measure: Measure::RealWorld,
             dt: 1.0/252.0, // Daily step
-            current_price: 100.0,
-            current_vol: 0.04,
+            vol_clip: None,
+            burn_in: 0,
         }
     }
-    
-    /// Simulates one step of Heston Stochastic Volatility Model
-    /// dS_t = mu*S_t*dt + sqrt(v_t)*S_t*dW_t^S
-    /// dv_t = kappa*(theta - v_t)*dt + xi*sqrt(v_t)*dW_t^v
-    pub fn next_tick(&mut self) -> f64 {
-        let mut rng = thread_rng();
-        let normal = Normal::new(0.0, 1.0).unwrap();
-        
-        // Correlated Brownian Motions
-        let z1 = normal.sample(&mut rng);
-        let z2 = self.rho * z1 + (1.0 - self.rho.powi(2)).sqrt() * normal.sample(&mut rng);
-        
+}
+
+impl HestonParams {
+    /// Price drift under the configured measure: `mu` (real world) or `risk_free_rate` (risk neutral)
+    pub fn drift(&self) -> f64 {
+        match self.measure {
+            Measure::RealWorld => self.mu,
+            Measure::RiskNeutral => self.risk_free_rate,
+        }
+    }
+}
+
+/// One Heston path: spot and variance v_t
+#[derive(Debug, Clone, Copy)]
+struct HestonState {
+    price: f64,
+    vol: f64,
+}
+
+impl HestonState {
+    /// Euler step driven by correlated standard normals (z1 -> price, z2 -> variance).
+    /// True when the variance had to be clamped at `vol_clip * theta`.
+    fn step(&mut self, p: &HestonParams, z1: f64, z2: f64) -> bool {
+        // The price diffuses with the variance at the start of the step: using the updated one
+        // (itself driven by z2, correlated with z1) would bias the drift by rho * xi / 2
+        let start_vol = self.vol;
+
         // Volatility Process (CIR) - Full Interaction
-        let dv = self.kappa * (self.theta - self.current_vol) * self.dt 
-                 + self.xi * self.current_vol.sqrt() * z2 * self.dt.sqrt();
+        let dv = p.kappa * (p.theta - self.vol) * p.dt 
+                 + p.xi * self.vol.sqrt() * z2 * p.dt.sqrt();
         
-        self.current_vol = (self.current_vol + dv).max(0.001); // Ensure positivity
+        self.vol = (self.vol + dv).max(0.001); // Ensure positivity
+        let cap = p.vol_clip.map(|m| m * p.theta).filter(|&cap| self.vol > cap || self.vol.is_nan());
+        if let Some(cap) = cap {
+            self.vol = cap;
+        }
         
         // Price Process
-        let drift = 0.05; // 5% risk-free assumption
-        let ds = drift * self.current_price * self.dt 
-                 + self.current_vol.sqrt() * self.current_price * z1 * self.dt.sqrt();
+        let ds = p.drift() * self.price * p.dt 
+                 + start_vol.sqrt() * self.price * z1 * p.dt.sqrt();
                  
-        self.current_price += ds;
+        self.price += ds;
+        cap.is_some()
+    }
+}
+
+// HESTON MODEL IMPLEMENTATION
+pub struct SentinelFeed {
+    params: HestonParams,
+    
+    current: HestonState,
+    mirror: HestonState, // Antithetic twin of `current`, only advanced by next_tick_antithetic
+    clock: f64, // Market clock in seconds, advanced by dt per step
+    normals: Box<dyn NormalSource>,
+    steps: u64,
+    clips: u64, // Steps whose variance hit the `vol_clip` ceiling
+    stats: FeedStats, // Of the main path's ticks
+    sre: Option<SentinelSRE>,
+}
+
+impl SentinelFeed {
+    #[allow(dead_code)]
+    pub fn new() -> Self {
+        Self::with_params(HestonParams::default())
+    }
+
+    pub fn with_params(params: HestonParams) -> Self {
+        Self::with_normals(params, Box::new(PseudoNormals::from_entropy()))
+    }
+
+    /// Reproducible path: two feeds with the same seed consume identical normal draws
+    pub fn with_seed(params: HestonParams, seed: u64) -> Self {
+        Self::with_normals(params, Box::new(PseudoNormals::with_seed(seed)))
+    }
+
+    /// Drives the path from any normal source, e.g. `SobolNormals` for quasi-Monte Carlo.
+    /// Each step, burn-in included, consumes two draws.
+    pub fn with_normals(params: HestonParams, normals: Box<dyn NormalSource>) -> Self {
+        let start = HestonState { price: params.s0, vol: params.v0 };
+        let mut feed = Self {
+            params,
+            current: start,
+            mirror: start,
+            clock: 0.0,
+            normals,
+            steps: 0,
+            clips: 0,
+            stats: FeedStats::new(params.dt),
+            sre: None,
+        };
+        feed.burn_in();
+        feed
+    }
+
+    /// Runs `burn_in` unobserved steps from the same draws as the path itself (so seeded feeds
+    /// stay reproducible), then restarts both paths at s0 with the burnt-in variance
+    fn burn_in(&mut self) {
+        for _ in 0..self.params.burn_in {
+            let (z1, z2) = self.draw();
+            self.current.step(&self.params, z1, z2);
+        }
+        self.current.price = self.params.s0;
+        self.mirror = self.current;
+    }
+
+    /// Builder: Report variance clips as ("feed", "vol_clip") metrics
+    pub fn with_sre(mut self, sre: SentinelSRE) -> Self {
+        self.sre = Some(sre);
+        self
+    }
+
+    /// Steps whose variance was clamped; stays near zero for well-conditioned parameters
+    #[allow(dead_code)]
+    pub fn clip_count(&self) -> u64 {
+        self.clips
+    }
+
+    /// Fraction of steps clamped so far
+    pub fn clip_rate(&self) -> f64 {
+        if self.steps == 0 { 0.0 } else { self.clips as f64 / self.steps as f64 }
+    }
+
+    /// Running statistics of the main path's prices since construction (burn-in excluded)
+    #[allow(dead_code)]
+    pub fn stats(&self) -> &FeedStats {
+        &self.stats
+    }
+
+    fn record_step(&mut self, clipped: bool) {
+        self.steps += 1;
+        self.stats.observe(self.current.price);
+        if !clipped {
+            return;
+        }
+        self.clips += 1;
+        warn!("HESTON: Variance clamped at {:.1} x theta (clip rate {:.2}%)", self.params.vol_clip.unwrap_or_default(), self.clip_rate() * 100.0);
+        if let Some(sre) = &self.sre {
+            sre.record_metric("feed", "vol_clip", self.clip_rate());
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn params(&self) -> &HestonParams {
+        &self.params
+    }
+
+    /// Correlated Brownian increments (z1 for the price, z2 for the variance)
+    fn draw(&mut self) -> (f64, f64) {
+        let z1 = self.normals.next_normal();
+        let z2 = self.params.rho * z1 + (1.0 - self.params.rho.powi(2)).sqrt() * self.normals.next_normal();
+        (z1, z2)
+    }
+    
+    /// Simulates one step of Heston Stochastic Volatility Model
+    /// dS_t = m*S_t*dt + sqrt(v_t)*S_t*dW_t^S, with m = `params.drift()` (mu or r by measure)
+    /// dv_t = kappa*(theta - v_t)*dt + xi*sqrt(v_t)*dW_t^v
+    pub fn next_tick(&mut self) -> f64 {
+        let (z1, z2) = self.draw();
+        let clipped = self.current.step(&self.params, z1, z2);
+        self.record_step(clipped);
+        self.clock += self.params.dt * SECONDS_PER_YEAR;
         
-        debug!("HESTON: Price={:.2}, Vol={:.4}", self.current_price, self.current_vol);
-        self.current_price
+        debug!("HESTON: Price={:.2}, Vol={:.4}", self.current.price, self.current.vol);
+        self.current.price
+    }
+
+    /// Antithetic variates: advances the main path with (z1, z2) and the mirrored path with
+    /// (-z1, -z2). Averaging a payoff over the pair cuts Monte Carlo variance for monotone payoffs.
+    #[allow(dead_code)]
+    pub fn next_tick_antithetic(&mut self) -> (f64, f64) {
+        let (z1, z2) = self.draw();
+        let clipped = self.current.step(&self.params, z1, z2);
+        let mirror_clipped = self.mirror.step(&self.params, -z1, -z2);
+        self.record_step(clipped || mirror_clipped);
+        self.clock += self.params.dt * SECONDS_PER_YEAR;
+
+        debug!("HESTON: Price={:.2} / Antithetic={:.2}", self.current.price, self.mirror.price);
+        (self.current.price, self.mirror.price)
+    }
+
+    /// Same step as `next_tick`, also exposing the volatility state
+    pub fn next_market_tick(&mut self) -> MarketTick {
+        let price = self.next_tick();
+        MarketTick { price, vol: self.current.vol.sqrt(), ts: self.clock }
+    }
+}
+
+impl PriceSource for SentinelFeed {
+    fn tick(&mut self) -> Option<MarketTick> {
+        Some(self.next_market_tick())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variance(samples: &[f64]) -> f64 {
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (samples.len() - 1) as f64
+    }
+
+    #[test]
+    fn antithetic_pairs_average_with_lower_variance_than_independent_draws() {
+        let params = HestonParams::default();
+        let (paths, steps) = (2000, 21);
+        let call = |price: f64| (price - params.s0).max(0.0);
+
+        let antithetic: Vec<f64> = (0..paths)
+            .map(|i| {
+                let mut feed = SentinelFeed::with_seed(params, i);
+                let mut pair = (params.s0, params.s0);
+                for _ in 0..steps {
+                    pair = feed.next_tick_antithetic();
+                }
+                (call(pair.0) + call(pair.1)) / 2.0
+            })
+            .collect();
+        let independent: Vec<f64> = (0..paths)
+            .map(|i| {
+                let terminal = |seed| {
+                    let mut feed = SentinelFeed::with_seed(params, seed);
+                    (0..steps).map(|_| feed.next_tick()).last().unwrap()
+                };
+                (call(terminal(paths + 2 * i)) + call(terminal(paths + 2 * i + 1))) / 2.0
+            })
+            .collect();
+
+        let (anti, indep) = (variance(&antithetic), variance(&independent));
+        assert!(anti < indep, "antithetic {:.4} vs independent {:.4}", anti, indep);
+    }
+
+    #[test]
+    fn burn_in_starts_the_variance_closer_to_theta() {
+        let raw = HestonParams { v0: 0.25, ..HestonParams::default() };
+        let burnt = HestonParams { burn_in: 756, ..raw }; // Three years of daily steps
+        // Mean variance over the first five ticks of 200 seeded paths
+        let early_variance = |params: HestonParams| {
+            let total: f64 = (0..200)
+                .map(|seed| {
+                    let mut feed = SentinelFeed::with_seed(params, seed);
+                    (0..5).map(|_| feed.next_market_tick().vol.powi(2)).sum::<f64>()
+                })
+                .sum();
+            total / 1000.0
+        };
+        let (raw_error, burnt_error) = ((early_variance(raw) - raw.theta).abs(), (early_variance(burnt) - raw.theta).abs());
+        assert!(burnt_error < raw_error / 10.0, "burnt-in {:.4} vs raw {:.4} from theta", burnt_error, raw_error);
+    }
+
+    #[test]
+    fn the_price_step_uses_the_start_of_step_variance() {
+        let p = HestonParams::default();
+        let mut state = HestonState { price: 100.0, vol: 0.04 };
+        // A large variance shock must not leak into the same step's price move
+        state.step(&p, 1.0, 3.0);
+        let expected = 100.0 + p.drift() * 100.0 * p.dt + 0.04_f64.sqrt() * 100.0 * p.dt.sqrt();
+        assert!((state.price - expected).abs() < 1e-12, "{} != {}", state.price, expected);
+        assert!(state.vol > 0.04);
     }
 }
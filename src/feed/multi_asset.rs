@@ -0,0 +1,144 @@
+use super::{HestonParams, HestonState, MarketTick, SECONDS_PER_YEAR};
+use log::debug;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rand_distr::{Distribution, Normal};
+use thiserror::Error;
+
+// Tolerance for symmetry checks on user-supplied correlation matrices
+const SYMMETRY_TOLERANCE: f64 = 1e-9;
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum CorrelationError {
+    #[error("{assets} assets need a {assets}x{assets} correlation matrix")]
+    WrongShape { assets: usize },
+    #[error("correlation[{i}][{j}] = {value} is not a correlation in [-1, 1] (diagonal must be 1)")]
+    InvalidEntry { i: usize, j: usize, value: f64 },
+    #[error("correlation matrix is not symmetric at ({i}, {j})")]
+    NotSymmetric { i: usize, j: usize },
+    #[error("correlation matrix is not positive-definite (pivot {pivot})")]
+    NotPositiveDefinite { pivot: usize },
+}
+
+/// Lower-triangular L with L * L^T = matrix; fails on the first non-positive pivot
+fn cholesky(matrix: &[Vec<f64>]) -> Result<Vec<Vec<f64>>, CorrelationError> {
+    let n = matrix.len();
+    let mut l = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        for j in 0..=i {
+            let sum: f64 = (0..j).map(|k| l[i][k] * l[j][k]).sum();
+            if i == j {
+                let pivot = matrix[i][i] - sum;
+                if pivot <= 0.0 {
+                    return Err(CorrelationError::NotPositiveDefinite { pivot: i });
+                }
+                l[i][j] = pivot.sqrt();
+            } else {
+                l[i][j] = (matrix[i][j] - sum) / l[j][j];
+            }
+        }
+    }
+    Ok(l)
+}
+
+/// N Heston underlyings whose price shocks are correlated through a shared correlation matrix.
+/// Each asset keeps its own parameters (and its own spot/variance leverage `rho`).
+pub struct MultiAssetFeed {
+    params: Vec<HestonParams>,
+    chol: Vec<Vec<f64>>,
+    states: Vec<HestonState>,
+    clock: f64, // Shared market clock; assets step together, on the first asset's dt
+    rng: StdRng,
+}
+
+impl MultiAssetFeed {
+    #[allow(dead_code)]
+    pub fn new(params: Vec<HestonParams>, correlation: Vec<Vec<f64>>) -> Result<Self, CorrelationError> {
+        Self::with_rng(params, correlation, StdRng::from_entropy())
+    }
+
+    pub fn with_seed(params: Vec<HestonParams>, correlation: Vec<Vec<f64>>, seed: u64) -> Result<Self, CorrelationError> {
+        Self::with_rng(params, correlation, StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(params: Vec<HestonParams>, correlation: Vec<Vec<f64>>, rng: StdRng) -> Result<Self, CorrelationError> {
+        let n = params.len();
+        if correlation.len() != n || correlation.iter().any(|row| row.len() != n) {
+            return Err(CorrelationError::WrongShape { assets: n });
+        }
+        for (i, row) in correlation.iter().enumerate() {
+            for (j, &value) in row.iter().enumerate() {
+                let valid = if i == j { value == 1.0 } else { (-1.0..=1.0).contains(&value) };
+                if !valid {
+                    return Err(CorrelationError::InvalidEntry { i, j, value });
+                }
+                if (value - correlation[j][i]).abs() > SYMMETRY_TOLERANCE {
+                    return Err(CorrelationError::NotSymmetric { i, j });
+                }
+            }
+        }
+        let chol = cholesky(&correlation)?;
+        let states = params.iter().map(|p| HestonState { price: p.s0, vol: p.v0 }).collect();
+        Ok(Self { params, chol, states, clock: 0.0, rng })
+    }
+
+    /// The correlation matrix the feed was built with (recovered as L·Lᵀ from its Cholesky factor)
+    #[allow(dead_code)]
+    pub fn correlation(&self) -> Vec<Vec<f64>> {
+        let n = self.chol.len();
+        (0..n)
+            .map(|i| (0..n).map(|j| (0..n).map(|k| self.chol[i][k] * self.chol[j][k]).sum()).collect())
+            .collect()
+    }
+
+    /// Restarts every asset at its `s0`/`v0` and the clock at zero; the random stream continues,
+    /// so successive paths are independent
+    pub fn reset(&mut self) {
+        for (state, p) in self.states.iter_mut().zip(&self.params) {
+            *state = HestonState { price: p.s0, vol: p.v0 };
+        }
+        self.clock = 0.0;
+    }
+
+    pub fn assets(&self) -> usize {
+        self.params.len()
+    }
+
+    /// Advances every asset one step and returns the new prices (in asset order)
+    pub fn next_ticks(&mut self) -> Vec<f64> {
+        self.next_market_ticks().into_iter().map(|t| t.price).collect()
+    }
+
+    /// Advances every asset by `dt` years instead of its configured step (e.g. a shorter last
+    /// step that lands exactly on a maturity) and returns the new prices
+    pub fn next_ticks_over(&mut self, dt: f64) -> Vec<f64> {
+        self.advance(Some(dt)).into_iter().map(|t| t.price).collect()
+    }
+
+    /// Same step as `next_ticks`, also exposing each asset's volatility state
+    pub fn next_market_ticks(&mut self) -> Vec<MarketTick> {
+        self.advance(None)
+    }
+
+    /// One step of `dt` years, or of each asset's own `dt` when None
+    fn advance(&mut self, dt: Option<f64>) -> Vec<MarketTick> {
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        let n = self.assets();
+
+        // Cross-asset price shocks: z = L * e
+        let e: Vec<f64> = (0..n).map(|_| normal.sample(&mut self.rng)).collect();
+        let z: Vec<f64> = self.chol.iter().map(|row| row.iter().zip(&e).map(|(l, e)| l * e).sum()).collect();
+
+        self.clock += dt.or(self.params.first().map(|p| p.dt)).unwrap_or(0.0) * SECONDS_PER_YEAR;
+        let mut ticks = Vec::with_capacity(n);
+        for (i, (state, p)) in self.states.iter_mut().zip(&self.params).enumerate() {
+            let p = &HestonParams { dt: dt.unwrap_or(p.dt), ..*p };
+            // Per-asset variance shock, correlated with that asset's own price shock
+            let z2 = p.rho * z[i] + (1.0 - p.rho.powi(2)).sqrt() * normal.sample(&mut self.rng);
+            state.step(p, z[i], z2);
+            ticks.push(MarketTick { price: state.price, vol: state.vol.sqrt(), ts: self.clock });
+        }
+        debug!("HESTON-MULTI: Prices={:?}", ticks.iter().map(|t| t.price).collect::<Vec<_>>());
+        ticks
+    }
+}
@@ -0,0 +1,171 @@
+use super::{MarketTick, PriceSource, RealizedVol};
+use log::{debug, info};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+// Spacing assumed between prices of a single-column file (the default tick cadence)
+const DEFAULT_SPACING_SECS: f64 = 0.05;
+
+#[derive(Debug, Error)]
+pub enum ReplayError {
+    #[error("cannot read replay file {path}: {source}")]
+    Io { path: String, source: std::io::Error },
+    #[error("replay line {line}: {reason}")]
+    Malformed { line: usize, reason: String },
+}
+
+/// Replays a recorded price path. Files hold either one column (`price`) or two (`ts,price`,
+/// ts in seconds); a non-numeric first line is taken as a header. Single-column files are spaced
+/// DEFAULT_SPACING_SECS apart, which their realized vol assumes too: record `ts` for any other
+/// cadence. `vol` is the annualized realized volatility of the recorded prices.
+///
+/// In paced mode `tick()` sleeps so ticks come out with their recorded inter-tick gaps, which
+/// reproduces time-sensitive behaviour (deadlines, breaker cooldowns) from the original session.
+pub struct ReplayFeed {
+    points: Vec<(f64, f64)>, // (ts, price), ts relative to the first point
+    cursor: usize,
+    paced: bool,
+    started: Option<Instant>, // Wall-clock instant of the first paced tick
+    vol: RealizedVol,
+}
+
+impl ReplayFeed {
+    #[allow(dead_code)]
+    pub fn from_prices(prices: Vec<f64>) -> Self {
+        let points = prices.into_iter()
+            .enumerate()
+            .map(|(i, price)| (i as f64 * DEFAULT_SPACING_SECS, price))
+            .collect();
+        Self::from_points(points)
+    }
+
+    fn from_points(mut points: Vec<(f64, f64)>) -> Self {
+        if let Some(&(t0, _)) = points.first() {
+            points.iter_mut().for_each(|(ts, _)| *ts -= t0);
+        }
+        Self {
+            points,
+            cursor: 0,
+            paced: false,
+            started: None,
+            vol: RealizedVol::default(),
+        }
+    }
+
+    pub fn from_csv(path: impl AsRef<Path>) -> Result<Self, ReplayError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|source| ReplayError::Io { path: path.display().to_string(), source })?;
+
+        let mut points = Vec::new();
+        let mut header_seen = false;
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let malformed = |reason: &str| ReplayError::Malformed { line: i + 1, reason: reason.to_string() };
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let numbers: Result<Vec<f64>, _> = fields.iter().map(|f| f.parse::<f64>()).collect();
+            let numbers = match numbers {
+                Ok(numbers) => numbers,
+                Err(_) if points.is_empty() && !header_seen => {
+                    header_seen = true; // e.g. "ts,price"
+                    continue;
+                }
+                Err(_) => return Err(malformed("non-numeric field")),
+            };
+
+            let point = match numbers[..] {
+                [price] => (points.len() as f64 * DEFAULT_SPACING_SECS, price),
+                [ts, price] => (ts, price),
+                _ => return Err(malformed("expected 'price' or 'ts,price'")),
+            };
+            if !(point.1.is_finite() && point.1 > 0.0) {
+                return Err(malformed("price must be positive"));
+            }
+            if points.last().is_some_and(|&(last, _)| point.0 < last) {
+                return Err(malformed("timestamps go backwards"));
+            }
+            points.push(point);
+        }
+
+        info!("Replay: Loaded {} ticks from {}", points.len(), path.display());
+        Ok(Self::from_points(points))
+    }
+
+    /// Builder: Sleep between ticks to match the recorded gaps
+    pub fn paced(mut self, paced: bool) -> Self {
+        self.paced = paced;
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Blocks until the tick's recorded offset has elapsed since the first paced tick. Anchoring
+    /// every tick to the start (not the previous tick) keeps slow consumers from accumulating drift.
+    fn wait_until(&mut self, ts: f64) {
+        let started = *self.started.get_or_insert_with(Instant::now);
+        let due = started + Duration::from_secs_f64(ts.max(0.0));
+        let wait = due.saturating_duration_since(Instant::now());
+        if !wait.is_zero() {
+            std::thread::sleep(wait);
+        }
+    }
+}
+
+impl PriceSource for ReplayFeed {
+    fn tick(&mut self) -> Option<MarketTick> {
+        let (ts, price) = *self.points.get(self.cursor)?;
+        self.cursor += 1;
+        if self.paced {
+            self.wait_until(ts);
+        }
+        debug!("REPLAY: t={:.3}s Price={:.2}", ts, price);
+        Some(MarketTick { price, vol: self.vol.update(price, ts), ts })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feed::SECONDS_PER_YEAR;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use rand_distr::{Distribution, StandardNormal};
+
+    #[test]
+    fn a_replayed_20_percent_vol_path_reads_about_0_2() {
+        // Daily closes of a driftless 20%-vol GBM: one trading day of SECONDS_PER_YEAR apart
+        let (sigma, day) = (0.2, SECONDS_PER_YEAR / 252.0);
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut price = 100.0;
+        let points = (0..2000)
+            .map(|i| {
+                let z: f64 = StandardNormal.sample(&mut rng);
+                price *= (sigma * (1.0 / 252.0_f64).sqrt() * z - 0.5 * sigma * sigma / 252.0).exp();
+                (i as f64 * day, price)
+            })
+            .collect();
+        let mut feed = ReplayFeed::from_points(points);
+        let readings: Vec<f64> = std::iter::from_fn(|| feed.tick()).skip(100).map(|tick| tick.vol).collect();
+        let mean = readings.iter().sum::<f64>() / readings.len() as f64;
+        assert!((mean - sigma).abs() < 0.01, "mean realized vol {:.4}", mean);
+    }
+
+    #[tokio::test]
+    async fn a_paced_replay_ticks_inside_a_current_thread_runtime() {
+        let mut feed = ReplayFeed::from_points(vec![(0.0, 100.0), (0.01, 101.0)]).paced(true);
+        assert_eq!(feed.tick().map(|tick| tick.price), Some(100.0));
+        assert_eq!(feed.tick().map(|tick| tick.price), Some(101.0));
+    }
+}
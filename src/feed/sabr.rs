@@ -0,0 +1,123 @@
+use super::{MarketTick, PriceSource, SECONDS_PER_YEAR};
+use log::{debug, warn};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rand_distr::{Distribution, Normal};
+use serde::Deserialize;
+
+// Floor for the forward when beta < 1 (the CEV forward is absorbed at zero)
+const MIN_FORWARD: f64 = 1e-8;
+
+/// SABR Model Parameters (the `[sabr]` table of sentinel.toml)
+/// dF_t = alpha_t * F_t^beta * dW_t^F,  dalpha_t = nu * alpha_t * dW_t^alpha,  d<W^F, W^alpha> = rho dt
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct SabrParams {
+    pub f0: f64,    // Initial Forward
+    pub alpha: f64, // Initial volatility level
+    pub beta: f64,  // CEV exponent in [0, 1]: 0 = normal, 1 = lognormal
+    pub rho: f64,   // Correlation
+    pub nu: f64,    // Vol of Vol
+    pub dt: f64,    // Time step
+}
+
+impl Default for SabrParams {
+    fn default() -> Self {
+        Self {
+            f0: 100.0,
+            alpha: 0.2,
+            beta: 1.0,
+            rho: -0.3,
+            nu: 0.4,
+            dt: 1.0/252.0, // Daily step
+        }
+    }
+}
+
+// SABR MODEL IMPLEMENTATION
+pub struct SabrFeed {
+    params: SabrParams,
+    forward: f64,
+    alpha: f64,
+    clock: f64, // Market clock in seconds, advanced by dt per step
+    rng: StdRng,
+}
+
+impl SabrFeed {
+    pub fn new(params: SabrParams) -> Self {
+        Self::with_rng(params, StdRng::from_entropy())
+    }
+
+    #[allow(dead_code)]
+    pub fn with_seed(params: SabrParams, seed: u64) -> Self {
+        Self::with_rng(params, StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(mut params: SabrParams, rng: StdRng) -> Self {
+        if !(0.0..=1.0).contains(&params.beta) {
+            warn!("SABR: beta={} outside [0,1], clamping.", params.beta);
+            params.beta = params.beta.clamp(0.0, 1.0);
+        }
+        Self {
+            params,
+            forward: params.f0,
+            alpha: params.alpha,
+            clock: 0.0,
+            rng,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn params(&self) -> &SabrParams {
+        &self.params
+    }
+
+    /// Lognormal-equivalent instantaneous volatility alpha * F^(beta-1)
+    fn local_vol(&self) -> f64 {
+        if self.params.beta == 1.0 {
+            self.alpha
+        } else {
+            self.alpha * self.forward.abs().max(MIN_FORWARD).powf(self.params.beta - 1.0)
+        }
+    }
+
+    /// One step of the forward process. Log-Euler for beta > 0; beta = 0 is the normal model,
+    /// where F may cross zero, so it is stepped arithmetically instead.
+    pub fn next_tick(&mut self) -> f64 {
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        let p = self.params;
+
+        // Correlated Brownian Motions
+        let z1 = normal.sample(&mut self.rng);
+        let z2 = p.rho * z1 + (1.0 - p.rho.powi(2)).sqrt() * normal.sample(&mut self.rng);
+        let sqrt_dt = p.dt.sqrt();
+
+        // Forward Process (uses alpha at the start of the step)
+        if p.beta == 0.0 {
+            self.forward += self.alpha * z1 * sqrt_dt;
+        } else {
+            let sigma = self.local_vol(); // beta = 1: sigma = alpha, plain GBM
+            let log_step = sigma * z1 * sqrt_dt - 0.5 * sigma * sigma * p.dt;
+            self.forward = (self.forward.max(MIN_FORWARD).ln() + log_step).exp().max(MIN_FORWARD);
+        }
+
+        // Volatility Process: exact lognormal step
+        self.alpha *= (p.nu * z2 * sqrt_dt - 0.5 * p.nu * p.nu * p.dt).exp();
+        self.clock += p.dt * SECONDS_PER_YEAR;
+
+        debug!("SABR: Forward={:.2}, Alpha={:.4}", self.forward, self.alpha);
+        self.forward
+    }
+
+    /// Same step as `next_tick`, also exposing the lognormal-equivalent volatility
+    pub fn next_market_tick(&mut self) -> MarketTick {
+        let price = self.next_tick();
+        MarketTick { price, vol: self.local_vol(), ts: self.clock }
+    }
+}
+
+impl PriceSource for SabrFeed {
+    fn tick(&mut self) -> Option<MarketTick> {
+        Some(self.next_market_tick())
+    }
+}
@@ -0,0 +1,221 @@
+use log::warn;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rand_distr::{Distribution, Normal};
+
+/// Source of standard-normal draws driving a simulated feed
+pub trait NormalSource: Send {
+    fn next_normal(&mut self) -> f64;
+}
+
+/// Pseudo-random N(0, 1) draws from a seedable RNG
+pub struct PseudoNormals {
+    rng: StdRng,
+    normal: Normal<f64>,
+}
+
+impl PseudoNormals {
+    pub fn new(rng: StdRng) -> Self {
+        Self { rng, normal: Normal::new(0.0, 1.0).unwrap() }
+    }
+
+    pub fn from_entropy() -> Self {
+        Self::new(StdRng::from_entropy())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::new(StdRng::seed_from_u64(seed))
+    }
+}
+
+impl NormalSource for PseudoNormals {
+    fn next_normal(&mut self) -> f64 {
+        self.normal.sample(&mut self.rng)
+    }
+}
+
+const SOBOL_BITS: usize = 32;
+
+// Joe & Kuo (2008) primitive polynomials and initial direction numbers for dimensions 2..=16,
+// as (degree s, coefficients a, m_1..m_s). Dimension 1 is the van der Corput sequence.
+const JOE_KUO: [(usize, u32, &[u32]); 15] = [
+    (1, 0, &[1]),
+    (2, 1, &[1, 3]),
+    (3, 1, &[1, 3, 1]),
+    (3, 2, &[1, 1, 1]),
+    (4, 1, &[1, 1, 3, 3]),
+    (4, 4, &[1, 3, 5, 13]),
+    (5, 2, &[1, 1, 5, 5, 17]),
+    (5, 4, &[1, 1, 5, 5, 5]),
+    (5, 7, &[1, 1, 7, 11, 19]),
+    (5, 11, &[1, 1, 5, 1, 1]),
+    (5, 13, &[1, 1, 1, 3, 11]),
+    (5, 14, &[1, 3, 5, 5, 31]),
+    (6, 1, &[1, 3, 3, 9, 7, 49]),
+    (6, 13, &[1, 1, 1, 15, 21, 21]),
+    (6, 16, &[1, 3, 1, 13, 27, 49]),
+];
+
+/// Highest dimension with Sobol direction numbers; further coordinates are padded pseudo-randomly
+pub const SOBOL_MAX_DIMENSIONS: usize = JOE_KUO.len() + 1;
+
+/// Quasi-random N(0, 1) draws: a Sobol sequence mapped through the inverse normal CDF.
+/// Draws are handed out coordinate by coordinate, point by point, so `dimensions` should equal
+/// the draws one sample consumes (two per Heston step) and each sample takes exactly one point.
+/// Coordinates beyond `SOBOL_MAX_DIMENSIONS` come from a seeded pseudo-random stream (padding):
+/// the leading coordinates keep their low discrepancy, the tail behaves like plain Monte Carlo.
+pub struct SobolNormals {
+    directions: Vec<[u32; SOBOL_BITS]>,
+    point: Vec<u32>, // Current point, one 32-bit fraction per Sobol dimension
+    index: u64,      // Index of `point` in the sequence
+    coordinate: usize,
+    dimensions: usize,
+    padding: PseudoNormals,
+}
+
+impl SobolNormals {
+    pub fn new(dimensions: usize) -> Self {
+        let dimensions = dimensions.max(1);
+        if dimensions > SOBOL_MAX_DIMENSIONS {
+            warn!("Sobol: {} dimensions requested, padding beyond {} with pseudo-random draws", dimensions, SOBOL_MAX_DIMENSIONS);
+        }
+        let sobol_dims = dimensions.min(SOBOL_MAX_DIMENSIONS);
+        let directions = (0..sobol_dims).map(direction_numbers).collect();
+        // Point 0 is the origin, whose inverse CDF is -inf; the sequence starts at point 1
+        Self::starting_at(directions, dimensions, 1)
+    }
+
+    /// Builder: Start at point `index` of the sequence, e.g. so path i of a Monte Carlo run
+    /// built from independent feeds takes point i + 1
+    pub fn skip_to(self, index: u64) -> Self {
+        Self::starting_at(self.directions, self.dimensions, index.max(1))
+    }
+
+    fn starting_at(directions: Vec<[u32; SOBOL_BITS]>, dimensions: usize, index: u64) -> Self {
+        // Gray-code construction: point n is the XOR of the directions at the set bits of n ^ (n >> 1)
+        let gray = index ^ (index >> 1);
+        let point = directions
+            .iter()
+            .map(|v| (0..SOBOL_BITS).filter(|&b| gray >> b & 1 == 1).fold(0, |x, b| x ^ v[b]))
+            .collect();
+        Self {
+            directions,
+            point,
+            index,
+            coordinate: 0,
+            dimensions,
+            padding: PseudoNormals::with_seed(index),
+        }
+    }
+
+    /// Moves to point index + 1 by flipping the direction at the lowest zero bit of index
+    fn advance(&mut self) {
+        let bit = (!self.index).trailing_zeros() as usize;
+        for (x, v) in self.point.iter_mut().zip(&self.directions) {
+            *x ^= v[bit.min(SOBOL_BITS - 1)];
+        }
+        self.index += 1;
+    }
+}
+
+impl NormalSource for SobolNormals {
+    fn next_normal(&mut self) -> f64 {
+        if self.coordinate == self.dimensions {
+            self.advance();
+            self.coordinate = 0;
+        }
+        let d = self.coordinate;
+        self.coordinate += 1;
+        match self.point.get(d) {
+            Some(&x) => inverse_normal_cdf((x as f64 + 0.5) / 4_294_967_296.0),
+            None => self.padding.next_normal(),
+        }
+    }
+}
+
+/// Direction numbers v_k = m_k / 2^k of one dimension, left-aligned in 32 bits
+fn direction_numbers(dimension: usize) -> [u32; SOBOL_BITS] {
+    let mut v = [0u32; SOBOL_BITS];
+    if dimension == 0 {
+        for (k, vk) in v.iter_mut().enumerate() {
+            *vk = 1 << (SOBOL_BITS - 1 - k);
+        }
+        return v;
+    }
+    let (s, a, m) = JOE_KUO[dimension - 1];
+    for k in 0..SOBOL_BITS {
+        v[k] = if k < s {
+            m[k] << (SOBOL_BITS - 1 - k)
+        } else {
+            // v_k = v_{k-s} ^ (v_{k-s} >> s) ^ sum_j a_j v_{k-j}
+            let mut x = v[k - s] ^ (v[k - s] >> s);
+            for j in 1..s {
+                if (a >> (s - 1 - j)) & 1 == 1 {
+                    x ^= v[k - j];
+                }
+            }
+            x
+        };
+    }
+    v
+}
+
+/// Acklam's rational approximation of the standard normal quantile (relative error < 1.2e-9)
+pub fn inverse_normal_cdf(p: f64) -> f64 {
+    const A: [f64; 6] = [-3.969683028665376e1, 2.209460984245205e2, -2.759285104469687e2, 1.38357751867269e2, -3.066479806614716e1, 2.506628277459239];
+    const B: [f64; 5] = [-5.447609879822406e1, 1.615858368580409e2, -1.556989798598866e2, 6.680131188771972e1, -1.328068155288572e1];
+    const C: [f64; 6] = [-7.784894002430293e-3, -3.223964580411365e-1, -2.400758277161838, -2.549732539343734, 4.374664141464968, 2.938163982698783];
+    const D: [f64; 4] = [7.784695709041462e-3, 3.224671290700398e-1, 2.445134137142996, 3.754408661907416];
+    const P_LOW: f64 = 0.02425;
+
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    if p <= 0.0 {
+        f64::NEG_INFINITY
+    } else if p >= 1.0 {
+        f64::INFINITY
+    } else if p < P_LOW {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - P_LOW {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feed::{HestonParams, Measure, SentinelFeed};
+
+    #[test]
+    fn sobol_paths_estimate_the_terminal_mean_more_accurately() {
+        let params = HestonParams { measure: Measure::RiskNeutral, dt: 1.0 / 12.0, ..HestonParams::default() };
+        let (paths, steps) = (1024, SOBOL_MAX_DIMENSIONS / 2);
+        // Each Euler step scales the expected price by (1 + r dt), whatever the draws
+        let exact = params.s0 * (1.0 + params.risk_free_rate * params.dt).powi(steps as i32);
+        let error = |normals: &mut dyn FnMut(u64) -> Box<dyn NormalSource>| {
+            let total: f64 = (0..paths)
+                .map(|i| {
+                    let mut feed = SentinelFeed::with_normals(params, normals(i));
+                    (0..steps).map(|_| feed.next_tick()).last().unwrap()
+                })
+                .sum();
+            (total / paths as f64 - exact).abs()
+        };
+
+        let sobol = error(&mut |i| Box::new(SobolNormals::new(2 * steps).skip_to(i + 1)));
+        // Root-mean-square error of the pseudo-random estimator over independent runs
+        let runs = 8;
+        let pseudo = ((0..runs)
+            .map(|run| error(&mut |i| Box::new(PseudoNormals::with_seed(run * paths + i))).powi(2))
+            .sum::<f64>() / runs as f64)
+            .sqrt();
+        assert!(sobol < pseudo / 2.0, "Sobol error {:.5} vs pseudo-random {:.5}", sobol, pseudo);
+    }
+}
@@ -0,0 +1,109 @@
+/// Running statistics of a simulated price path, updated in O(1) per tick: price range, mean and
+/// variance (Welford), and the realized volatility of its log returns, annualized by `dt` so it
+/// can be compared with `sqrt(theta)` at a glance
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FeedStats {
+    dt: f64, // Model years per tick
+    count: u64,
+    min: f64,
+    max: f64,
+    mean: f64,
+    m2: f64, // Sum of squared deviations from `mean`
+    last_price: Option<f64>,
+    returns: u64,
+    return_mean: f64,
+    return_m2: f64,
+}
+
+impl FeedStats {
+    pub fn new(dt: f64) -> Self {
+        Self {
+            dt,
+            count: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            mean: 0.0,
+            m2: 0.0,
+            last_price: None,
+            returns: 0,
+            return_mean: 0.0,
+            return_m2: 0.0,
+        }
+    }
+
+    pub fn observe(&mut self, price: f64) {
+        self.count += 1;
+        self.min = self.min.min(price);
+        self.max = self.max.max(price);
+        let delta = price - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (price - self.mean);
+
+        if let Some(last) = self.last_price.filter(|&last| last > 0.0 && price > 0.0) {
+            let r = (price / last).ln();
+            self.returns += 1;
+            let delta = r - self.return_mean;
+            self.return_mean += delta / self.returns as f64;
+            self.return_m2 += delta * (r - self.return_mean);
+        }
+        self.last_price = Some(price);
+    }
+
+    /// Ticks observed
+    #[allow(dead_code)]
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Lowest price seen; None before the first tick
+    #[allow(dead_code)]
+    pub fn min(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.min)
+    }
+
+    /// Highest price seen; None before the first tick
+    #[allow(dead_code)]
+    pub fn max(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.max)
+    }
+
+    #[allow(dead_code)]
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Sample variance of the prices; 0 until two ticks are in
+    #[allow(dead_code)]
+    pub fn variance(&self) -> f64 {
+        if self.count < 2 { 0.0 } else { self.m2 / (self.count - 1) as f64 }
+    }
+
+    /// Annualized sample standard deviation of the log returns, sqrt(var(r) / dt). For a Heston
+    /// path it converges to roughly sqrt(theta) (the long-run variance) as the path lengthens.
+    #[allow(dead_code)]
+    pub fn realized_vol(&self) -> f64 {
+        if self.returns < 2 || self.dt <= 0.0 {
+            return 0.0;
+        }
+        (self.return_m2 / (self.returns - 1) as f64 / self.dt).sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::feed::{HestonParams, SentinelFeed};
+
+    #[test]
+    fn realized_vol_of_a_long_path_is_near_sqrt_theta() {
+        let params = HestonParams::default();
+        let mut feed = SentinelFeed::with_seed(params, 11);
+        for _ in 0..50_000 {
+            feed.next_tick();
+        }
+        let stats = feed.stats();
+        assert_eq!(stats.count(), 50_000);
+        assert!(stats.min().unwrap() <= stats.mean() && stats.mean() <= stats.max().unwrap());
+        let target = params.theta.sqrt();
+        assert!((stats.realized_vol() - target).abs() < 0.1 * target, "realized {:.4} vs sqrt(theta) {:.4}", stats.realized_vol(), target);
+    }
+}
@@ -0,0 +1,61 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Device-level calibration snapshot from calibration_scanner.py. Explicit top-level fields
+/// (`t1_us`, `t2_us`, `eplg`, `readout_error`, `gate_errors`) win; otherwise coherence and
+/// readout are the medians over the operational entries of `qubits` (the way IBM summarizes a
+/// device) and the EPLG comes from `parameters.eplg_input`. A field that is absent or of the
+/// wrong type is left as None rather than failing the whole snapshot.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CalibrationData {
+    pub backend: Option<String>,
+    pub t1_us: Option<f64>,
+    pub t2_us: Option<f64>,
+    pub eplg: Option<f64>,
+    pub readout_error: Option<f64>,
+    pub gate_errors: HashMap<String, f64>, // e.g. "cz" -> 3.1e-3
+}
+
+impl CalibrationData {
+    /// Only text that is not a JSON object is an error
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let value: Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        if !value.is_object() {
+            return Err(format!("expected a JSON object, got {}", value));
+        }
+
+        let qubits: Vec<&Value> = value["qubits"].as_array()
+            .map(|qs| qs.iter().filter(|q| q["operational"].as_bool() != Some(false)).collect())
+            .unwrap_or_default();
+        let per_qubit = |key: &str| median(qubits.iter().filter_map(|q| positive(&q[key])).collect());
+
+        Ok(Self {
+            backend: value["backend"].as_str().map(str::to_string),
+            t1_us: positive(&value["t1_us"]).or_else(|| per_qubit("t1")),
+            t2_us: positive(&value["t2_us"]).or_else(|| per_qubit("t2")),
+            eplg: probability(&value["eplg"]).or_else(|| probability(&value["parameters"]["eplg_input"])),
+            readout_error: probability(&value["readout_error"]).or_else(|| per_qubit("readout_error")),
+            gate_errors: value["gate_errors"].as_object()
+                .map(|gates| gates.iter().filter_map(|(gate, e)| Some((gate.clone(), probability(e)?))).collect())
+                .unwrap_or_default(),
+        })
+    }
+}
+
+fn positive(value: &Value) -> Option<f64> {
+    value.as_f64().filter(|v| v.is_finite() && *v > 0.0)
+}
+
+fn probability(value: &Value) -> Option<f64> {
+    value.as_f64().filter(|p| (0.0..=1.0).contains(p))
+}
+
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) { (values[mid - 1] + values[mid]) / 2.0 } else { values[mid] })
+}
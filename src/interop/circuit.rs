@@ -0,0 +1,227 @@
+use crate::sre::GateModel;
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// One instruction of the gate subset the QAOA generators emit. Angles are kept as the QASM
+/// expression text (e.g. `2*beta_0`), since unbound parameters have no numeric value yet.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Gate {
+    H(usize),
+    X(usize),
+    Rz(usize, String),
+    Rx(usize, String),
+    Cx(usize, usize), // (control, target)
+    Rzz(usize, usize, String),
+    /// Scheduling fence: later gates on these qubits wait for the slowest of them. Takes no time.
+    Barrier(Vec<usize>),
+    Measure(usize),
+}
+
+impl Gate {
+    pub fn qubits(&self) -> Vec<usize> {
+        match self {
+            Gate::H(q) | Gate::X(q) | Gate::Rz(q, _) | Gate::Rx(q, _) | Gate::Measure(q) => vec![*q],
+            Gate::Cx(a, b) | Gate::Rzz(a, b, _) => vec![*a, *b],
+            Gate::Barrier(qubits) => qubits.clone(),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn is_two_qubit(&self) -> bool {
+        matches!(self, Gate::Cx(..) | Gate::Rzz(..))
+    }
+
+    /// (1q, 2q) hardware layers the gate occupies: rzz runs as the cx-rz-cx it transpiles to
+    fn layers(&self) -> (usize, usize) {
+        match self {
+            Gate::Barrier(_) => (0, 0),
+            Gate::Cx(..) => (0, 1),
+            Gate::Rzz(..) => (1, 2),
+            _ => (1, 0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Error)]
+#[error("QASM statement {statement}: {reason}")]
+pub struct QasmParseError {
+    pub statement: usize,
+    pub reason: String,
+}
+
+/// Gate-level view of a circuit, parsed from OpenQASM 2 or built directly, so its depth and
+/// critical path can be measured in Rust instead of estimated from the QAOA depth
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Circuit {
+    pub num_qubits: usize,
+    pub gates: Vec<Gate>,
+}
+
+impl Circuit {
+    pub fn new(num_qubits: usize) -> Self {
+        Self { num_qubits, gates: Vec::new() }
+    }
+
+    /// Appends `gate`, growing the register if it names a higher qubit
+    pub fn push(&mut self, gate: Gate) {
+        let highest = gate.qubits().into_iter().max().map_or(0, |q| q + 1);
+        self.num_qubits = self.num_qubits.max(highest);
+        self.gates.push(gate);
+    }
+
+    /// Parses the OpenQASM 2 subset the QAOA generators emit: `qreg`/`creg` declarations, h, x,
+    /// rz, rx, cx, rzz, barrier and measure, each on indexed qubits or a whole register.
+    /// Headers, includes and `//` comments are skipped; any other gate is an error.
+    pub fn from_qasm(qasm: &str) -> Result<Self, QasmParseError> {
+        let code: String = qasm.lines().map(|line| line.split("//").next().unwrap_or_default()).collect::<Vec<_>>().join("\n");
+        let mut circuit = Circuit::new(0);
+        let mut registers: HashMap<String, (usize, usize)> = HashMap::new(); // name -> (offset, size)
+
+        for (idx, statement) in code.split(';').map(str::trim).filter(|s| !s.is_empty()).enumerate() {
+            let error = |reason: String| QasmParseError { statement: idx + 1, reason };
+            let (head, params, rest) = split_statement(statement).map_err(error)?;
+            let operands = |text: &str| -> Result<Vec<Vec<usize>>, QasmParseError> {
+                text.split(',').map(|operand| resolve(operand.trim(), &registers).map_err(error)).collect()
+            };
+            match head {
+                "OPENQASM" | "include" | "creg" => {}
+                "qreg" => {
+                    let (name, size) = declaration(rest).map_err(error)?;
+                    registers.insert(name.to_string(), (circuit.num_qubits, size));
+                    circuit.num_qubits += size;
+                }
+                "barrier" => circuit.push(Gate::Barrier(operands(rest)?.concat())),
+                "measure" => {
+                    let (qubits, _clbits) = rest.split_once("->").ok_or_else(|| error("measure without a target".to_string()))?;
+                    for q in operands(qubits)?.concat() {
+                        circuit.push(Gate::Measure(q));
+                    }
+                }
+                "h" | "x" | "rz" | "rx" => {
+                    let angle = || params.map(str::to_string).ok_or_else(|| error(format!("{} needs an angle", head)));
+                    for q in operands(rest)?.concat() {
+                        circuit.push(match head {
+                            "h" => Gate::H(q),
+                            "x" => Gate::X(q),
+                            "rz" => Gate::Rz(q, angle()?),
+                            _ => Gate::Rx(q, angle()?),
+                        });
+                    }
+                }
+                "cx" | "rzz" => {
+                    let pair = operands(rest)?;
+                    let [a, b] = pair.as_slice() else {
+                        return Err(error(format!("{} takes two operands", head)));
+                    };
+                    if a.len() != 1 || b.len() != 1 || a[0] == b[0] {
+                        return Err(error(format!("{} needs two distinct single qubits", head)));
+                    }
+                    circuit.push(match head {
+                        "cx" => Gate::Cx(a[0], b[0]),
+                        _ => Gate::Rzz(a[0], b[0], params.ok_or_else(|| error("rzz needs an angle".to_string()))?.to_string()),
+                    });
+                }
+                other => return Err(error(format!("unsupported instruction '{}'", other))),
+            }
+        }
+        Ok(circuit)
+    }
+
+    /// Layers on the longest qubit timeline, counted the way Qiskit does: every gate (measure
+    /// included) is one layer on its qubits; barriers align qubits but add none
+    pub fn depth(&self) -> usize {
+        self.schedule(|(n1, n2)| if n1 + n2 > 0 { 1.0 } else { 0.0 }).1
+    }
+
+    /// (1q layers, 2q layers) on the critical path under `model`'s gate times: plugged into
+    /// `CoherenceVerifier::check` they reproduce the circuit's scheduled duration
+    pub fn critical_layers(&self, model: &GateModel) -> (usize, usize) {
+        self.schedule(|(n1, n2)| model.duration_micros(n1, n2)).0
+    }
+
+    /// ASAP schedule. Per qubit: finish time, the (1q, 2q) layers of the path that got it there
+    /// and its gate count. Returns the layers of the latest-finishing path and the largest count.
+    fn schedule(&self, duration: impl Fn((usize, usize)) -> f64) -> ((usize, usize), usize) {
+        let mut finish = vec![(0.0_f64, (0usize, 0usize), 0usize); self.num_qubits];
+        for gate in &self.gates {
+            let qubits: Vec<usize> = gate.qubits().into_iter().filter(|&q| q < self.num_qubits).collect();
+            let Some(&latest) = qubits.iter().max_by(|&&a, &&b| finish[a].0.total_cmp(&finish[b].0).then(finish[a].2.cmp(&finish[b].2))) else {
+                continue;
+            };
+            let (start, (n1, n2), count) = finish[latest];
+            let (g1, g2) = gate.layers();
+            let counted = usize::from(!matches!(gate, Gate::Barrier(_)));
+            let next = (start + duration((g1, g2)), (n1 + g1, n2 + g2), count + counted);
+            for q in qubits {
+                finish[q] = next;
+            }
+        }
+        let critical = finish.iter().max_by(|a, b| a.0.total_cmp(&b.0)).map_or((0, 0), |f| f.1);
+        (critical, finish.iter().map(|f| f.2).max().unwrap_or(0))
+    }
+}
+
+/// `name(params) operands` -> (name, params, operands)
+fn split_statement(statement: &str) -> Result<(&str, Option<&str>, &str), String> {
+    let name_end = statement.find(|c: char| c.is_whitespace() || c == '(').unwrap_or(statement.len());
+    let (name, rest) = statement.split_at(name_end);
+    let rest = rest.trim_start();
+    match rest.strip_prefix('(') {
+        Some(inner) => {
+            let close = inner.rfind(')').ok_or_else(|| format!("unclosed parameter list in '{}'", statement))?;
+            Ok((name, Some(inner[..close].trim()), inner[close + 1..].trim()))
+        }
+        None => Ok((name, None, rest)),
+    }
+}
+
+/// `q[4]` -> ("q", 4)
+fn declaration(text: &str) -> Result<(&str, usize), String> {
+    let (name, size) = text.trim().strip_suffix(']').and_then(|t| t.split_once('['))
+        .ok_or_else(|| format!("bad register declaration '{}'", text))?;
+    let size = size.trim().parse().map_err(|_| format!("bad register size in '{}'", text))?;
+    Ok((name.trim(), size))
+}
+
+/// `q[2]` -> [offset + 2]; a bare register name -> all of its qubits
+fn resolve(operand: &str, registers: &HashMap<String, (usize, usize)>) -> Result<Vec<usize>, String> {
+    let lookup = |name: &str| registers.get(name.trim()).copied().ok_or_else(|| format!("unknown register '{}'", name.trim()));
+    match operand.strip_suffix(']').and_then(|t| t.split_once('[')) {
+        Some((name, index)) => {
+            let (offset, size) = lookup(name)?;
+            let index: usize = index.trim().parse().map_err(|_| format!("bad qubit index in '{}'", operand))?;
+            if index >= size {
+                return Err(format!("'{}' is outside its register of {}", operand, size));
+            }
+            Ok(vec![offset + index])
+        }
+        None => {
+            let (offset, size) = lookup(operand)?;
+            Ok((offset..offset + size).collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn depth_of_a_small_circuit_counts_layers_on_the_longest_timeline() {
+        let circuit = Circuit::from_qasm(
+            "OPENQASM 2.0;
+             include \"qelib1.inc\";
+             qreg q[3];
+             creg c[3];
+             h q;               // layer 1 on every qubit
+             cx q[0],q[1];      // 2
+             cx q[1],q[2];      // 3
+             rz(0.5) q[0];      // 3, beside the second cx
+             barrier q;
+             measure q -> c;    // 4",
+        ).unwrap();
+        assert_eq!(circuit.num_qubits, 3);
+        assert_eq!(circuit.gates.len(), 3 + 2 + 1 + 1 + 3);
+        assert_eq!(circuit.depth(), 4);
+    }
+}
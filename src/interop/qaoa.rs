@@ -0,0 +1,120 @@
+use super::circuit::{Circuit, Gate};
+use super::InteropError;
+use serde::Serialize;
+
+/// Weighted MaxCut-style cost Hamiltonian `sum w_uv Z_u Z_v` and the QAOA depth to run it at.
+/// Serializes to `{"num_qubits": 4, "edges": [[0, 1, 0.5], ...], "p": 2}` for qaoa_strategy.py.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QaoaProblem {
+    pub num_qubits: usize,
+    pub edges: Vec<(usize, usize, f64)>, // (u, v, weight)
+    pub p: usize,
+}
+
+impl QaoaProblem {
+    pub fn new(num_qubits: usize, edges: Vec<(usize, usize, f64)>, p: usize) -> Self {
+        Self { num_qubits, edges, p }
+    }
+
+    /// Portfolio diversification: one qubit per asset, coupled by the pairwise correlation, so
+    /// low-energy states avoid holding strongly co-moving assets together. Pairs whose |correlation|
+    /// is at most `min_abs` are dropped to keep the circuit sparse.
+    #[allow(dead_code)]
+    pub fn from_correlation(correlation: &[Vec<f64>], p: usize, min_abs: f64) -> Self {
+        let n = correlation.len();
+        let edges = (0..n)
+            .flat_map(|u| (u + 1..n).map(move |v| (u, v)))
+            .filter_map(|(u, v)| {
+                let weight = correlation[u].get(v).copied()?;
+                (weight.abs() > min_abs).then_some((u, v, weight))
+            })
+            .collect();
+        Self::new(n, edges, p)
+    }
+
+    /// The unit-weight ring qaoa_strategy.generate_qaoa_circuit builds when given only a depth
+    pub fn ring(num_qubits: usize, p: usize) -> Self {
+        let edges = (0..num_qubits).map(|u| (u, (u + 1) % num_qubits, 1.0)).collect();
+        Self::new(num_qubits, edges, p)
+    }
+
+    /// Cost `sum w_uv z_u z_v` of an assignment, with z = +1 for false and -1 for true
+    pub fn energy(&self, assignment: &[bool]) -> f64 {
+        let spin = |q: usize| if assignment[q] { -1.0 } else { 1.0 };
+        self.edges.iter().map(|&(u, v, w)| w * spin(u) * spin(v)).sum()
+    }
+
+    /// Classical stand-in for QAOA: greedy single-bit-flip descent from all-false, taking the
+    /// most improving flip until none improves. Finds a local minimum (the optimum on rings).
+    pub fn greedy_solve(&self) -> (Vec<bool>, f64) {
+        let mut assignment = vec![false; self.num_qubits];
+        let mut energy = self.energy(&assignment);
+        loop {
+            let best = (0..self.num_qubits)
+                .map(|q| {
+                    assignment[q] = !assignment[q];
+                    let flipped = self.energy(&assignment);
+                    assignment[q] = !assignment[q];
+                    (q, flipped)
+                })
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+            match best {
+                Some((q, flipped)) if flipped < energy => {
+                    assignment[q] = !assignment[q];
+                    energy = flipped;
+                }
+                _ => return (assignment, energy),
+            }
+        }
+    }
+
+    /// The ansatz qaoa_strategy's fallback compiler emits for this problem: H on every qubit, then
+    /// per layer a cx-rz-cx cost gate for each edge (with an X-X echo on the idle qubits when
+    /// `dynamical_decoupling`) and the RX mixer, then measurement
+    pub fn circuit(&self, dynamical_decoupling: bool) -> Circuit {
+        let mut circuit = Circuit::new(self.num_qubits);
+        let all = 0..self.num_qubits;
+        all.clone().for_each(|q| circuit.push(Gate::H(q)));
+        for step in 0..self.p {
+            for &(u, v, weight) in &self.edges {
+                let gamma = if weight == 1.0 { format!("gamma_{}", step) } else { format!("{:?}*gamma_{}", weight, step) };
+                circuit.push(Gate::Cx(u, v));
+                circuit.push(Gate::Rz(v, gamma));
+                circuit.push(Gate::Cx(u, v));
+                if dynamical_decoupling {
+                    for idle in all.clone().filter(|&q| q != u && q != v) {
+                        circuit.push(Gate::X(idle));
+                        circuit.push(Gate::X(idle));
+                    }
+                }
+            }
+            all.clone().for_each(|q| circuit.push(Gate::Rx(q, format!("2*beta_{}", step))));
+        }
+        all.for_each(|q| circuit.push(Gate::Measure(q)));
+        circuit
+    }
+
+    /// Rejects problems Python would choke on: out-of-range or repeated endpoints, non-finite weights, p = 0
+    #[allow(dead_code)]
+    pub fn validate(&self) -> Result<(), InteropError> {
+        let invalid = |reason: String| Err(InteropError::InvalidArgument(format!("QAOA problem: {}", reason)));
+        if self.num_qubits == 0 {
+            return invalid("no qubits".to_string());
+        }
+        if self.p == 0 {
+            return invalid("depth p must be at least 1".to_string());
+        }
+        for &(u, v, weight) in &self.edges {
+            if u >= self.num_qubits || v >= self.num_qubits {
+                return invalid(format!("edge ({}, {}) outside {} qubits", u, v, self.num_qubits));
+            }
+            if u == v {
+                return invalid(format!("self-loop on qubit {}", u));
+            }
+            if !weight.is_finite() {
+                return invalid(format!("edge ({}, {}) has weight {}", u, v, weight));
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,11 +1,84 @@
+use crate::interop::CalibrationData;
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet, VecDeque};
 use std::fs;
-use std::path::Path;
-use tracing::{info, error};
+use std::fmt;
+use thiserror::Error;
+use tracing::{debug, info, warn};
 
-#[derive(Debug, Deserialize, Clone)]
+/// Traversal cap for path queries; keeps pathological graphs from exploding the search
+#[allow(dead_code)]
+const MAX_PATH_DEPTH: usize = 16;
+// Hardware node property holding the learned per-depth job outcomes (see `DepthOutcomes`)
+const DEPTH_OUTCOMES_KEY: &str = "depth_outcomes";
+// Jobs a depth needs on record before its failure rate can move the recommendation
+const MIN_OUTCOMES: u64 = 5;
+// A depth is stepped down from once its failure rate is confidently above this
+const FAILURE_CLUSTER_RATE: f64 = 0.5;
+// One-sided ~97.5% normal quantile for that confidence bound
+const FAILURE_CONFIDENCE_Z: f64 = 1.96;
+
+/// Job outcomes observed at one QAOA depth on one device: the failure rate is the running mean
+/// of 0/1 outcomes, with Welford's M2 so its spread is known without keeping the history
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct DepthOutcomes {
+    pub jobs: u64,
+    pub failure_rate: f64,
+    m2: f64, // Sum of squared deviations from `failure_rate`
+}
+
+impl DepthOutcomes {
+    pub fn record(&mut self, failed: bool) {
+        let x = if failed { 1.0 } else { 0.0 };
+        self.jobs += 1;
+        let delta = x - self.failure_rate;
+        self.failure_rate += delta / self.jobs as f64;
+        self.m2 += delta * (x - self.failure_rate);
+    }
+
+    /// Sample variance of the 0/1 outcomes
+    pub fn variance(&self) -> f64 {
+        if self.jobs < 2 { 0.0 } else { self.m2 / (self.jobs - 1) as f64 }
+    }
+
+    /// Failures cluster here: enough jobs, and the failure rate's lower confidence bound is above
+    /// `FAILURE_CLUSTER_RATE`, so a short unlucky streak does not cost a depth level
+    pub fn failures_cluster(&self) -> bool {
+        let lower = self.failure_rate - FAILURE_CONFIDENCE_Z * (self.variance() / self.jobs.max(1) as f64).sqrt();
+        self.jobs >= MIN_OUTCOMES && lower > FAILURE_CLUSTER_RATE
+    }
+}
+
+/// An edge endpoint that names no node in the graph
+#[derive(Debug, Clone, PartialEq)]
+pub struct DanglingRef {
+    pub source: String,
+    pub target: String,
+    pub relationship: String,
+    pub missing: String,
+}
+
+impl fmt::Display for DanglingRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} --[{}]--> {} (missing '{}')", self.source, self.relationship, self.target, self.missing)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum KnowledgeError {
+    #[error("cannot read KG file {path}: {source}")]
+    Io { path: String, source: std::io::Error },
+    #[error("invalid KG JSON in {path}: {source}")]
+    Parse { path: String, source: serde_json::Error },
+    #[error("{} dangling edge(s): {}", .0.len(), .0.iter().map(|d| d.to_string()).collect::<Vec<_>>().join("; "))]
+    DanglingEdges(Vec<DanglingRef>),
+    #[error("edge {from} --[{relationship}]--> {to} has weight {weight}; weights must be non-negative numbers")]
+    InvalidWeight { from: String, to: String, relationship: String, weight: serde_json::Value },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Node {
     pub id: String,
     #[serde(rename = "type")]
@@ -14,7 +87,7 @@ pub struct Node {
     pub properties: HashMap<String, serde_json::Value>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Edge {
     pub source: String,
     pub target: String,
@@ -22,97 +95,472 @@ pub struct Edge {
     pub properties: HashMap<String, serde_json::Value>,
 }
 
-#[derive(Debug, Deserialize)]
+impl Edge {
+    /// Traversal cost from `properties["weight"]` (e.g. transpilation cost); None when absent
+    #[allow(dead_code)]
+    pub fn weight(&self) -> Option<f64> {
+        self.properties.get("weight").and_then(|w| w.as_f64())
+    }
+
+    /// The recorded weight if `cheapest_path` cannot use it (not a number, or negative); None when
+    /// the weight is usable or absent (unweighted edges cost 1)
+    fn invalid_weight(&self) -> Option<&serde_json::Value> {
+        self.properties.get("weight").filter(|w| !w.as_f64().is_some_and(|x| x >= 0.0))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct KnowledgeGraph {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
     pub nodes: Vec<Node>,
     pub edges: Vec<Edge>,
 }
 
 pub struct QuantumKnowledge {
+    metadata: Option<serde_json::Value>, // Carried through to `save` untouched apart from the totals
     pub nodes: HashMap<String, Node>,
     pub edges_by_source: HashMap<String, Vec<Edge>>,
+    #[allow(dead_code)]
+    pub edges_by_target: HashMap<String, Vec<Edge>>,
 }
 
 impl QuantumKnowledge {
-    pub fn new(path: &str) -> Option<Self> {
+    /// Loads and validates a graph: every edge must connect two known nodes. Duplicate node ids
+    /// are tolerated (the last definition wins) but logged.
+    pub fn new(path: &str) -> Result<Self, KnowledgeError> {
         info!("Loading Quantum Knowledge Graph from: {}", path);
-        let content = match fs::read_to_string(path) {
-            Ok(c) => c,
-            Err(e) => {
-                error!("Failed to read KG file: {}", e);
-                return None;
-            }
-        };
-
-        let kg: KnowledgeGraph = match serde_json::from_str(&content) {
-            Ok(k) => k,
-            Err(e) => {
-                error!("Failed to parse KG JSON: {}", e);
-                return None;
-            }
-        };
+        let content = fs::read_to_string(path)
+            .map_err(|source| KnowledgeError::Io { path: path.to_string(), source })?;
+        let kg: KnowledgeGraph = serde_json::from_str(&content)
+            .map_err(|source| KnowledgeError::Parse { path: path.to_string(), source })?;
+        Self::from_graph(kg)
+    }
 
+    /// Validates and indexes an already-parsed graph, as `new` does after reading the file
+    pub fn from_graph(kg: KnowledgeGraph) -> Result<Self, KnowledgeError> {
         let mut nodes_map = HashMap::new();
         for node in kg.nodes {
-            nodes_map.insert(node.id.clone(), node);
+            if let Some(previous) = nodes_map.insert(node.id.clone(), node) {
+                warn!("KG: Duplicate node id '{}'; keeping the last definition.", previous.id);
+            }
         }
 
+        let dangling: Vec<DanglingRef> = kg.edges.iter()
+            .flat_map(|e| [&e.source, &e.target].into_iter()
+                .filter(|id| !nodes_map.contains_key(id.as_str()))
+                .map(|id| DanglingRef {
+                    source: e.source.clone(),
+                    target: e.target.clone(),
+                    relationship: e.relationship.clone(),
+                    missing: id.clone(),
+                }))
+            .collect();
+        if !dangling.is_empty() {
+            return Err(KnowledgeError::DanglingEdges(dangling));
+        }
+        // Dijkstra is only correct without negative costs
+        if let Some((edge, weight)) = kg.edges.iter().find_map(|e| e.invalid_weight().map(|w| (e, w))) {
+            return Err(KnowledgeError::InvalidWeight {
+                from: edge.source.clone(),
+                to: edge.target.clone(),
+                relationship: edge.relationship.clone(),
+                weight: weight.clone(),
+            });
+        }
+
+        // Edges are small (ids + a few properties), so the reverse index simply holds clones
         let mut edges_map: HashMap<String, Vec<Edge>> = HashMap::new();
+        let mut incoming_map: HashMap<String, Vec<Edge>> = HashMap::new();
+        let edge_count = kg.edges.len();
         for edge in kg.edges {
+            incoming_map.entry(edge.target.clone()).or_default().push(edge.clone());
             edges_map.entry(edge.source.clone()).or_default().push(edge);
         }
 
-        info!("Knowledge Graph Loaded: {} Nodes, {} Edges", nodes_map.len(), edges_map.len());
-        Some(Self {
+        info!("Knowledge Graph Loaded: {} Nodes, {} Edges", nodes_map.len(), edge_count);
+        Ok(Self {
+            metadata: kg.metadata,
             nodes: nodes_map,
             edges_by_source: edges_map,
+            edges_by_target: incoming_map,
         })
     }
 
+    /// Inserts a node, replacing (and returning) any node with the same id. Edges are keyed by id,
+    /// so existing connections stay attached to the replacement.
+    #[allow(dead_code)]
+    pub fn add_node(&mut self, node: Node) -> Option<Node> {
+        self.nodes.insert(node.id.clone(), node)
+    }
+
+    /// Inserts an edge into both indices. Both endpoints must exist and a weight, if any, must be
+    /// non-negative; an edge with the same (source, target, relationship) has its properties
+    /// replaced instead of being duplicated.
+    #[allow(dead_code)]
+    pub fn add_edge(&mut self, edge: Edge) -> bool {
+        if !self.nodes.contains_key(&edge.source) || !self.nodes.contains_key(&edge.target) {
+            warn!("KG: Rejected edge {} --[{}]--> {}: unknown endpoint", edge.source, edge.relationship, edge.target);
+            return false;
+        }
+        if let Some(weight) = edge.invalid_weight() {
+            warn!("KG: Rejected edge {} --[{}]--> {}: invalid weight {}", edge.source, edge.relationship, edge.target, weight);
+            return false;
+        }
+        let same = |e: &Edge| e.source == edge.source && e.target == edge.target && e.relationship == edge.relationship;
+        for index in [
+            self.edges_by_source.entry(edge.source.clone()).or_default(),
+            self.edges_by_target.entry(edge.target.clone()).or_default(),
+        ] {
+            match index.iter_mut().find(|e| same(e)) {
+                Some(existing) => existing.properties = edge.properties.clone(),
+                None => index.push(edge.clone()),
+            }
+        }
+        true
+    }
+
+    /// Sets one property on an existing node (e.g. a fresh "eplg" or "t1" from the calibration
+    /// scanner). Returns false if the node is unknown.
+    pub fn update_node_property(&mut self, id: &str, key: &str, value: serde_json::Value) -> bool {
+        match self.nodes.get_mut(id) {
+            Some(node) => {
+                node.properties.insert(key.to_string(), value);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Flat node/edge lists rebuilt from the indices in a stable order (nodes by id, edges by
+    /// source, target, relationship), so two versions of a graph diff cleanly. Metadata totals
+    /// are refreshed.
+    pub fn to_graph(&self) -> KnowledgeGraph {
+        let mut nodes: Vec<Node> = self.nodes.values().cloned().collect();
+        nodes.sort_by(|a, b| a.id.cmp(&b.id));
+        let mut edges: Vec<Edge> = self.edges_by_source.values().flatten().cloned().collect();
+        edges.sort_by(|a, b| (&a.source, &a.target, &a.relationship).cmp(&(&b.source, &b.target, &b.relationship)));
+
+        let mut metadata = self.metadata.clone();
+        if let Some(serde_json::Value::Object(meta)) = metadata.as_mut() {
+            meta.insert("total_nodes".to_string(), nodes.len().into());
+            meta.insert("total_edges".to_string(), edges.len().into());
+        }
+        KnowledgeGraph { metadata, nodes, edges }
+    }
+
+    /// Writes `to_graph` back in the schema `new` reads, so successive saves diff cleanly
+    #[allow(dead_code)]
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        let graph = self.to_graph();
+        // Write-then-rename so a crash mid-save never truncates the graph
+        let tmp = format!("{}.tmp", path);
+        fs::write(&tmp, serde_json::to_string_pretty(&graph)?)?;
+        fs::rename(&tmp, path)?;
+        info!("Knowledge Graph Saved: {} Nodes, {} Edges -> {}", graph.nodes.len(), graph.edges.len(), path);
+        Ok(())
+    }
+
+    #[allow(dead_code)]
     pub fn get_node(&self, id: &str) -> Option<&Node> {
         self.nodes.get(id)
     }
 
+    #[allow(dead_code)]
     pub fn get_related(&self, id: &str) -> &[Edge] {
         self.edges_by_source.get(id).map(|v| v.as_slice()).unwrap_or(&[])
     }
+
+    /// All nodes of a type (case-insensitive, e.g. "hardware"), ordered by id for stable iteration
+    pub fn nodes_of_type(&self, node_type: &str) -> Vec<&Node> {
+        let mut nodes: Vec<&Node> = self.nodes.values()
+            .filter(|n| n.node_type.eq_ignore_ascii_case(node_type))
+            .collect();
+        nodes.sort_by(|a, b| a.id.cmp(&b.id));
+        nodes
+    }
+
+    /// All edges carrying a relationship label, ordered by (source, target)
+    #[allow(dead_code)]
+    pub fn edges_of_relationship(&self, rel: &str) -> Vec<&Edge> {
+        let mut edges: Vec<&Edge> = self.edges_by_source.values()
+            .flatten()
+            .filter(|e| e.relationship == rel)
+            .collect();
+        edges.sort_by(|a, b| (&a.source, &a.target).cmp(&(&b.source, &b.target)));
+        edges
+    }
+
+    /// Incoming edges, e.g. which algorithms target a given hardware node
+    #[allow(dead_code)]
+    pub fn get_incoming(&self, id: &str) -> &[Edge] {
+        self.edges_by_target.get(id).map(|v| v.as_slice()).unwrap_or(&[])
+    }
     
+    #[allow(dead_code)]
     pub fn get_device_specs(&self, id: &str) -> Option<HashMap<String, serde_json::Value>> {
         self.nodes.get(id).map(|n| n.properties.clone())
     }
 
+    /// Breadth-first search for the fewest-hop edge chain from `from` to `to`.
+    /// Returns an empty chain when `from == to`, `None` if unreachable within MAX_PATH_DEPTH hops.
+    #[allow(dead_code)]
+    pub fn shortest_path(&self, from: &str, to: &str) -> Option<Vec<Edge>> {
+        if from == to {
+            return Some(Vec::new());
+        }
+
+        let mut visited: HashSet<&str> = HashSet::from([from]);
+        // Predecessor edge for every discovered node, used to rebuild the chain
+        let mut came_from: HashMap<&str, &Edge> = HashMap::new();
+        let mut queue: VecDeque<(&str, usize)> = VecDeque::from([(from, 0)]);
+
+        while let Some((node, depth)) = queue.pop_front() {
+            if depth >= MAX_PATH_DEPTH {
+                continue;
+            }
+            for edge in self.get_related(node) {
+                if !visited.insert(edge.target.as_str()) {
+                    continue;
+                }
+                came_from.insert(edge.target.as_str(), edge);
+                if edge.target == to {
+                    let mut path = Vec::new();
+                    let mut cursor = to;
+                    while let Some(e) = came_from.get(cursor) {
+                        path.push((*e).clone());
+                        cursor = e.source.as_str();
+                    }
+                    path.reverse();
+                    return Some(path);
+                }
+                queue.push_back((edge.target.as_str(), depth + 1));
+            }
+        }
+        None
+    }
+
+    /// Dijkstra over edge weights (missing weights cost 1.0): the lowest-cost edge chain from `from`
+    /// to `to` and its total cost. Unlike `shortest_path` this prefers cheap mappings over few hops.
+    #[allow(dead_code)]
+    pub fn cheapest_path(&self, from: &str, to: &str) -> Option<(f64, Vec<Edge>)> {
+        let mut best: HashMap<&str, f64> = HashMap::from([(from, 0.0)]);
+        let mut came_from: HashMap<&str, &Edge> = HashMap::new();
+        let mut frontier = BinaryHeap::from([Reverse(Cost(0.0, from))]);
+
+        while let Some(Reverse(Cost(cost, node))) = frontier.pop() {
+            if node == to {
+                let mut path = Vec::new();
+                let mut cursor = to;
+                while let Some(e) = came_from.get(cursor) {
+                    path.push((*e).clone());
+                    cursor = e.source.as_str();
+                }
+                path.reverse();
+                return Some((cost, path));
+            }
+            if best.get(node).is_some_and(|&b| cost > b) {
+                continue; // Stale heap entry
+            }
+            for edge in self.get_related(node) {
+                let weight = edge.weight().unwrap_or_else(|| {
+                    debug!("KG: {} --[{}]--> {} has no weight, using 1.0", edge.source, edge.relationship, edge.target);
+                    1.0
+                });
+                let next = cost + weight;
+                if best.get(edge.target.as_str()).is_none_or(|&b| next < b) {
+                    best.insert(edge.target.as_str(), next);
+                    came_from.insert(edge.target.as_str(), edge);
+                    frontier.push(Reverse(Cost(next, edge.target.as_str())));
+                }
+            }
+        }
+        None
+    }
+
+    /// Explainability: Renders the reasoning chain linking two nodes
+    #[allow(dead_code)]
+    pub fn explain_connection(&self, from: &str, to: &str) -> String {
+        match self.shortest_path(from, to) {
+            Some(path) => self.render_chain(from, to, &path, ""),
+            None => format!("No connection from {} to {} in Knowledge Graph.", from, to),
+        }
+    }
+
+    /// "Reasoning Chain: A -> C" followed by one line per hop, each line prefixed with `indent`
+    fn render_chain(&self, from: &str, to: &str, path: &[Edge], indent: &str) -> String {
+        let label = |id: &str| self.nodes.get(id).map(|n| n.label.clone()).unwrap_or_else(|| id.to_string());
+        let mut desc = format!("{}Reasoning Chain: {} -> {}\n", indent, label(from), label(to));
+        for edge in path {
+            desc.push_str(&format!("{}    {} --[{}]--> {}\n", indent, label(&edge.source), edge.relationship, label(&edge.target)));
+        }
+        desc
+    }
+
+    /// Relaxation time T1 of a device, normalized to microseconds
+    pub fn get_t1_micros(&self, hw_id: &str) -> Option<f64> {
+        self.nodes.get(hw_id)?.properties.get("t1").and_then(parse_micros)
+    }
+
+    /// Dephasing time T2 of a device, normalized to microseconds
+    pub fn get_t2_micros(&self, hw_id: &str) -> Option<f64> {
+        self.nodes.get(hw_id)?.properties.get("t2").and_then(parse_micros)
+    }
+
+    /// Gate duration property of a device (e.g. "gate_time_2q": "660ns"), normalized to nanoseconds.
+    /// Bare numbers are taken as nanoseconds.
+    pub fn get_gate_time_ns(&self, hw_id: &str, key: &str) -> Option<f64> {
+        match self.nodes.get(hw_id)?.properties.get(key)? {
+            serde_json::Value::Number(n) => n.as_f64().filter(|ns| ns.is_finite() && *ns > 0.0),
+            value => parse_micros(value).map(|us| us * 1000.0),
+        }
+    }
+
+    /// Error per layered gate of a device, from "3.7E-3" or 0.0037. None if missing or malformed.
+    pub fn get_eplg(&self, hw_id: &str) -> Option<f64> {
+        let value = self.nodes.get(hw_id)?.properties.get("eplg")?;
+        let eplg = parse_eplg(value);
+        if eplg.is_none() {
+            warn!("KG: Malformed EPLG {} on {}", value, hw_id);
+        }
+        eplg
+    }
+
+    /// Qubit count of a device; revision lists like "133/156" yield the largest
+    pub fn get_qubits(&self, hw_id: &str) -> Option<u64> {
+        self.nodes.get(hw_id)?.properties.get("qubits").map(parse_qubits)
+    }
+
+    /// Writes a calibration snapshot onto a hardware node ("t1", "t2", "eplg", "readout_error",
+    /// "gate_errors"), so the next inference and coherence check use measured values. Fields the
+    /// snapshot lacks keep their recorded value. Returns the number of properties updated.
+    pub fn apply_calibration(&mut self, hw_id: &str, calibration: &CalibrationData) -> usize {
+        let Some(node) = self.nodes.get_mut(hw_id) else {
+            return 0;
+        };
+        let mut updates: Vec<(&str, serde_json::Value)> = Vec::new();
+        // Coherence as suffixed strings: parse_micros would read bare values below 1.0 as seconds
+        if let Some(t1) = calibration.t1_us {
+            updates.push(("t1", format!("{}us", t1).into()));
+        }
+        if let Some(t2) = calibration.t2_us {
+            updates.push(("t2", format!("{}us", t2).into()));
+        }
+        if let Some(eplg) = calibration.eplg {
+            updates.push(("eplg", eplg.into()));
+        }
+        if let Some(readout) = calibration.readout_error {
+            updates.push(("readout_error", readout.into()));
+        }
+        if !calibration.gate_errors.is_empty() {
+            updates.push(("gate_errors", serde_json::to_value(&calibration.gate_errors).unwrap_or_default()));
+        }
+        let updated = updates.len();
+        for (key, value) in updates {
+            node.properties.insert(key.to_string(), value);
+        }
+        updated
+    }
+
+    /// Learned job outcomes of a device by QAOA depth; empty until jobs have been recorded
+    pub fn depth_outcomes(&self, hw_id: &str) -> BTreeMap<usize, DepthOutcomes> {
+        self.nodes.get(hw_id)
+            .and_then(|node| node.properties.get(DEPTH_OUTCOMES_KEY))
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Folds one finished job into the device's "depth_outcomes" property, so the learned
+    /// adjustment survives a `save_outcomes`. Returns false if the device is unknown.
+    pub fn record_job_outcome(&mut self, hw_id: &str, depth: usize, failed: bool) -> bool {
+        let mut outcomes = self.depth_outcomes(hw_id);
+        let entry = outcomes.entry(depth).or_default();
+        entry.record(failed);
+        debug!("KG: {} at depth {}: {} jobs, failure rate {:.2}", hw_id, depth, entry.jobs, entry.failure_rate);
+        let value = serde_json::to_value(&outcomes).unwrap_or_default();
+        self.update_node_property(hw_id, DEPTH_OUTCOMES_KEY, value)
+    }
+
+    /// Writes every device's learned "depth_outcomes" to `path` as `{ hw_id: { depth: outcomes } }`,
+    /// leaving the graph file itself untouched
+    pub fn save_outcomes(&self, path: &str) -> std::io::Result<()> {
+        let outcomes: BTreeMap<&String, BTreeMap<usize, DepthOutcomes>> = self.nodes.keys()
+            .map(|id| (id, self.depth_outcomes(id)))
+            .filter(|(_, outcomes)| !outcomes.is_empty())
+            .collect();
+        // Write-then-rename, as in `save`
+        let tmp = format!("{}.tmp", path);
+        fs::write(&tmp, serde_json::to_string_pretty(&outcomes)?)?;
+        fs::rename(&tmp, path)
+    }
+
+    /// Restores outcomes written by `save_outcomes` onto their devices; a missing file means none
+    /// were learned yet. Devices no longer in the graph are skipped. Returns the devices restored.
+    pub fn load_outcomes(&mut self, path: &str) -> Result<usize, KnowledgeError> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(source) => return Err(KnowledgeError::Io { path: path.to_string(), source }),
+        };
+        let outcomes: BTreeMap<String, BTreeMap<usize, DepthOutcomes>> = serde_json::from_str(&content)
+            .map_err(|source| KnowledgeError::Parse { path: path.to_string(), source })?;
+        let mut restored = 0;
+        for (hw_id, by_depth) in outcomes {
+            let value = serde_json::to_value(&by_depth).unwrap_or_default();
+            if self.update_node_property(&hw_id, DEPTH_OUTCOMES_KEY, value) {
+                restored += 1;
+            } else {
+                warn!("KG: Skipping learned outcomes for unknown device {}", hw_id);
+            }
+        }
+        Ok(restored)
+    }
+
+    /// Steps `depth` down past every depth at which this device's jobs have been failing
+    /// (`DepthOutcomes::failures_cluster`); depth 1 is the floor
+    fn adjusted_depth(&self, hw_id: &str, depth: usize) -> usize {
+        let outcomes = self.depth_outcomes(hw_id);
+        let mut adjusted = depth;
+        while adjusted > 1 && outcomes.get(&adjusted).is_some_and(DepthOutcomes::failures_cluster) {
+            adjusted -= 1;
+        }
+        if adjusted != depth {
+            info!("Inference: Failures cluster at depth {} on {}, stepping down to {}", depth, hw_id, adjusted);
+        }
+        adjusted
+    }
+
     /// INFERENCE ENGINE: Determines optimal Algorithm parameters based on Hardware Constraints
-    /// Uses Knowledge Graph (EPLG) to set QAOA Depth (p)
+    /// Uses Knowledge Graph (EPLG) to set QAOA Depth (p), lowered where recorded jobs keep failing
     pub fn infer_optimal_strategy(&self, target_hw: &str) -> (String, usize) {
-        let mut depth = 1; // Conservative default
-        let mut strategy = "Standard-QAOA".to_string();
-
-        if let Some(specs) = self.get_device_specs(target_hw) {
-            if let Some(eplg_val) = specs.get("eplg") {
-                // Parse "3.7E-3" or 0.0037
-                let eplg = if let Some(s) = eplg_val.as_str() {
-                     s.parse::<f64>().unwrap_or(0.01)
-                } else {
-                     eplg_val.as_f64().unwrap_or(0.01)
-                };
-
-                // Semantic Rule: "High Fidelity Hardware allows Deeper Circuits"
-                // Thresholds derived from literature (kb)
-                if eplg < 1e-3 {
-                    depth = 4; // High Precision
-                    strategy = "Deep-QAOA (High-Fi)".to_string();
-                } else if eplg < 5e-3 {
-                    depth = 2; // Balanced (IBM Heron range: 3.7e-3)
-                    strategy = "Balanced-QAOA".to_string();
-                } else {
-                    depth = 1; // NISQ Safe
-                    strategy = "Shallow-QAOA (NISQ)".to_string();
-                }
+        match self.get_eplg(target_hw) {
+            Some(eplg) => {
+                let (strategy, depth) = strategy_for_eplg(eplg);
+                (strategy, self.adjusted_depth(target_hw, depth))
             }
+            None => ("Standard-QAOA".to_string(), 1), // Conservative default
         }
-        (strategy, depth)
     }
 
+    /// INFERENCE ENGINE (Auto-Select): Scans every Hardware node with a recorded EPLG and picks
+    /// the lowest-error device, breaking ties on qubit count. Returns (hw_id, strategy, depth).
+    pub fn infer_best_strategy(&self) -> Option<(String, String, usize)> {
+        let best = self.nodes_of_type("hardware")
+            .into_iter()
+            .filter_map(|n| {
+                let eplg = self.get_eplg(&n.id)?;
+                let qubits = n.properties.get("qubits").map(parse_qubits).unwrap_or(0);
+                Some((n, eplg, qubits))
+            })
+            .min_by(|(_, ea, qa), (_, eb, qb)| ea.total_cmp(eb).then(qb.cmp(qa)))?;
+
+        let (node, eplg, _) = best;
+        let (strategy, depth) = strategy_for_eplg(eplg);
+        info!("Inference: Selected {} (EPLG={:.2e}) -> {}", node.id, eplg, strategy);
+        Some((node.id.clone(), strategy, self.adjusted_depth(&node.id, depth)))
+    }
+
+    #[allow(dead_code)]
     pub fn describe_algorithm(&self, algo_id: &str) -> String {
         if let Some(node) = self.nodes.get(algo_id) {
             // ... (rest of function)
@@ -133,8 +581,156 @@ impl QuantumKnowledge {
                      }
                 }
             }
+
+            // How the algorithm reaches each device it can run on, hop by hop
+            for hw in self.nodes_of_type("hardware") {
+                if let Some(path) = self.shortest_path(algo_id, &hw.id).filter(|p| !p.is_empty()) {
+                    desc.push_str(&self.render_chain(algo_id, &hw.id, &path, "  "));
+                }
+            }
             return desc;
         }
         format!("Algorithm {} not found in Knowledge Graph.", algo_id)
     }
 }
+
+/// Normalizes a coherence time to microseconds.
+/// Accepts suffixed strings ("120us", "120µs", "0.12ms", "1.2E-4s", "120000ns") and bare values.
+/// Bare values below 1.0 are taken as seconds (IBM backend properties report SI), otherwise microseconds.
+fn parse_micros(value: &serde_json::Value) -> Option<f64> {
+    let (number, scale) = match value {
+        serde_json::Value::Number(n) => (n.as_f64()?, None),
+        serde_json::Value::String(s) => {
+            let s = s.trim();
+            let units: [(&str, f64); 5] = [("ns", 1e-3), ("us", 1.0), ("µs", 1.0), ("ms", 1e3), ("s", 1e6)];
+            match units.iter().find(|(suffix, _)| s.ends_with(suffix)) {
+                Some((suffix, scale)) => (s[..s.len() - suffix.len()].trim().parse::<f64>().ok()?, Some(*scale)),
+                None => (s.parse::<f64>().ok()?, None),
+            }
+        }
+        _ => return None,
+    };
+
+    let scale = scale.unwrap_or(if number < 1.0 { 1e6 } else { 1.0 });
+    let micros = number * scale;
+    (micros.is_finite() && micros > 0.0).then_some(micros)
+}
+
+/// Parses "3.7E-3" or 0.0037; anything that is not a probability in [0, 1] is rejected
+fn parse_eplg(value: &serde_json::Value) -> Option<f64> {
+    let eplg = match value {
+        serde_json::Value::String(s) => s.trim().parse::<f64>().ok()?,
+        v => v.as_f64()?,
+    };
+    (0.0..=1.0).contains(&eplg).then_some(eplg)
+}
+
+/// Qubit counts appear as numbers or revision lists like "133/156"; the largest revision wins
+fn parse_qubits(value: &serde_json::Value) -> u64 {
+    match value {
+        serde_json::Value::String(s) => s.split('/').filter_map(|q| q.trim().parse().ok()).max().unwrap_or(0),
+        v => v.as_u64().unwrap_or(0),
+    }
+}
+
+/// Semantic Rule: "High Fidelity Hardware allows Deeper Circuits"
+/// Thresholds derived from literature (kb)
+fn strategy_for_eplg(eplg: f64) -> (String, usize) {
+    if eplg < 1e-3 {
+        ("Deep-QAOA (High-Fi)".to_string(), 4) // High Precision
+    } else if eplg < 5e-3 {
+        ("Balanced-QAOA".to_string(), 2) // Balanced (IBM Heron range: 3.7e-3)
+    } else {
+        ("Shallow-QAOA (NISQ)".to_string(), 1) // NISQ Safe
+    }
+}
+
+/// Heap entry for `cheapest_path`, ordered by cost (weights are validated non-negative and finite)
+#[derive(PartialEq)]
+#[allow(dead_code)]
+struct Cost<'a>(f64, &'a str);
+
+impl Eq for Cost<'_> {}
+
+impl PartialOrd for Cost<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Cost<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0).then_with(|| self.1.cmp(other.1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn graph(nodes: Value, edges: Value) -> Result<QuantumKnowledge, KnowledgeError> {
+        QuantumKnowledge::from_graph(serde_json::from_value(json!({ "nodes": nodes, "edges": edges })).unwrap())
+    }
+
+    fn node(id: &str, node_type: &str, properties: Value) -> Value {
+        json!({ "id": id, "type": node_type, "label": id, "properties": properties })
+    }
+
+    #[test]
+    fn t1_strings_normalize_to_microseconds() {
+        let kg = graph(json!([
+            node("hw", "Hardware", json!({ "t1": "150us" })),
+            node("hw-si", "Hardware", json!({ "t1": "1.2E-4" })),
+            node("hw-bare", "Hardware", json!({})),
+        ]), json!([])).unwrap();
+        assert_eq!(kg.get_t1_micros("hw"), Some(150.0));
+        assert!((kg.get_t1_micros("hw-si").unwrap() - 120.0).abs() < 1e-9);
+        assert_eq!(kg.get_t1_micros("hw-bare"), None);
+    }
+
+    #[test]
+    fn describe_algorithm_explains_the_path_to_hardware() {
+        let edge = |s: &str, t: &str, rel: &str| json!({ "source": s, "target": t, "relationship": rel, "properties": {} });
+        let kg = graph(
+            json!([node("qaoa", "Algorithm", json!({})), node("qiskit", "Technology", json!({})), node("heron", "Hardware", json!({}))]),
+            json!([edge("qaoa", "qiskit", "implemented_in"), edge("qiskit", "heron", "targets")]),
+        ).unwrap();
+        let desc = kg.describe_algorithm("qaoa");
+        assert!(desc.contains("Reasoning Chain: qaoa -> heron"), "{}", desc);
+        assert!(desc.contains("qiskit --[targets]--> heron"), "{}", desc);
+    }
+
+    #[test]
+    fn a_node_reached_by_one_edge_is_in_exactly_one_incoming_list() {
+        let edge = json!({ "source": "qaoa", "target": "heron", "relationship": "targets", "properties": {} });
+        let kg = graph(json!([node("qaoa", "Algorithm", json!({})), node("heron", "Hardware", json!({}))]), json!([edge])).unwrap();
+        let lists: Vec<&Vec<Edge>> = kg.edges_by_target.values().filter(|edges| edges.iter().any(|e| e.source == "qaoa")).collect();
+        assert_eq!(lists.len(), 1);
+        assert_eq!(kg.get_incoming("heron").len(), 1);
+        assert_eq!(kg.get_incoming("heron")[0].source, "qaoa");
+        assert!(kg.get_incoming("qaoa").is_empty());
+    }
+
+    #[test]
+    fn a_dangling_edge_fails_loading_and_names_the_missing_node() {
+        let edge = json!({ "source": "qaoa", "target": "osprey", "relationship": "targets", "properties": {} });
+        let Err(e) = graph(json!([node("qaoa", "Algorithm", json!({}))]), json!([edge])) else {
+            panic!("a graph with a dangling edge loaded");
+        };
+        assert!(matches!(&e, KnowledgeError::DanglingEdges(refs) if refs.len() == 1));
+        assert_eq!(e.to_string(), "1 dangling edge(s): qaoa --[targets]--> osprey (missing 'osprey')");
+    }
+
+    #[test]
+    fn eplg_reads_scientific_strings_and_floats_but_not_garbage() {
+        let kg = graph(json!([
+            node("heron", "Hardware", json!({ "eplg": "3.7E-3" })),
+            node("eagle", "Hardware", json!({ "eplg": 0.008 })),
+            node("broken", "Hardware", json!({ "eplg": "n/a" })),
+        ]), json!([])).unwrap();
+        assert_eq!(kg.get_eplg("heron"), Some(3.7e-3));
+        assert_eq!(kg.get_eplg("eagle"), Some(0.008));
+        assert_eq!(kg.get_eplg("broken"), None);
+    }
+}
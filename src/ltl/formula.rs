@@ -0,0 +1,377 @@
+use std::fmt;
+use thiserror::Error;
+
+/// Closed tick window [lo, hi] for metric (bounded) temporal operators
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Interval {
+    pub lo: u64,
+    pub hi: u64,
+}
+
+impl Interval {
+    /// Window as seen one tick later
+    fn shift(self) -> Self {
+        Self { lo: self.lo.saturating_sub(1), hi: self.hi.saturating_sub(1) }
+    }
+}
+
+/// Linear Temporal Logic over SentinelEvent predicates.
+/// `None` intervals are the classic unbounded operators; `Some([lo,hi])` bounds them in ticks.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Ltl {
+    True,
+    False,
+    Atom(String),
+    Not(Box<Ltl>),
+    And(Box<Ltl>, Box<Ltl>),
+    Or(Box<Ltl>, Box<Ltl>),
+    Next(Box<Ltl>),
+    Eventually(Option<Interval>, Box<Ltl>),
+    Globally(Option<Interval>, Box<Ltl>),
+    Until(Option<Interval>, Box<Ltl>, Box<Ltl>),
+}
+
+#[derive(Debug, Error, PartialEq)]
+#[error("LTL parse error at offset {offset}: {message}")]
+pub struct LtlParseError {
+    pub offset: usize,
+    pub message: String,
+}
+
+impl Ltl {
+    /// Parses formulas such as `G(price_below_100 -> F[0,10] hedge)`.
+    /// Operators: `!` `&` `|` `->` `X` `F` `G` `U`, with optional `[lo,hi]` tick bounds on F/G/U.
+    pub fn parse(input: &str) -> Result<Ltl, LtlParseError> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let formula = parser.implies()?;
+        match parser.peek() {
+            None => Ok(formula),
+            Some((offset, tok)) => Err(LtlParseError { offset, message: format!("unexpected '{}'", tok) }),
+        }
+    }
+
+    /// Formula Progression: rewrites the formula into the obligation that must hold from the next
+    /// tick onwards, given which atoms hold now. Reduces to True/False once the verdict is final.
+    pub fn progress(&self, holds: &dyn Fn(&str) -> bool) -> Ltl {
+        match self {
+            Ltl::True => Ltl::True,
+            Ltl::False => Ltl::False,
+            Ltl::Atom(p) => if holds(p) { Ltl::True } else { Ltl::False },
+            Ltl::Not(f) => negate(f.progress(holds)),
+            Ltl::And(a, b) => and(a.progress(holds), b.progress(holds)),
+            Ltl::Or(a, b) => or(a.progress(holds), b.progress(holds)),
+            Ltl::Next(f) => (**f).clone(),
+            Ltl::Eventually(None, f) => or(f.progress(holds), self.clone()),
+            Ltl::Globally(None, f) => and(f.progress(holds), self.clone()),
+            Ltl::Until(None, a, b) => or(b.progress(holds), and(a.progress(holds), self.clone())),
+            Ltl::Eventually(Some(w), f) => {
+                if w.lo > 0 {
+                    return Ltl::Eventually(Some(w.shift()), f.clone());
+                }
+                let rest = if w.hi > 0 { Ltl::Eventually(Some(w.shift()), f.clone()) } else { Ltl::False };
+                or(f.progress(holds), rest)
+            }
+            Ltl::Globally(Some(w), f) => {
+                if w.lo > 0 {
+                    return Ltl::Globally(Some(w.shift()), f.clone());
+                }
+                let rest = if w.hi > 0 { Ltl::Globally(Some(w.shift()), f.clone()) } else { Ltl::True };
+                and(f.progress(holds), rest)
+            }
+            Ltl::Until(Some(w), a, b) => {
+                if w.lo > 0 {
+                    return and(a.progress(holds), Ltl::Until(Some(w.shift()), a.clone(), b.clone()));
+                }
+                let rest = if w.hi > 0 { Ltl::Until(Some(w.shift()), a.clone(), b.clone()) } else { Ltl::False };
+                or(b.progress(holds), and(a.progress(holds), rest))
+            }
+        }
+    }
+}
+
+// Smart constructors: fold constants and drop duplicate conjuncts/disjuncts so progressed
+// formulas stay proportional to the number of live obligations.
+
+fn negate(f: Ltl) -> Ltl {
+    match f {
+        Ltl::True => Ltl::False,
+        Ltl::False => Ltl::True,
+        Ltl::Not(inner) => *inner,
+        other => Ltl::Not(Box::new(other)),
+    }
+}
+
+fn and(a: Ltl, b: Ltl) -> Ltl {
+    match (a, b) {
+        (Ltl::False, _) | (_, Ltl::False) => Ltl::False,
+        (Ltl::True, f) | (f, Ltl::True) => f,
+        (a, b) if contains(&a, &b, true) => a,
+        (a, b) => Ltl::And(Box::new(a), Box::new(b)),
+    }
+}
+
+fn or(a: Ltl, b: Ltl) -> Ltl {
+    match (a, b) {
+        (Ltl::True, _) | (_, Ltl::True) => Ltl::True,
+        (Ltl::False, f) | (f, Ltl::False) => f,
+        (a, b) if contains(&a, &b, false) => a,
+        (a, b) => Ltl::Or(Box::new(a), Box::new(b)),
+    }
+}
+
+/// True if `needle` already appears as an operand of the And-chain (conj) / Or-chain of `hay`
+fn contains(hay: &Ltl, needle: &Ltl, conj: bool) -> bool {
+    if hay == needle {
+        return true;
+    }
+    match (hay, conj) {
+        (Ltl::And(l, r), true) | (Ltl::Or(l, r), false) => contains(l, needle, conj) || contains(r, needle, conj),
+        _ => false,
+    }
+}
+
+impl fmt::Display for Ltl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bound = |w: &Option<Interval>| w.map(|w| format!("[{},{}]", w.lo, w.hi)).unwrap_or_default();
+        match self {
+            Ltl::True => write!(f, "true"),
+            Ltl::False => write!(f, "false"),
+            Ltl::Atom(p) => write!(f, "{}", p),
+            Ltl::Not(a) => write!(f, "!{}", a),
+            Ltl::And(a, b) => write!(f, "({} & {})", a, b),
+            Ltl::Or(a, b) => write!(f, "({} | {})", a, b),
+            Ltl::Next(a) => write!(f, "X {}", a),
+            Ltl::Eventually(w, a) => write!(f, "F{} {}", bound(w), a),
+            Ltl::Globally(w, a) => write!(f, "G{} {}", bound(w), a),
+            Ltl::Until(w, a, b) => write!(f, "({} U{} {})", a, bound(w), b),
+        }
+    }
+}
+
+// --- Parser ---
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(u64),
+    Not,
+    And,
+    Or,
+    Implies,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Ident(s) => write!(f, "{}", s),
+            Token::Number(n) => write!(f, "{}", n),
+            Token::Not => write!(f, "!"),
+            Token::And => write!(f, "&"),
+            Token::Or => write!(f, "|"),
+            Token::Implies => write!(f, "->"),
+            Token::LParen => write!(f, "("),
+            Token::RParen => write!(f, ")"),
+            Token::LBracket => write!(f, "["),
+            Token::RBracket => write!(f, "]"),
+            Token::Comma => write!(f, ","),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, LtlParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some(&(i, c)) = chars.peek() {
+        let token = match c {
+            c if c.is_whitespace() => { chars.next(); continue; }
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            '[' => Token::LBracket,
+            ']' => Token::RBracket,
+            ',' => Token::Comma,
+            '!' | '~' => Token::Not,
+            '&' | '|' => {
+                chars.next();
+                // Accept both `&` and `&&` (and `|` / `||`)
+                if chars.peek().map(|&(_, n)| n) == Some(c) {
+                    chars.next();
+                }
+                tokens.push((i, if c == '&' { Token::And } else { Token::Or }));
+                continue;
+            }
+            '-' => {
+                chars.next();
+                match chars.next() {
+                    Some((_, '>')) => { tokens.push((i, Token::Implies)); continue; }
+                    _ => return Err(LtlParseError { offset: i, message: "expected '->'".to_string() }),
+                }
+            }
+            c if c.is_ascii_digit() => {
+                let mut end = i;
+                while let Some(&(j, d)) = chars.peek() {
+                    if !d.is_ascii_digit() { break; }
+                    end = j + d.len_utf8();
+                    chars.next();
+                }
+                let n = input[i..end].parse().map_err(|_| LtlParseError { offset: i, message: "bound out of range".to_string() })?;
+                tokens.push((i, Token::Number(n)));
+                continue;
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut end = i;
+                while let Some(&(j, d)) = chars.peek() {
+                    if !(d.is_alphanumeric() || d == '_' || d == '.') { break; }
+                    end = j + d.len_utf8();
+                    chars.next();
+                }
+                tokens.push((i, Token::Ident(input[i..end].to_string())));
+                continue;
+            }
+            other => return Err(LtlParseError { offset: i, message: format!("unexpected character '{}'", other) }),
+        };
+        chars.next();
+        tokens.push((i, token));
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent, loosest binding first: `->` (right-assoc) < `|` < `&` < `U` < unary
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<(usize, Token)> {
+        self.tokens.get(self.pos).cloned()
+    }
+
+    fn offset(&self) -> usize {
+        self.tokens.get(self.pos).map(|(o, _)| *o).unwrap_or_else(|| self.tokens.last().map(|(o, _)| o + 1).unwrap_or(0))
+    }
+
+    fn error<T>(&self, message: &str) -> Result<T, LtlParseError> {
+        Err(LtlParseError { offset: self.offset(), message: message.to_string() })
+    }
+
+    fn eat(&mut self, expected: &Token) -> bool {
+        if self.tokens.get(self.pos).map(|(_, t)| t) == Some(expected) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), LtlParseError> {
+        if self.eat(&expected) { Ok(()) } else { self.error(&format!("expected '{}'", expected)) }
+    }
+
+    fn is_keyword(&self, kw: &str) -> bool {
+        matches!(self.tokens.get(self.pos), Some((_, Token::Ident(s))) if s == kw)
+    }
+
+    fn implies(&mut self) -> Result<Ltl, LtlParseError> {
+        let lhs = self.or()?;
+        if self.eat(&Token::Implies) {
+            let rhs = self.implies()?;
+            return Ok(Ltl::Or(Box::new(Ltl::Not(Box::new(lhs))), Box::new(rhs)));
+        }
+        Ok(lhs)
+    }
+
+    fn or(&mut self) -> Result<Ltl, LtlParseError> {
+        let mut lhs = self.and()?;
+        while self.eat(&Token::Or) {
+            lhs = Ltl::Or(Box::new(lhs), Box::new(self.and()?));
+        }
+        Ok(lhs)
+    }
+
+    fn and(&mut self) -> Result<Ltl, LtlParseError> {
+        let mut lhs = self.until()?;
+        while self.eat(&Token::And) {
+            lhs = Ltl::And(Box::new(lhs), Box::new(self.until()?));
+        }
+        Ok(lhs)
+    }
+
+    fn until(&mut self) -> Result<Ltl, LtlParseError> {
+        let lhs = self.unary()?;
+        if self.is_keyword("U") {
+            self.pos += 1;
+            let window = self.interval()?;
+            let rhs = self.until()?;
+            return Ok(Ltl::Until(window, Box::new(lhs), Box::new(rhs)));
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Ltl, LtlParseError> {
+        if self.eat(&Token::Not) {
+            return Ok(Ltl::Not(Box::new(self.unary()?)));
+        }
+        for kw in ["X", "F", "G"] {
+            if self.is_keyword(kw) {
+                self.pos += 1;
+                let window = if kw == "X" { None } else { self.interval()? };
+                let operand = Box::new(self.unary()?);
+                return Ok(match kw {
+                    "X" => Ltl::Next(operand),
+                    "F" => Ltl::Eventually(window, operand),
+                    _ => Ltl::Globally(window, operand),
+                });
+            }
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Ltl, LtlParseError> {
+        match self.peek() {
+            Some((_, Token::LParen)) => {
+                self.pos += 1;
+                let inner = self.implies()?;
+                self.expect(Token::RParen)?;
+                Ok(inner)
+            }
+            Some((_, Token::Ident(name))) if name != "U" => {
+                self.pos += 1;
+                Ok(match name.as_str() {
+                    "true" => Ltl::True,
+                    "false" => Ltl::False,
+                    _ => Ltl::Atom(name),
+                })
+            }
+            Some(_) => self.error("expected a proposition or '('"),
+            None => self.error("unexpected end of formula"),
+        }
+    }
+
+    fn interval(&mut self) -> Result<Option<Interval>, LtlParseError> {
+        if !self.eat(&Token::LBracket) {
+            return Ok(None);
+        }
+        let lo = self.number()?;
+        self.expect(Token::Comma)?;
+        let hi = self.number()?;
+        self.expect(Token::RBracket)?;
+        if lo > hi {
+            return self.error("interval lower bound exceeds upper bound");
+        }
+        Ok(Some(Interval { lo, hi }))
+    }
+
+    fn number(&mut self) -> Result<u64, LtlParseError> {
+        match self.peek() {
+            Some((_, Token::Number(n))) => { self.pos += 1; Ok(n) }
+            _ => self.error("expected a tick count"),
+        }
+    }
+}
@@ -1,11 +1,69 @@
+use crate::clock::{Clock, SystemClock};
 use log::{warn, error, info};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+pub mod formula;
+
+pub use formula::{Ltl, LtlParseError};
+
+// Events seen before a violation, kept for its counterexample trace
+const DEFAULT_TRACE_LEN: usize = 32;
 
 // Abstract Event enum
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
 pub enum SentinelEvent {
     PriceUpdate(f64),
+    #[allow(dead_code)]
+    AssetPriceUpdate(usize, f64), // Multi-asset feeds: (asset index, price)
+    VolatilitySpike(f64), // Instantaneous volatility that crossed the spike threshold
+    #[allow(dead_code)]
     HedgeExecuted,
-    QuantumJobFinished,
+    JobSubmitted(String),
+    JobFinished(String),
+    JobFailed(String),
+    JobCancelled(String), // Cancelled by us or on the QPU; says nothing about the hardware
+    CircuitBreakerOpened,
+    CycleAborted(u64), // Optimization cycle (step) rolled back after a session, submission or ledger failure
+}
+
+impl SentinelEvent {
+    /// Atomic propositions: does the named predicate hold for this event?
+    /// Parametric predicates carry their threshold in the name, e.g. `price_below_100`, `vol_above_0.3`.
+    /// Job liveness, for example: `G(job_submitted -> F[0,200] (job_finished | job_failed))`;
+    /// `job_done` covers every outcome (finished, failed or cancelled) for a BoundedProperty response.
+    /// Per-asset price predicates take an `asset{i}_` prefix, e.g. `asset2_price_below_95`.
+    pub fn satisfies(&self, predicate: &str) -> bool {
+        let threshold = |prefix: &str| predicate.strip_prefix(prefix).and_then(|t| t.parse::<f64>().ok());
+
+        match self {
+            SentinelEvent::PriceUpdate(price) => {
+                if let Some(t) = threshold("price_below_") {
+                    *price < t
+                } else if let Some(t) = threshold("price_above_") {
+                    *price > t
+                } else {
+                    predicate == "price_update"
+                }
+            }
+            SentinelEvent::AssetPriceUpdate(asset, price) => predicate
+                .strip_prefix(&format!("asset{}_", asset))
+                .is_some_and(|p| SentinelEvent::PriceUpdate(*price).satisfies(p)),
+            SentinelEvent::VolatilitySpike(vol) => match threshold("vol_above_") {
+                Some(t) => *vol > t,
+                None => predicate == "vol_spike",
+            },
+            SentinelEvent::HedgeExecuted => predicate == "hedge",
+            SentinelEvent::JobSubmitted(_) => predicate == "job_submitted",
+            SentinelEvent::JobFinished(_) => predicate == "job_finished" || predicate == "job_done",
+            SentinelEvent::JobFailed(_) => predicate == "job_failed" || predicate == "job_done",
+            SentinelEvent::JobCancelled(_) => predicate == "job_cancelled" || predicate == "job_done",
+            SentinelEvent::CircuitBreakerOpened => predicate == "circuit_breaker_opened",
+            SentinelEvent::CycleAborted(_) => predicate == "cycle_aborted",
+        }
+    }
 }
 
 // Property: [](Price < 100 -> <>(Hedge))
@@ -13,49 +71,565 @@ pub enum SentinelEvent {
 // State 0: Normal. If Price < 100 -> State 1 (Obligation).
 // State 1: Obligation. If Hedge -> State 0.
 // If we persist in State 1 too long, it's a "violation" in a practical sense (liveness property).
+//
+// Generalized: every registered BoundedProperty `[](trigger -> <>[0,deadline] response)` owns at most
+// one active Obligation, and all obligations advance independently on each event.
+
+/// How long an armed property may wait for its response
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Deadline {
+    /// Events checked after the trigger; depends on the feed's tick rate
+    Ticks(u64),
+    /// Time on the monitor's clock since the trigger, the same at 50ms ticks and in a replay.
+    /// Expiry is still only noticed when an event is checked.
+    #[allow(dead_code)]
+    WallClock(Duration),
+}
+
+impl std::fmt::Display for Deadline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Deadline::Ticks(ticks) => write!(f, "{} ticks", ticks),
+            Deadline::WallClock(timeout) => write!(f, "{}ms", timeout.as_millis()),
+        }
+    }
+}
+
+/// Bounded-eventually property: whenever `trigger` holds, `response` must follow within `deadline`
+#[derive(Debug, Clone)]
+pub struct BoundedProperty {
+    pub name: String,
+    pub trigger: String,
+    pub response: String,
+    pub deadline: Deadline,
+}
+
+impl BoundedProperty {
+    /// Deadline in ticks
+    pub fn new(name: &str, trigger: &str, response: &str, deadline: u64) -> Self {
+        Self::with_deadline(name, trigger, response, Deadline::Ticks(deadline))
+    }
+
+    /// Deadline in wall-clock time, e.g. "hedge within 500ms"
+    #[allow(dead_code)]
+    pub fn timed(name: &str, trigger: &str, response: &str, timeout: Duration) -> Self {
+        Self::with_deadline(name, trigger, response, Deadline::WallClock(timeout))
+    }
+
+    pub fn with_deadline(name: &str, trigger: &str, response: &str, deadline: Deadline) -> Self {
+        Self {
+            name: name.to_string(),
+            trigger: trigger.to_string(),
+            response: response.to_string(),
+            deadline,
+        }
+    }
+}
+
+/// Side of the threshold that counts as a breach
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoundDirection {
+    Below,
+    #[allow(dead_code)]
+    Above,
+}
+
+/// Danger level for the hedge property: e.g. `Above` 120 reads "price above 120 must be followed by a hedge"
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PriceBound {
+    pub threshold: f64,
+    pub direction: BoundDirection,
+}
+
+impl Default for PriceBound {
+    fn default() -> Self {
+        Self { threshold: 100.0, direction: BoundDirection::Below }
+    }
+}
+
+impl PriceBound {
+    #[allow(dead_code)]
+    pub fn below(threshold: f64) -> Self {
+        Self { threshold, direction: BoundDirection::Below }
+    }
+
+    #[allow(dead_code)]
+    pub fn above(threshold: f64) -> Self {
+        Self { threshold, direction: BoundDirection::Above }
+    }
+
+    /// The equivalent atomic proposition, e.g. `price_above_120`
+    pub fn predicate(&self) -> String {
+        match self.direction {
+            BoundDirection::Below => format!("price_below_{}", self.threshold),
+            BoundDirection::Above => format!("price_above_{}", self.threshold),
+        }
+    }
+}
+
+/// An armed property waiting for its response
+#[derive(Debug, Clone)]
+pub struct Obligation {
+    pub property: String,
+    pub elapsed: u64, // Ticks since obligation started
+    pub deadline: Deadline,
+    pub armed_at: Instant,     // Monitor clock when the trigger held
+    pub elapsed_time: Duration, // Monitor clock since `armed_at`, as of the last checked event
+}
+
+impl Obligation {
+    /// Ticks left on a tick deadline; None for a wall-clock one
+    pub fn ticks_remaining(&self) -> Option<u64> {
+        match self.deadline {
+            Deadline::Ticks(ticks) => Some(ticks.saturating_sub(self.elapsed)),
+            Deadline::WallClock(_) => None,
+        }
+    }
+
+    /// Time left on a wall-clock deadline as of the last checked event; None for a tick one
+    pub fn time_remaining(&self) -> Option<Duration> {
+        match self.deadline {
+            Deadline::Ticks(_) => None,
+            Deadline::WallClock(timeout) => Some(timeout.saturating_sub(self.elapsed_time)),
+        }
+    }
+
+    fn expired(&self) -> bool {
+        match self.deadline {
+            Deadline::Ticks(ticks) => self.elapsed > ticks,
+            Deadline::WallClock(timeout) => self.elapsed_time > timeout,
+        }
+    }
+}
+
+impl Serialize for Obligation {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("Obligation", 5)?;
+        s.serialize_field("property", &self.property)?;
+        s.serialize_field("elapsed", &self.elapsed)?;
+        s.serialize_field("deadline", &self.deadline.to_string())?;
+        s.serialize_field("ticks_remaining", &self.ticks_remaining())?;
+        s.serialize_field("ms_remaining", &self.time_remaining().map(|t| t.as_millis() as u64))?;
+        s.end()
+    }
+}
 
 #[derive(Debug)]
 pub enum MonitorState {
     Safe,
-    PendingHedge(u64), // Ticks since obligation started
+    Pending(Vec<Obligation>),
+}
+
+impl MonitorState {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MonitorState::Safe => "Safe",
+            MonitorState::Pending(_) => "Pending",
+        }
+    }
+
+    /// Ticks until the most urgent tick-deadline obligation expires (None when there is none)
+    pub fn ticks_remaining(&self) -> Option<u64> {
+        match self {
+            MonitorState::Safe => None,
+            MonitorState::Pending(obligations) => obligations.iter().filter_map(Obligation::ticks_remaining).min(),
+        }
+    }
+
+    /// Time until the most urgent wall-clock obligation expires (None when there is none)
+    pub fn time_remaining(&self) -> Option<Duration> {
+        match self {
+            MonitorState::Safe => None,
+            MonitorState::Pending(obligations) => obligations.iter().filter_map(Obligation::time_remaining).min(),
+        }
+    }
+}
+
+/// Dashboard snapshot: `{"state": "Pending", "ticks_remaining": 3, "ms_remaining": null, "obligations": [...]}`
+impl Serialize for MonitorState {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let obligations: &[Obligation] = match self {
+            MonitorState::Safe => &[],
+            MonitorState::Pending(obligations) => obligations,
+        };
+        let mut s = serializer.serialize_struct("MonitorState", 4)?;
+        s.serialize_field("state", self.name())?;
+        s.serialize_field("ticks_remaining", &self.ticks_remaining())?;
+        s.serialize_field("ms_remaining", &self.time_remaining().map(|t| t.as_millis() as u64))?;
+        s.serialize_field("obligations", obligations)?;
+        s.end()
+    }
+}
+
+/// Counterexample for post-mortems: the last events a monitor saw, ending with the one on which
+/// `property` failed. `tick` counts the events checked so far, that one included.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ViolationTrace {
+    pub property: String,
+    pub events: Vec<SentinelEvent>,
+    pub tick: u64,
+}
+
+/// Bounded ring of recently checked events
+#[derive(Debug, Clone)]
+struct EventHistory {
+    events: VecDeque<SentinelEvent>,
+    capacity: usize,
+    ticks: u64,
+}
+
+impl EventHistory {
+    fn new(capacity: usize) -> Self {
+        Self { events: VecDeque::with_capacity(capacity), capacity: capacity.max(1), ticks: 0 }
+    }
+
+    fn record(&mut self, event: &SentinelEvent) {
+        if self.events.len() == self.capacity {
+            self.events.pop_front();
+        }
+        self.events.push_back(event.clone());
+        self.ticks += 1;
+    }
+
+    fn trace(&self, property: &str) -> ViolationTrace {
+        ViolationTrace {
+            property: property.to_string(),
+            events: self.events.iter().cloned().collect(),
+            tick: self.ticks,
+        }
+    }
 }
 
+/// Reported when an obligation outlives its deadline
+#[derive(Debug, Clone)]
+pub struct ViolationInfo {
+    #[allow(dead_code)]
+    pub property: String,
+    #[allow(dead_code)]
+    pub event: SentinelEvent, // The event on which the deadline expired
+    #[allow(dead_code)]
+    pub elapsed: u64,
+    #[allow(dead_code)]
+    pub elapsed_time: Duration,
+    #[allow(dead_code)]
+    pub deadline: Deadline,
+    #[allow(dead_code)]
+    pub trace: ViolationTrace,
+}
+
+/// Mitigation policy invoked on every violation (cancel jobs, trip a breaker, page an operator)
+pub type ViolationHandler = Box<dyn Fn(&ViolationInfo) + Send>;
+
 pub struct SafetyMonitor {
     state: MonitorState,
-    max_ticks_tolerance: u64,
+    properties: Vec<BoundedProperty>,
+    handlers: Vec<ViolationHandler>,
+    history: EventHistory,
+    clock: Arc<dyn Clock>, // Measures wall-clock deadlines
 }
 
 impl SafetyMonitor {
+    /// Default deployment: "hedge within `tolerance` ticks of the price dropping below 100"
     pub fn new(tolerance: u64) -> Self {
+        Self::with_price_bound(PriceBound::default(), tolerance)
+    }
+
+    /// "hedge within `tolerance` ticks of the price breaching `bound`"
+    pub fn with_price_bound(bound: PriceBound, tolerance: u64) -> Self {
+        let mut monitor = Self::empty();
+        monitor.register(BoundedProperty::new("hedge_liveness", &bound.predicate(), "hedge", tolerance));
+        monitor
+    }
+
+    pub fn empty() -> Self {
         Self {
             state: MonitorState::Safe,
-            max_ticks_tolerance: tolerance,
+            properties: Vec::new(),
+            handlers: Vec::new(),
+            history: EventHistory::new(DEFAULT_TRACE_LEN),
+            clock: Arc::new(SystemClock),
         }
     }
 
-    pub fn check(&mut self, event: &SentinelEvent) -> bool {
-        match &mut self.state {
-            MonitorState::Safe => {
-                if let SentinelEvent::PriceUpdate(price) = event {
-                    if *price < 100.0 {
-                        warn!("LTL Monitor: Violation of Precondition (Price < 100). Entering Obligation State.");
-                        self.state = MonitorState::PendingHedge(0);
-                    }
-                }
+    /// Builder: Measure wall-clock deadlines on `clock`, e.g. a `MockClock` advanced by the
+    /// replayed timestamps so a backtest sees the deadlines the live loop would
+    #[allow(dead_code)]
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Builder: Keep the last `len` events for violation traces (default 32)
+    #[allow(dead_code)]
+    pub fn with_trace_len(mut self, len: usize) -> Self {
+        self.history = EventHistory::new(len);
+        self
+    }
+
+    pub fn current_state(&self) -> &MonitorState {
+        &self.state
+    }
+
+    /// Recovery: drops every pending obligation and returns to Safe
+    #[allow(dead_code)]
+    pub fn reset(&mut self) {
+        if let MonitorState::Pending(obligations) = &self.state {
+            warn!("LTL Monitor: Forced reset, discarding {} pending obligations.", obligations.len());
+        }
+        self.state = MonitorState::Safe;
+    }
+
+    /// Registers a callback run at the moment a violation is detected, before `check` returns
+    #[allow(dead_code)]
+    pub fn on_violation(&mut self, handler: ViolationHandler) {
+        self.handlers.push(handler);
+    }
+
+    pub fn register(&mut self, property: BoundedProperty) {
+        info!("LTL Monitor: Registered [](({}) -> <>[0,{}] ({})) as '{}'",
+              property.trigger, property.deadline, property.response, property.name);
+        self.properties.push(property);
+    }
+
+    /// Advances every obligation by one event and returns those that expired on this tick.
+    /// An expired obligation is discharged, so the property can re-arm on its next trigger.
+    pub fn check(&mut self, event: &SentinelEvent) -> Vec<ViolationInfo> {
+        self.history.record(event);
+        let now = self.clock.now();
+        let mut pending = match std::mem::replace(&mut self.state, MonitorState::Safe) {
+            MonitorState::Safe => Vec::new(),
+            MonitorState::Pending(obligations) => obligations,
+        };
+        let mut violations = Vec::new();
+
+        pending.retain_mut(|ob| {
+            let Some(prop) = self.properties.iter().find(|p| p.name == ob.property) else {
+                return false;
+            };
+            if event.satisfies(&prop.response) {
+                info!("LTL Monitor: Obligation '{}' Met ({}). Discharged.", prop.name, prop.response);
+                return false;
             }
-            MonitorState::PendingHedge(ticks) => {
-                if let SentinelEvent::HedgeExecuted = event {
-                    info!("LTL Monitor: Obligation Met (Hedge). Returning to Safe State.");
-                    self.state = MonitorState::Safe;
-                } else {
-                    *ticks += 1;
-                    if *ticks > self.max_ticks_tolerance {
-                        error!("LTL Monitor: SAFETY VIOLATION! Expected Hedge within {} ticks.", self.max_ticks_tolerance);
-                        return false; // Hardware Interrupt Trigger
-                    }
-                }
+            ob.elapsed += 1;
+            ob.elapsed_time = now.saturating_duration_since(ob.armed_at);
+            if ob.expired() {
+                error!("LTL Monitor: SAFETY VIOLATION! '{}' expected {} within {}.",
+                       prop.name, prop.response, ob.deadline);
+                violations.push(ViolationInfo {
+                    property: ob.property.clone(),
+                    event: event.clone(),
+                    elapsed: ob.elapsed,
+                    elapsed_time: ob.elapsed_time,
+                    deadline: ob.deadline,
+                    trace: self.history.trace(&ob.property),
+                });
+                return false; // Hardware Interrupt Trigger
+            }
+            true
+        });
+
+        for prop in &self.properties {
+            let armed = pending.iter().any(|ob| ob.property == prop.name);
+            if !armed && event.satisfies(&prop.trigger) {
+                warn!("LTL Monitor: Precondition '{}' of '{}' holds. Entering Obligation State.", prop.trigger, prop.name);
+                pending.push(Obligation {
+                    property: prop.name.clone(),
+                    elapsed: 0,
+                    deadline: prop.deadline,
+                    armed_at: now,
+                    elapsed_time: Duration::ZERO,
+                });
             }
         }
+
+        if !pending.is_empty() {
+            self.state = MonitorState::Pending(pending);
+        }
+        for violation in &violations {
+            for handler in &self.handlers {
+                handler(violation);
+            }
+        }
+        violations
+    }
+}
+
+/// Generic Runtime Verification Monitor: checks an arbitrary LTL formula over the event stream
+/// by formula progression. Violations are only detectable for safety and bounded-liveness parts;
+/// unbounded `F` stays pending on a finite trace.
+pub struct LtlMonitor {
+    spec: Ltl,
+    current: Ltl,
+    history: EventHistory,
+    last_violation: Option<ViolationTrace>,
+}
+
+impl LtlMonitor {
+    pub fn new(spec: Ltl) -> Self {
+        Self {
+            current: spec.clone(),
+            spec,
+            history: EventHistory::new(DEFAULT_TRACE_LEN),
+            last_violation: None,
+        }
+    }
+
+    /// Builder: Keep the last `len` events for violation traces (default 32)
+    #[allow(dead_code)]
+    pub fn with_trace_len(mut self, len: usize) -> Self {
+        self.history = EventHistory::new(len);
+        self
+    }
+
+    /// Builds a monitor from a config string, e.g. `G(price_below_100 -> F[0,10] hedge)`
+    #[allow(dead_code)]
+    pub fn from_formula(formula: &str) -> Result<Self, LtlParseError> {
+        Ok(Self::new(Ltl::parse(formula)?))
+    }
+
+    pub fn spec(&self) -> &Ltl {
+        &self.spec
+    }
+
+    /// Outstanding obligation after the events seen so far
+    #[allow(dead_code)]
+    pub fn residual(&self) -> &Ltl {
+        &self.current
+    }
+
+    /// Counterexample of the most recent violation, labelled with the formula
+    pub fn last_violation(&self) -> Option<&ViolationTrace> {
+        self.last_violation.as_ref()
+    }
+
+    /// Advances the monitor by one event. Returns false on the tick the property is violated,
+    /// after which the monitor re-arms from the original specification.
+    pub fn check(&mut self, event: &SentinelEvent) -> bool {
+        self.history.record(event);
+        self.current = self.current.progress(&|atom| event.satisfies(atom));
+        if self.current == Ltl::False {
+            error!("LTL Monitor: SAFETY VIOLATION of {} on {:?}", self.spec, event);
+            self.last_violation = Some(self.history.trace(&self.spec.to_string()));
+            self.current = self.spec.clone();
+            return false;
+        }
         true
     }
 }
+
+/// Several named LTL properties checked side by side. Each monitor progresses its own formula,
+/// so one property's violation neither masks nor re-arms another.
+#[derive(Default)]
+pub struct MonitorSet {
+    monitors: Vec<(String, LtlMonitor)>,
+}
+
+impl MonitorSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Default deployment:
+    /// - `hedge_liveness`: a breach of `bound` is hedged within `hedge_deadline` ticks
+    /// - `job_liveness`: every submitted job finishes or fails within 200 ticks
+    /// - `vol_response`: a volatility spike is answered by a job submission within `cycle_interval` ticks
+    pub fn standard(bound: PriceBound, hedge_deadline: u64, cycle_interval: u64) -> Self {
+        let mut set = Self::new();
+        for (name, formula) in [
+            ("hedge_liveness", format!("G({} -> F[0,{}] hedge)", bound.predicate(), hedge_deadline)),
+            ("job_liveness", "G(job_submitted -> F[0,200] job_done)".to_string()),
+            ("vol_response", format!("G(vol_spike -> F[0,{}] job_submitted)", cycle_interval)),
+        ] {
+            let spec = Ltl::parse(&formula).expect("built-in LTL property");
+            set.add(name, LtlMonitor::new(spec));
+        }
+        set
+    }
+
+    pub fn add(&mut self, name: &str, monitor: LtlMonitor) {
+        info!("LTL Monitor: Registered {} as '{}'", monitor.spec(), name);
+        self.monitors.push((name.to_string(), monitor));
+    }
+
+    /// Builder: Parses and adds a property, e.g. from sentinel.toml
+    #[allow(dead_code)]
+    pub fn with_property(mut self, name: &str, formula: &str) -> Result<Self, LtlParseError> {
+        self.add(name, LtlMonitor::from_formula(formula)?);
+        Ok(self)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&LtlMonitor> {
+        self.monitors.iter().find(|(n, _)| n == name).map(|(_, m)| m)
+    }
+
+    /// Counterexample of the named property's most recent violation, labelled with its name
+    pub fn trace(&self, name: &str) -> Option<ViolationTrace> {
+        let trace = self.get(name)?.last_violation()?;
+        Some(ViolationTrace { property: name.to_string(), ..trace.clone() })
+    }
+
+    /// Advances every property by one event: `(name, holds)` per property, in registration order
+    pub fn check(&mut self, event: &SentinelEvent) -> Vec<(String, bool)> {
+        self.monitors
+            .iter_mut()
+            .map(|(name, monitor)| (name.clone(), monitor.check(event)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_expired_obligation_reports_a_violation() {
+        let mut monitor = SafetyMonitor::empty();
+        monitor.register(BoundedProperty::new("hedge_liveness", "price_below_100", "hedge", 3));
+        monitor.register(BoundedProperty::new("job_liveness", "job_submitted", "job_done", 10));
+
+        assert!(monitor.check(&SentinelEvent::PriceUpdate(95.0)).is_empty());
+        assert!(monitor.check(&SentinelEvent::JobSubmitted("job-1".to_string())).is_empty());
+        for _ in 0..2 {
+            assert!(monitor.check(&SentinelEvent::PriceUpdate(101.0)).is_empty());
+        }
+
+        let violations = monitor.check(&SentinelEvent::PriceUpdate(101.0));
+        let names: Vec<&str> = violations.iter().map(|v| v.property.as_str()).collect();
+        assert_eq!(names, ["hedge_liveness"]);
+        match monitor.current_state() {
+            MonitorState::Pending(obligations) => {
+                assert_eq!(obligations.len(), 1);
+                assert_eq!(obligations[0].property, "job_liveness");
+            }
+            MonitorState::Safe => panic!("job_liveness should still be pending"),
+        }
+    }
+
+    #[test]
+    fn an_above_bound_arms_on_high_prices_only() {
+        let mut monitor = SafetyMonitor::with_price_bound(PriceBound::above(120.0), 10);
+        monitor.check(&SentinelEvent::PriceUpdate(80.0));
+        assert!(matches!(monitor.current_state(), MonitorState::Safe));
+        monitor.check(&SentinelEvent::PriceUpdate(125.0));
+        assert!(matches!(monitor.current_state(), MonitorState::Pending(_)));
+    }
+
+    #[test]
+    fn a_violation_trace_ends_with_the_triggering_event() {
+        let mut monitor = SafetyMonitor::new(2).with_trace_len(3);
+        let prices = [101.0, 95.0, 101.0, 102.0, 103.0, 104.0];
+        let (tick, event, violation) = prices.iter().enumerate()
+            .find_map(|(i, &price)| {
+                let event = SentinelEvent::PriceUpdate(price);
+                monitor.check(&event).into_iter().next().map(|v| (i as u64 + 1, event, v))
+            })
+            .expect("the unhedged breach should expire");
+
+        let trace = violation.trace;
+        assert_eq!(trace.events.last(), Some(&event));
+        assert_eq!(trace.events.len(), 3);
+        assert_eq!(trace.tick, tick);
+        let json = serde_json::to_value(&trace).unwrap();
+        assert_eq!(json["property"], trace.property.as_str());
+    }
+}
@@ -1,3 +1,4 @@
+
 mod interop;
 mod sre;
 mod knowledge;
@@ -6,22 +7,34 @@ mod qpu;
 mod ltl;
 mod crypto;
 mod manager;
+mod metrics;
+mod config;
+mod backtest;
+mod cli;
+mod scheduler;
+mod pricing;
+mod payoff;
+mod clock;
 
-use feed::SentinelFeed;
-use qpu::QiskitRuntimeService;
-use ltl::{SafetyMonitor, SentinelEvent};
+use ltl::{MonitorSet, PriceBound, SafetyMonitor, SentinelEvent};
 use crypto::Ledger;
-use interop::InteropNexus;
 use sre::SentinelSRE;
 use manager::QuantumManager; // Architecture Upgrade
-use knowledge::QuantumKnowledge;
+use feed::{tick_channel, TickReceiver};
+use qpu::neutral_atom::NeutralAtomAdapter;
+use qpu::{QiskitRuntimeService, QuantumBackend};
+use config::{SentinelConfig, DEFAULT_CONFIG_PATH};
+use scheduler::CycleScheduler;
+use cli::Cli;
+use clap::Parser;
 use dotenv::dotenv;
-use tracing::{info, warn, error};
-use tokio::sync::mpsc;
-use serde_json::Value;
+use tracing::{debug, error, info, warn};
+use std::sync::Arc;
+use tokio::sync::{mpsc, watch};
+use tokio_util::sync::CancellationToken;
 
-// Real-world program ID would be dynamic or loaded from config
-const PROGRAM_ID: &str = "pricing_iqpe_v1";
+// Instantaneous volatility above which a VolatilitySpike event is emitted
+const VOL_SPIKE_THRESHOLD: f64 = 0.35;
 
 
 // ... (other imports)
@@ -30,60 +43,294 @@ const PROGRAM_ID: &str = "pricing_iqpe_v1";
 async fn main() {
     dotenv().ok();
     tracing_subscriber::fmt::init();
+
+    // Operator subcommands (e.g. `verify-ledger`) run standalone and exit
+    if let Some(command) = Cli::parse().command {
+        std::process::exit(cli::run(command).await);
+    }
     
+    // Operator settings (override the path with SENTINEL_CONFIG)
+    let config_path = std::env::var("SENTINEL_CONFIG").unwrap_or_else(|_| DEFAULT_CONFIG_PATH.to_string());
+    let config = match SentinelConfig::load_or_default(&config_path) {
+        Ok(config) => config,
+        Err(e) => {
+            error!("Config: {}", e);
+            std::process::exit(1);
+        }
+    };
+
     // ARCHITECTURE UPGRADE: Quantum Manager (Orchestrator)
-    let manager = QuantumManager::new("./knowledge_data/quantum_kg.json");
-    let sre = SentinelSRE::new();
+    let sre = SentinelSRE::new().with_job_duration_buckets(&config.runtime.job_duration_buckets);
+    // `backend = "pasqal"` / `"quera"` selects the neutral-atom adapter, any other value an IBM device
+    let qpu: Box<dyn QuantumBackend> = match config.runtime.backend.as_deref() {
+        Some(provider @ ("pasqal" | "quera")) => Box::new(NeutralAtomAdapter::from_env(provider)),
+        _ => Box::new(
+            QiskitRuntimeService::with_token_sources(&config.runtime.token_sources)
+                .with_session_file(&config.runtime.session_file)
+                .with_rate_limit(config.runtime.requests_per_second),
+        ),
+    };
+    let manager = if config.require_knowledge_graph {
+        match QuantumManager::new_strict(&config.knowledge_path) {
+            Ok(manager) => manager,
+            Err(e) => {
+                error!("Knowledge Graph required but not loaded: {}", e);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        QuantumManager::new(&config.knowledge_path)
+    };
+    let mut manager = manager
+        .with_outcomes_file(&config.job_outcomes_path)
+        .with_sre(sre.clone())
+        .with_target(&config.runtime.program_id, config.runtime.backend.as_deref())
+        .with_backend(qpu)
+        .with_max_concurrent_jobs(config.runtime.max_concurrent_jobs)
+        .with_max_pending_jobs(config.runtime.max_pending_jobs)
+        .with_gate_model(config.coherence)
+        .with_simulated_pricing(config.simulate_pricing)
+        .with_greeks_model(config.feed)
+        .with_dry_run(config.dry_run);
     
     info!("Sentinel Hypervisor [ENTERPRISE EDITION] Active.");
+
+    // Probe + Prometheus endpoint: /healthz, /status, /metrics (override with SENTINEL_METRICS_ADDR)
+    let metrics_addr = std::env::var("SENTINEL_METRICS_ADDR").unwrap_or_else(|_| config.metrics_addr.clone());
+    match metrics_addr.parse() {
+        Ok(addr) => {
+            let status_sre = Arc::new(sre.clone());
+            tokio::spawn(async move {
+                if let Err(e) = sre::serve_status(status_sre, addr).await {
+                    warn!("Metrics endpoint stopped: {}", e);
+                }
+            });
+        }
+        Err(e) => warn!("Invalid SENTINEL_METRICS_ADDR '{}': {}", metrics_addr, e),
+    }
     
     // ... (Heston/Feed Logic) ...
-    let mut feed = SentinelFeed::new(); 
-    let mut ledger = Ledger::new("sentinel_ledger.log");
+    let ledger = match Ledger::new(&config.ledger_path) {
+        Ok(ledger) => ledger,
+        Err(e) => {
+            sre.report_failure("ledger", &e.to_string());
+            error!("Ledger {} could not be opened: {}", config.ledger_path, e);
+            std::process::exit(1);
+        }
+    };
+    let mut ledger = ledger
+        .with_max_size(config.ledger_max_bytes)
+        .with_format(config.ledger_format)
+        .with_retry_budget(config.ledger_retry_budget)
+        .with_sre(sre.clone());
+    // `monitor` tracks the hedge obligation countdown for /status; `properties` gives the per-property verdicts
     let mut monitor = SafetyMonitor::new(10); 
-    let (tx, mut rx) = mpsc::channel(32);
+    let mut properties = MonitorSet::standard(PriceBound::default(), 10, config.cadence.optimize_every);
+    let (tx, mut rx) = tick_channel(config.cadence.tick_buffer, config.cadence.overflow);
+    let tx = tx.with_sre(sre.clone());
+    let (shutdown_tx, mut shutdown_rx) = watch::channel(false);
     
-    tokio::spawn(async move {
-        let mut sim_feed = SentinelFeed::new();
-        loop {
-            let p = sim_feed.next_tick();
-            if tx.send(p).await.is_err() { break; }
-            tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+    let mut source = match config.price_source(&sre) {
+        Ok(source) => source,
+        Err(e) => {
+            error!("Feed could not start: {}", e);
+            std::process::exit(1);
         }
+    };
+    let tick_interval = config.tick_interval_ms();
+    let runtime = tokio::runtime::Handle::current();
+    // Sources may block between ticks, so they are read on a blocking thread, off the workers
+    let feed_task = tokio::task::spawn_blocking(move || {
+        while let Some(tick) = source.tick() {
+            let sent = runtime.block_on(async {
+                tokio::select! {
+                    _ = shutdown_rx.changed() => false,
+                    sent = tx.send(tick) => sent.is_ok(),
+                }
+            });
+            if !sent {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(tick_interval));
+        }
+        info!("Feed: Stopped.");
     });
 
-    // ... (Qiskit Service) ...
-    let mut qiskit_service = QiskitRuntimeService::new(); 
+    // Out-of-band events (SRE, QPU) are fed to the LTL monitor alongside market ticks
+    let (event_tx, mut event_rx) = mpsc::unbounded_channel::<SentinelEvent>();
+    manager = manager.with_events(event_tx.clone());
+    sre.on_circuit_open(move |_component| {
+        let _ = event_tx.send(SentinelEvent::CircuitBreakerOpened);
+    });
 
     let mut step = 1;
-    while let Some(price) = rx.recv().await {
+    let mut scheduler = CycleScheduler::from_config(&config.cadence);
+    // Fired by SIGINT: ends the loop, interrupts the cycle in progress through its child token and
+    // cancels the committed jobs the manager's watchers are still waiting on
+    let sigint = CancellationToken::new();
+    manager = manager.with_shutdown(sigint.clone());
+    // The latest cycle's token; a newer cycle cancels it, so a stale cycle stops before its ledger commit
+    let mut cycle: Option<CancellationToken> = None;
+    let on_sigint = sigint.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            info!("Shutdown: SIGINT received.");
+            on_sigint.cancel();
+        }
+    });
+    loop {
+        let Some(tick) = next_tick(&mut rx, &sigint).await else { break };
+        let price = tick.price;
+
+        let mut violated: Vec<String> = Vec::new(); // Properties violated on this tick
+        while let Ok(event) = event_rx.try_recv() {
+            if event == SentinelEvent::CircuitBreakerOpened {
+                // Circuit Breaker -> cancel in-flight QPU jobs so we stop burning QPU seconds
+                let n = manager.qpu().cancel_outstanding().await;
+                warn!("SRE: Circuit opened, cancelled {} in-flight QPU jobs.", n);
+            }
+            manager.record_job_event(&event);
+            monitor.check(&event);
+            violated.extend(violations(&mut properties, &event, &sre));
+        }
+        cancel_if_hung(&manager, &mut violated).await;
         
-        if !sre.check_health() {
-            tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+        // Pauses while a breaker cools down; once it has, the manager's gated QPU / interop call
+        // is the half-open probe that closes (or re-opens) it
+        if !sre.check_health_all() {
+            if !pause(tokio::time::Duration::from_secs(5), &sigint).await {
+                break;
+            }
             continue;
         }
 
+        if tick.vol > VOL_SPIKE_THRESHOLD {
+            let spike = SentinelEvent::VolatilitySpike(tick.vol);
+            monitor.check(&spike);
+            violated.extend(violations(&mut properties, &spike, &sre));
+        }
+
         let event = SentinelEvent::PriceUpdate(price);
-        if !monitor.check(&event) {
-            warn!("LTL Violation: Price {:.2}", price);
-            continue; 
+        monitor.check(&event);
+        violated.extend(violations(&mut properties, &event, &sre));
+        sre.publish_status(price, monitor.current_state());
+        cancel_if_hung(&manager, &mut violated).await;
+        if violated.iter().any(|p| p == "hedge_liveness") {
+            continue; // Unhedged breach: no new exposure until it clears
         }
 
-        // Advanced Workflow
-        if step % 50 == 0 {
-            // 1. Quant Pricing (IQAE) - Direct Interop Call
-            let vol = 0.2; 
-            if let Ok(_) = InteropNexus::generate_pricing_circuit(price, 105.0, vol) {
-                 info!("Quant: IQAE Pricing Complete.");
+        // Advanced Workflow: on cadence, or at once when a vol spike went unanswered
+        if scheduler.should_run(&tick) || violated.iter().any(|p| p == "vol_response") {
+            // 1. Quant Pricing (IQAE) - Interop Call on the blocking pool, so the feed keeps draining
+            // Model ticks carry their own vol; the config loader insists on one for observed feeds
+            let vol = config.option.volatility.unwrap_or(tick.vol);
+            let cancel = sigint.child_token();
+            if let Some(previous) = cycle.replace(cancel.clone()) {
+                previous.cancel();
             }
+            manager.run_pricing(&config.option, &tick, vol, &mut ledger, &cancel).await;
 
-            // 2. Optimization (QAOA) - Delegated to Manager (Actor)
-            manager.run_optimization_cycle(step, price, &mut ledger);
+            // 2. Optimization (QAOA) - Delegated to Manager (Actor); job lifecycle arrives via event_rx
+            manager.run_optimization_cycle(step, tick, &mut ledger, &cancel).await;
+            debug!(target: "audit", "{}", manager.explain_last_cycle());
         }
         
-        if step % 10 == 0 {
+        if step % config.cadence.report_every.max(1) == 0 {
              info!("Market Price: {:.2}", price);
+             if let Some(remaining) = monitor.current_state().ticks_remaining() {
+                 sre.record_metric("ltl", "ticks_remaining", remaining as f64);
+             }
         }
         step += 1;
     }
+
+    // Shutdown: stop the feed, release the billed Runtime session, persist the ledger
+    let _ = shutdown_tx.send(true);
+    let _ = feed_task.await;
+    manager.qpu().close().await;
+    if let Err(e) = ledger.flush() {
+        warn!("Shutdown: Ledger flush failed: {}", e);
+    }
+    info!("Sentinel Hypervisor: Clean exit after {} ticks.", step - 1);
+}
+
+/// Checks `event` against every property and returns the names of those it violated, each
+/// logged with its counterexample trace (JSON, for post-mortems) and counted by the SRE
+fn violations(properties: &mut MonitorSet, event: &SentinelEvent, sre: &SentinelSRE) -> Vec<String> {
+    let failed: Vec<String> = properties.check(event).into_iter().filter(|(_, holds)| !holds).map(|(name, _)| name).collect();
+    for property in &failed {
+        warn!("LTL Violation: '{}' on {:?}", property, event);
+        if let Some(trace) = properties.trace(property) {
+            info!(target: "ltl_trace", "{}", serde_json::to_string(&trace).unwrap_or_default());
+        }
+        sre.record_violation(property, &format!("{:?}", event));
+    }
+    failed
+}
+
+/// job_liveness failed: a job outlived its deadline, so stop paying for the stragglers
+async fn cancel_if_hung(manager: &QuantumManager, violated: &mut Vec<String>) {
+    let before = violated.len();
+    violated.retain(|p| p != "job_liveness");
+    if violated.len() < before {
+        let n = manager.qpu().cancel_outstanding().await;
+        warn!("LTL: Job liveness violated, cancelled {} outstanding QPU jobs.", n);
+    }
+}
+
+/// The next tick, or None once the feed ended or SIGINT fired; a shutdown wins over queued ticks
+async fn next_tick<T>(rx: &mut TickReceiver<T>, sigint: &CancellationToken) -> Option<T> {
+    tokio::select! {
+        biased;
+        _ = sigint.cancelled() => None,
+        tick = rx.recv() => tick,
+    }
+}
+
+/// Sleeps for `duration` unless SIGINT fires first; false if it did
+async fn pause(duration: tokio::time::Duration, sigint: &CancellationToken) -> bool {
+    tokio::select! {
+        _ = sigint.cancelled() => false,
+        _ = tokio::time::sleep(duration) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use feed::OverflowPolicy;
+    use tokio::time::{Duration, Instant};
+
+    #[tokio::test]
+    async fn shutdown_ends_the_loop_within_one_tick() {
+        let tick_interval = Duration::from_millis(200);
+        let (tx, mut rx) = tick_channel::<u64>(8, OverflowPolicy::Block);
+        let feed = tokio::spawn(async move {
+            for n in 0.. {
+                if tx.send(n).await.is_err() {
+                    break;
+                }
+                tokio::time::sleep(tick_interval).await;
+            }
+        });
+        let sigint = CancellationToken::new();
+        let on_sigint = sigint.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            on_sigint.cancel();
+        });
+
+        // The loop's two waits: the next tick and the unhealthy-breaker pause
+        let started = Instant::now();
+        let mut ticks = 0;
+        while next_tick(&mut rx, &sigint).await.is_some() {
+            ticks += 1;
+            if !pause(Duration::from_secs(5), &sigint).await {
+                break;
+            }
+        }
+        assert!(started.elapsed() < tick_interval, "shutdown took {:?}", started.elapsed());
+        assert_eq!(ticks, 1);
+        feed.abort();
+    }
 }
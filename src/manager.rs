@@ -1,62 +1,1009 @@
-use crate::interop::InteropNexus;
-use crate::knowledge::QuantumKnowledge;
-use crate::sre::CoherenceVerifier;
+use crate::config::OptionConfig;
+use crate::interop::{Circuit, InteropCapabilities, InteropNexus, QaoaProblem};
+use crate::pricing::{self, IqaeConfig, PricingProblem, PricingResult};
+use crate::knowledge::{KnowledgeError, QuantumKnowledge};
+use crate::ltl::SentinelEvent;
+use crate::qpu::{JobStatus, JobStatusClient, QiskitRuntimeService, QuantumBackend, SessionGuard};
+use crate::sre::{CoherenceVerdict, CoherenceVerifier, GateModel};
 use crate::sre::SentinelSRE;
 use crate::crypto::Ledger;
-use log::{info, warn, error};
+use crate::feed::greeks::{GreeksEstimator, OptionKind};
+use crate::feed::{HestonParams, MarketTick};
+use crate::feed::sampler::SOBOL_MAX_DIMENSIONS;
+use tracing::{error, info, info_span, warn, Instrument};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio_util::sync::CancellationToken;
+
+// Runtime program executing the DD-protected QAOA hedge circuit
+const QAOA_PROGRAM_ID: &str = "hedge_qaoa_v1";
+// Cadence of the per-job lifecycle watchers
+const JOB_POLL_INTERVAL: Duration = Duration::from_secs(2);
+// Calibration scanner inputs for devices whose graph node lacks them (the scanner's own defaults)
+const DEFAULT_CALIBRATION_EPLG: f64 = 0.01;
+const DEFAULT_CALIBRATION_QUBITS: u64 = 5;
+// Inference fallbacks when the knowledge graph has no answer (or is absent): one QAOA layer,
+// verified against a conservative T1
+const DEFAULT_DEPTH: usize = 1;
+const DEFAULT_T1_MICROS: f64 = 50.0;
+// Monte Carlo paths per revaluation of the `[option]` Greeks; each cycle revalues five times
+const GREEKS_PATHS: usize = 2_000;
+// Jobs allowed in flight at once unless configured otherwise
+pub const DEFAULT_MAX_CONCURRENT_JOBS: usize = 4;
+
+/// What each stage of a cycle produced. `job_id` is set once the circuit reached the QPU;
+/// `error` carries the reason the cycle stopped early, if it did.
+/// `cancelled` means the caller's token stopped it; a job it had submitted was cancelled too.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptimizationOutcome {
+    pub strategy: String,
+    pub depth: usize,
+    pub job_id: Option<String>,
+    pub coherence_ok: bool,
+    pub error: Option<String>,
+    /// Set when QAOA ran on the classical fallback (no qaoa_strategy): the greedy cut's energy
+    pub classical_energy: Option<f64>,
+    pub cancelled: bool,
+}
+
+/// What the last cycle decided and on which evidence, kept for `explain_last_cycle`
+#[derive(Debug, Clone)]
+struct CycleTrace {
+    step: u64,
+    spot: f64,
+    hardware: String,
+    eplg: Option<f64>,
+    t1_us: f64,
+    t1_recorded: bool, // False: the conservative default stood in
+    t2_us: Option<f64>,
+    calibrated: bool,
+    strategy: String,
+    depth: usize,
+    circuit_depth: usize, // Of the generated circuit once parsed, of the expected ansatz before
+    schedule_us: f64,
+    budget_us: f64, // safety_fraction of the binding coherence limit
+    verdict: CoherenceVerdict,
+    qasm: Option<String>, // None: the cycle stopped before a circuit was generated
+    preflight: Option<String>, // Queue check verdict, when one was made
+    outcome: Option<OptimizationOutcome>,
+}
 
 /// Enterprise Architecture: Quantum Manager Actor
 /// Encapsulates Lifecycle: Knowledge -> Inference -> Verification -> Execution -> Ledger
 pub struct QuantumManager {
     kg: Option<QuantumKnowledge>,
+    outcomes_path: Option<String>, // Where learned job outcomes are persisted; None = kept in memory only
     sre: SentinelSRE,
+    qpu: Box<dyn QuantumBackend>, // Provider jobs are dispatched to; IBM Runtime unless chosen at startup
+    twin: Box<dyn QuantumBackend>, // Takes the cycle when the device's queue is too deep
+    max_pending_jobs: Option<u64>, // Queue depth beyond which cycles go to `twin`; None = never check
+    gate_model: GateModel, // Gate times the KG does not record, and the coherence safety fraction
+    simulate_pricing: bool, // Run IQAE on the digital twin even with a hardware backend configured
+    greeks_model: Option<HestonParams>, // Dynamics the `[option]` Greeks are estimated under; None = not estimated
+    session: Option<SessionGuard>, // Deletes the session if the manager is dropped before close_session
+    program_id: String,
+    backend: Option<String>, // None: open sessions on the hardware the KG inference picked
+    events: Option<UnboundedSender<SentinelEvent>>, // Job lifecycle events for the LTL monitor
+    shutdown: CancellationToken, // Cancels committed jobs' watchers; a cycle's own token never reaches past the ledger
+    capabilities: InteropCapabilities, // Python backends found at startup; missing ones use Rust fallbacks
+    job_slots: Arc<Semaphore>, // One permit per in-flight job, held by its watcher until a terminal state
+    throttled_cycles: u64,     // Cycles skipped because every slot was taken
+    aborted_cycles: u64,       // Cycles rolled back at the session, submission or ledger stage
+    queue_fallbacks: u64,      // Cycles routed to the twin by the pre-flight queue check
+    in_flight: HashMap<String, (String, usize)>, // Hardware job id -> (device, depth) until its lifecycle event
+    last_cycle: Option<CycleTrace>,
 }
 
 impl QuantumManager {
+    /// Lenient constructor: a graph that fails to load is logged, and every cycle then runs on
+    /// the inference defaults. Use `new_strict` where a missing graph is a misconfiguration.
     pub fn new(kg_path: &str) -> Self {
-        let kg = QuantumKnowledge::new(kg_path);
+        let kg = QuantumKnowledge::new(kg_path)
+            .map_err(|e| {
+                error!("Failed to load Knowledge Graph: {}", e);
+                warn!("Mgr: Running without a Knowledge Graph. Defaults: Hardware='Unknown', Strategy='Unknown', \
+                       Depth={}, T1={}us, no T2 or EPLG check, the [coherence] gate times; job outcomes are not learned",
+                      DEFAULT_DEPTH, DEFAULT_T1_MICROS);
+            })
+            .ok();
+        Self::with_knowledge(kg)
+    }
+
+    /// Strict constructor: fails with the graph's load error (missing file, bad JSON, dangling
+    /// edges, ...) instead of falling back to the inference defaults
+    pub fn new_strict(kg_path: &str) -> Result<Self, KnowledgeError> {
+        let kg = QuantumKnowledge::new(kg_path)?;
+        Ok(Self::with_knowledge(Some(kg)))
+    }
+
+    fn with_knowledge(kg: Option<QuantumKnowledge>) -> Self {
         let sre = SentinelSRE::new();
-        Self { kg, sre }
+        Self {
+            kg,
+            outcomes_path: None,
+            sre,
+            qpu: Box::new(QiskitRuntimeService::new()),
+            twin: Box::new(QiskitRuntimeService::digital_twin()),
+            max_pending_jobs: None,
+            gate_model: GateModel::default(),
+            simulate_pricing: false,
+            greeks_model: None,
+            session: None,
+            program_id: QAOA_PROGRAM_ID.to_string(),
+            backend: None,
+            events: None,
+            shutdown: CancellationToken::new(),
+            capabilities: InteropNexus::probe(),
+            job_slots: Arc::new(Semaphore::new(DEFAULT_MAX_CONCURRENT_JOBS)),
+            throttled_cycles: 0,
+            aborted_cycles: 0,
+            queue_fallbacks: 0,
+            in_flight: HashMap::new(),
+            last_cycle: None,
+        }
+    }
+
+    /// Builder: Runtime program and (optionally) a fixed backend, e.g. from sentinel.toml
+    pub fn with_target(mut self, program_id: &str, backend: Option<&str>) -> Self {
+        self.program_id = program_id.to_string();
+        self.backend = backend.map(str::to_string);
+        self
+    }
+
+    /// Reports failures into a shared SRE (e.g. the one whose breakers gate the main loop)
+    pub fn with_sre(mut self, sre: SentinelSRE) -> Self {
+        self.sre = sre;
+        self
+    }
+
+    /// Builder: Dispatch to `qpu` (IBM Runtime, a neutral-atom provider, ...) instead of the default Runtime
+    pub fn with_backend(mut self, qpu: Box<dyn QuantumBackend>) -> Self {
+        self.qpu = qpu;
+        self
+    }
+
+    /// Builder: Persist learned job outcomes to `path`, restoring any saved there by an earlier
+    /// run. The knowledge graph file itself is never rewritten.
+    pub fn with_outcomes_file(mut self, path: &str) -> Self {
+        if let Some(graph) = self.kg.as_mut() {
+            match graph.load_outcomes(path) {
+                Ok(0) => {}
+                Ok(devices) => info!("Mgr: Restored learned job outcomes for {} devices from {}", devices, path),
+                Err(e) => warn!("Mgr: Ignoring learned job outcomes in {}: {}", path, e),
+            }
+        }
+        self.outcomes_path = Some(path.to_string());
+        self
+    }
+
+    /// Builder: Emits JobSubmitted / JobFinished / JobFailed / JobCancelled (with the job id) into
+    /// the monitor's event channel; a polling task per submitted job reports its terminal state
+    pub fn with_events(mut self, events: UnboundedSender<SentinelEvent>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Builder: Token that cancels jobs still in flight, e.g. on SIGINT. A committed job outlives
+    /// the cycle that submitted it, so the cycle's token only stops the stages before the ledger.
+    pub fn with_shutdown(mut self, shutdown: CancellationToken) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Builder: Hermetic mode for demos and CI; the backend never touches the network
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        if dry_run {
+            info!(target: "dryrun", "Mgr: Dry-run enabled, QPU submissions are synthetic.");
+        }
+        self.qpu.set_dry_run(dry_run);
+        self.twin.set_dry_run(dry_run);
+        self
+    }
+
+    /// Builder: Before each submission, check the device's queue and run the cycle on the digital
+    /// twin instead when more than `max` jobs are pending (or the device is down), so decisions
+    /// do not stall behind a long queue. None disables the check.
+    pub fn with_max_pending_jobs(mut self, max: Option<u64>) -> Self {
+        self.max_pending_jobs = max;
+        self
+    }
+
+    /// Builder: Simulate-only pricing. Every pricing run goes through the IQAE loop on the digital
+    /// twin, even when quantum_pricing is missing or a hardware backend is configured.
+    pub fn with_simulated_pricing(mut self, simulate: bool) -> Self {
+        self.simulate_pricing = simulate;
+        self
+    }
+
+    /// Builder: Estimate delta, gamma and vega of the `[option]` call on every pricing run, by
+    /// bump-and-revalue Monte Carlo under `model` (e.g. the feed's Heston parameters) started at
+    /// the tick's spot and volatility. Recorded as ("pricing", "delta" / "gamma" / "vega").
+    pub fn with_greeks_model(mut self, model: HestonParams) -> Self {
+        self.greeks_model = Some(model);
+        self
+    }
+
+    /// Builder: Gate times for hardware whose graph node records none, and the share of the
+    /// coherence limit a circuit may use. A safety fraction outside (0, 1] is ignored.
+    pub fn with_gate_model(mut self, mut model: GateModel) -> Self {
+        if !(model.safety_fraction > 0.0 && model.safety_fraction <= 1.0) {
+            warn!("Mgr: Safety fraction {} outside (0, 1], keeping {}.", model.safety_fraction, self.gate_model.safety_fraction);
+            model.safety_fraction = self.gate_model.safety_fraction;
+        }
+        self.gate_model = model;
+        self
+    }
+
+    /// Builder: Override the startup probe, e.g. to force the Rust fallbacks
+    #[allow(dead_code)]
+    pub fn with_capabilities(mut self, capabilities: InteropCapabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Builder: At most `max` jobs in flight; cycles beyond that are skipped, not queued
+    pub fn with_max_concurrent_jobs(mut self, max: usize) -> Self {
+        self.job_slots = Arc::new(Semaphore::new(max.max(1)));
+        self
+    }
+
+    /// Free submission slots right now
+    #[allow(dead_code)]
+    pub fn available_job_slots(&self) -> usize {
+        self.job_slots.available_permits()
     }
 
-    /// The "Magic" Method: Orchestrates the entire Super-Exponential Flow
-    pub fn run_optimization_cycle(&self, step: u64, price: f64, ledger: &mut Ledger) {
+    #[allow(dead_code)]
+    pub fn capabilities(&self) -> InteropCapabilities {
+        self.capabilities
+    }
+
+    /// Prices the `[option]` contract: IQAE circuit generation through quantum_pricing.py, or,
+    /// without it, the Black-Scholes value. On the digital twin, or in simulate-only mode, the IQAE
+    /// loop then runs end to end against the twin (on the Rust model of the contract when no
+    /// circuit could be generated), is cross-checked against Black-Scholes and is signed into the
+    /// ledger. Abandoned without a ledger entry once `cancel` fires.
+    pub async fn run_pricing(&self, option: &OptionConfig, tick: &MarketTick, vol: f64, ledger: &mut Ledger, cancel: &CancellationToken) -> Option<PricingResult> {
+        let spot = tick.price;
+        if !(vol.is_finite() && vol > 0.0) {
+            warn!("Quant: Not pricing at spot {:.2}, volatility must be positive, got {}.", spot, vol);
+            return None;
+        }
+        let problem = PricingProblem::from_config(option, spot, vol);
+        let analytic = problem.black_scholes();
+        self.record_greeks(option, tick, vol, cancel).await;
+        if !self.capabilities.quantum_pricing && !self.simulate_pricing {
+            info!("Quant: Black-Scholes fallback price {:.4} (quantum_pricing unavailable).", analytic);
+            self.sre.record_metric("pricing", "analytic_price", analytic);
+            return None;
+        }
+        let circuit = if self.capabilities.quantum_pricing && !self.sre.check_health("interop") {
+            info!("Quant: Interop circuit breaker open, simulating the IQAE circuit from the Rust model.");
+            None
+        } else if self.capabilities.quantum_pricing {
+            let generation = InteropNexus::generate_pricing_circuit_async(
+                spot, option.strike, vol, option.risk_free_rate, option.dividend_yield, option.time_to_maturity,
+            );
+            match cancel.run_until_cancelled(generation).await {
+                None => {
+                    info!("Quant: Pricing at spot {:.2} cancelled.", spot);
+                    self.sre.abandon_probe("interop");
+                    return None;
+                }
+                Some(Ok(qasm)) => {
+                    info!("Quant: IQAE Pricing Complete.");
+                    self.sre.report_success("interop");
+                    Some(qasm)
+                }
+                Some(Err(e)) => {
+                    self.sre.report_failure("interop", &e.to_string());
+                    return None;
+                }
+            }
+        } else {
+            info!("Quant: quantum_pricing unavailable, simulating the IQAE circuit from the Rust model.");
+            None
+        };
+
+        if !(self.simulate_pricing || self.qpu.is_simulated()) {
+            return None;
+        }
+        // Seeded by the tick, so each cycle's run is its own twin job yet replays identically
+        match pricing::price_on_twin(&problem, circuit, &IqaeConfig::default(), tick.ts.to_bits(), cancel).await {
+            Ok(result) => {
+                self.record_price_check(result.estimate.price, analytic);
+                let estimate = &result.estimate;
+                if let Err(e) = ledger.record_pricing(tick, estimate.price, estimate.half_width(), result.latency_ms, &result.job_id) {
+                    warn!("Quant: Pricing estimate not recorded: {}", e);
+                    self.sre.report_failure("ledger", &e.to_string());
+                }
+                Some(result)
+            }
+            Err(e) if cancel.is_cancelled() => {
+                info!("Quant: {}", e);
+                None
+            }
+            Err(e) => {
+                warn!("Quant: IQAE loop failed: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Greeks of the `[option]` call under the Greeks model, computed on a blocking thread so the
+    /// Monte Carlo does not stall the event loop. Seeded by the tick like the twin pricing run;
+    /// maturities short enough for the Sobol table take quasi-random draws. Nothing is recorded
+    /// once `cancel` fires.
+    async fn record_greeks(&self, option: &OptionConfig, tick: &MarketTick, vol: f64, cancel: &CancellationToken) {
+        let Some(model) = self.greeks_model else {
+            return;
+        };
+        let spot = tick.price;
+        // Burn-in would carry the variance away from the tick's before the first step
+        let params = HestonParams { s0: spot, v0: vol * vol, risk_free_rate: option.risk_free_rate, burn_in: 0, ..model };
+        let steps = (option.time_to_maturity / params.dt).round().max(1.0) as usize;
+        let estimator = GreeksEstimator::new(params, OptionKind::Call, option.strike, steps)
+            .with_paths(GREEKS_PATHS)
+            .with_seed(tick.ts.to_bits())
+            .with_quasi_random(2 * steps <= SOBOL_MAX_DIMENSIONS);
+        match cancel.run_until_cancelled(tokio::task::spawn_blocking(move || estimator.estimate())).await {
+            Some(Ok(greeks)) => {
+                info!("Quant: Greeks at spot {:.2}: delta={:.4} gamma={:.5} vega={:.4}", spot, greeks.delta, greeks.gamma, greeks.vega);
+                self.sre.record_metric("pricing", "delta", greeks.delta);
+                self.sre.record_metric("pricing", "gamma", greeks.gamma);
+                self.sre.record_metric("pricing", "vega", greeks.vega);
+            }
+            Some(Err(e)) => warn!("Quant: Greeks estimation failed: {}", e),
+            None => {}
+        }
+    }
+
+    /// Sanity metric: relative difference of a quantum price estimate from Black-Scholes. Includes
+    /// the grid's discretization error, so it tracks drift rather than proving correctness.
+    pub fn record_price_check(&self, quantum: f64, analytic: f64) {
+        let relative = if analytic.abs() > f64::EPSILON { (quantum - analytic).abs() / analytic.abs() } else { quantum.abs() };
+        info!("Quant: IQAE {:.4} vs Black-Scholes {:.4} ({:.2}% apart).", quantum, analytic, relative * 100.0);
+        self.sre.record_metric("pricing", "iqae_rel_diff", relative);
+    }
+
+    /// Rollback of a cycle that reached the QPU stages: the compensating `aborted` ledger record
+    /// and a CycleAborted event take the place of the transaction, so each decision is audited once
+    fn abort(&mut self, step: u64, tick: &MarketTick, reason: String, ledger: &mut Ledger, outcome: &mut OptimizationOutcome) {
+        if let Err(e) = ledger.record_aborted(step, tick, &reason) {
+            error!("Mgr: Could not record the abort of cycle {}: {}", step, e);
+            self.sre.report_failure("ledger", &e.to_string());
+        }
+        if let Some(events) = &self.events {
+            let _ = events.send(SentinelEvent::CycleAborted(step));
+        }
+        self.aborted_cycles += 1;
+        self.sre.record_metric("qpu", "aborted_cycles", self.aborted_cycles as f64);
+        outcome.error = Some(reason);
+    }
+
+    /// Pulls a fresh calibration for `hw` (seeded with its recorded EPLG and qubit count) and
+    /// writes it into the knowledge graph. False when the scanner is unavailable or failed, in
+    /// which case the graph's recorded specs stand.
+    async fn refresh_calibration(&mut self, hw: &str) -> bool {
+        let Some(graph) = self.kg.as_ref().filter(|_| self.capabilities.calibration) else {
+            return false;
+        };
+        if !self.sre.check_health("interop") {
+            warn!("Mgr: Interop circuit breaker open, using the recorded specs of {}.", hw);
+            return false;
+        }
+        let eplg = graph.get_eplg(hw).unwrap_or(DEFAULT_CALIBRATION_EPLG);
+        let qubits = graph.get_qubits(hw).filter(|&q| q > 0).unwrap_or(DEFAULT_CALIBRATION_QUBITS);
+        let calibration = match InteropNexus::get_backend_calibration_async(hw.to_string(), eplg, qubits).await {
+            Ok(calibration) => {
+                self.sre.report_success("interop");
+                calibration
+            }
+            Err(e) => {
+                warn!("Mgr: Calibration of {} unavailable ({}), using recorded specs.", hw, e);
+                self.sre.report_failure("interop", &e.to_string());
+                return false;
+            }
+        };
+        let updated = self.kg.as_mut().map_or(0, |graph| graph.apply_calibration(hw, &calibration));
+        info!("Mgr: Calibrated {}: T1={:?}us T2={:?}us EPLG={:?} ({} properties updated)",
+              hw, calibration.t1_us, calibration.t2_us, calibration.eplg, updated);
+        updated > 0
+    }
+
+    /// Pre-flight capacity check: true when `device` is too busy (or down) to take this cycle's job.
+    /// An unreadable status lets the submission go ahead.
+    async fn queue_saturated(&mut self, device: &str) -> bool {
+        let Some(max) = self.max_pending_jobs.filter(|_| !self.qpu.is_simulated()) else {
+            return false;
+        };
+        let status = match self.qpu.backend_status(device).await {
+            Ok(Some(status)) => status,
+            Ok(None) => return false,
+            Err(e) => {
+                warn!("Mgr: Queue of '{}' unknown ({}), submitting anyway.", device, e);
+                return false;
+            }
+        };
+        self.sre.record_metric("qpu", "pending_jobs", status.pending_jobs as f64);
+        let verdict = if !status.operational {
+            format!("{} is not operational", device)
+        } else if status.pending_jobs > max {
+            format!("{} has {} pending jobs (limit {})", device, status.pending_jobs, max)
+        } else {
+            self.set_preflight(format!("{} has {} pending jobs, submitted", device, status.pending_jobs));
+            return false;
+        };
+        self.queue_fallbacks += 1;
+        warn!("Mgr: {}, routing the cycle to the digital twin ({} so far).", verdict, self.queue_fallbacks);
+        self.sre.record_metric("qpu", "queue_fallbacks", self.queue_fallbacks as f64);
+        self.set_preflight(format!("{}, routed to the digital twin", verdict));
+        true
+    }
+
+    fn set_preflight(&mut self, verdict: String) {
+        if let Some(trace) = self.last_cycle.as_mut() {
+            trace.preflight = Some(verdict);
+        }
+    }
+
+    /// The backend jobs are dispatched to (e.g. to cancel them when a breaker opens)
+    pub fn qpu(&self) -> &dyn QuantumBackend {
+        self.qpu.as_ref()
+    }
+
+    /// This cycle's backend: the twin when the pre-flight check rerouted it
+    fn target(&self, on_twin: bool) -> &dyn QuantumBackend {
+        if on_twin { self.twin.as_ref() } else { self.qpu.as_ref() }
+    }
+
+    /// The "Magic" Method: Orchestrates the entire Super-Exponential Flow.
+    /// Runs inside a `cycle` span (step, strategy, depth, job_id) with one child span per stage;
+    /// stage failures are recorded as error events on the stage's span.
+    /// Once `cancel` fires the cycle stops at the next stage boundary with a `cancelled` outcome;
+    /// a job submitted but not yet committed is cancelled on the QPU and the cycle rolled back in
+    /// the ledger. Once committed, only the `with_shutdown` token cancels the job.
+    pub async fn run_optimization_cycle(&mut self, step: u64, tick: MarketTick, ledger: &mut Ledger, cancel: &CancellationToken) -> OptimizationOutcome {
+        let span = info_span!("cycle",
+            step,
+            strategy = tracing::field::Empty,
+            depth = tracing::field::Empty,
+            job_id = tracing::field::Empty,
+        );
+        let outcome = self.cycle(step, tick, ledger, cancel).instrument(span.clone()).await;
+        span.record("strategy", outcome.strategy.as_str());
+        span.record("depth", outcome.depth);
+        if let Some(job_id) = &outcome.job_id {
+            span.record("job_id", job_id.as_str());
+        }
+        if let Some(trace) = self.last_cycle.as_mut() {
+            trace.outcome = Some(outcome.clone());
+        }
+        outcome
+    }
+
+    /// Learns from a job-lifecycle event: a hardware job that finished or failed is folded into its
+    /// device's per-depth outcomes in the knowledge graph, which are saved to the outcomes file (see
+    /// `with_outcomes_file`) so inference keeps stepping down from failing depths across restarts.
+    /// Cancelled jobs are forgotten without counting either way. Twin jobs are not tracked.
+    pub fn record_job_event(&mut self, event: &SentinelEvent) {
+        let (job_id, failed) = match event {
+            SentinelEvent::JobFinished(job_id) => (job_id, Some(false)),
+            SentinelEvent::JobFailed(job_id) => (job_id, Some(true)),
+            SentinelEvent::JobCancelled(job_id) => (job_id, None),
+            _ => return,
+        };
+        let Some((hardware, depth)) = self.in_flight.remove(job_id) else {
+            return;
+        };
+        // A cancelled job never reached an outcome, so it says nothing about the depth
+        let (Some(failed), Some(graph)) = (failed, self.kg.as_mut()) else {
+            return;
+        };
+        if graph.record_job_outcome(&hardware, depth, failed) {
+            if let Some(path) = &self.outcomes_path {
+                if let Err(e) = graph.save_outcomes(path) {
+                    warn!("Mgr: Could not save job outcomes to {}: {}", path, e);
+                }
+            }
+        }
+    }
+
+    /// Human-readable justification of the last cycle's decisions, for audit: the hardware and
+    /// its error/coherence figures, the inferred strategy, the coherence verdict with its numbers,
+    /// the QASM check, and the job submitted or the reason the cycle stopped.
+    pub fn explain_last_cycle(&self) -> String {
+        let Some(trace) = &self.last_cycle else {
+            return "No optimization cycle has run yet.".to_string();
+        };
+        let mut desc = format!("Cycle {} (spot {:.2})\n", trace.step, trace.spot);
+        let eplg = trace.eplg.map_or("not recorded".to_string(), |e| format!("{:.4}", e));
+        let t1_source = if trace.t1_recorded { "" } else { " (conservative default, none recorded)" };
+        desc.push_str(&format!("  Hardware: {} (EPLG {}, T1 {:.1}us{})\n", trace.hardware, eplg, trace.t1_us, t1_source));
+        if let Some(t2) = trace.t2_us {
+            desc.push_str(&format!("    T2: {:.1}us\n", t2));
+        }
+        if trace.calibrated {
+            desc.push_str("    Figures from a fresh calibration\n");
+        }
+        desc.push_str(&format!("  Strategy: {} at depth {} (circuit depth {})\n", trace.strategy, trace.depth, trace.circuit_depth));
+        let within = if trace.verdict.is_ok() { "within" } else { "exceeds" };
+        desc.push_str(&format!("  Coherence: {:?} (~{:.3}us schedule {} the {:.3}us budget)\n",
+                               trace.verdict, trace.schedule_us, within, trace.budget_us));
+        desc.push_str(&format!("  QASM: {}\n", trace.qasm.as_deref().unwrap_or("not generated")));
+        if let Some(preflight) = &trace.preflight {
+            desc.push_str(&format!("  Pre-flight: {}\n", preflight));
+        }
+        let result = match &trace.outcome {
+            Some(OptimizationOutcome { job_id: Some(job_id), .. }) => format!("submitted as job {}", job_id),
+            Some(OptimizationOutcome { error: Some(reason), .. }) => format!("stopped: {}", reason),
+            Some(OptimizationOutcome { classical_energy: Some(energy), .. }) => {
+                format!("classical fallback, greedy cut energy {}", energy)
+            }
+            _ => "no job submitted".to_string(),
+        };
+        desc.push_str(&format!("  Result: {}\n", result));
+        desc
+    }
+
+    async fn cycle(&mut self, step: u64, tick: MarketTick, ledger: &mut Ledger, cancel: &CancellationToken) -> OptimizationOutcome {
         info!("--- Cycle {}: Quantum Optimization Triggered ---", step);
         
         // 1. Knowledge Inference (Inference Engine)
+        let inference = info_span!("inference");
+        let best = inference.in_scope(|| self.kg.as_ref().and_then(|graph| graph.infer_best_strategy()));
+        // Calibration -> graph -> inference: measured values replace the chosen device's recorded specs
+        let calibrated = match &best {
+            Some((hw, _, _)) => self.refresh_calibration(hw).instrument(inference.clone()).await,
+            None => false,
+        };
+        let inference = inference.entered();
         // Default to safe values
         let mut strategy = "Unknown".to_string();
-        let mut depth = 1;
-        let mut t1_limit = 50.0; // conservative default
+        let mut depth = DEFAULT_DEPTH;
+        let mut t1_limit = DEFAULT_T1_MICROS;
+        let mut t1_recorded = false;
+        let mut t2_limit = None; // Dephasing only constrains the check when recorded
 
-        if let Some(ref graph) = self.kg {
-            let (strat, d) = graph.infer_optimal_strategy("hw-ibm-heron");
+        let mut gates = self.gate_model;
+        let mut hardware = "Unknown".to_string();
+        let mut eplg = None;
+
+        if let Some((hw, strat, d)) = best {
             strategy = strat;
             depth = d;
-            
-            // Get T1 for verification
-            if let Some(specs) = graph.get_device_specs("hw-ibm-heron") {
-                // Simplified extraction, in real system would parse properly
-                t1_limit = 100.0; // Mocking correct inference from specs
+            if let Some(graph) = self.kg.as_ref().filter(|_| calibrated) {
+                (strategy, depth) = graph.infer_optimal_strategy(&hw);
+            }
+
+            // Get T1 for verification (falls back to the conservative default when not recorded)
+            match self.kg.as_ref().and_then(|graph| graph.get_t1_micros(&hw)) {
+                Some(t1) => {
+                    t1_limit = t1;
+                    t1_recorded = true;
+                }
+                None => warn!("Mgr: No T1 recorded for {}, using conservative {}us", hw, t1_limit),
             }
+            t2_limit = self.kg.as_ref().and_then(|graph| graph.get_t2_micros(&hw));
+            // Gate times from the hardware node ("gate_time_1q" / "gate_time_2q"), configured ones otherwise
+            if let Some(graph) = self.kg.as_ref() {
+                gates.single_qubit_ns = graph.get_gate_time_ns(&hw, "gate_time_1q").unwrap_or(gates.single_qubit_ns);
+                gates.two_qubit_ns = graph.get_gate_time_ns(&hw, "gate_time_2q").unwrap_or(gates.two_qubit_ns);
+            }
+            eplg = self.kg.as_ref().and_then(|graph| graph.get_eplg(&hw));
+            hardware = hw;
         }
         
-        info!("Mgr: Strategy='{}', Depth={}", strategy, depth);
+        info!("Mgr: Hardware='{}', Strategy='{}', Depth={}", hardware, strategy, depth);
+        drop(inference);
+        let mut outcome = OptimizationOutcome {
+            strategy,
+            depth,
+            job_id: None,
+            coherence_ok: false,
+            error: None,
+            classical_energy: None,
+            cancelled: false,
+        };
 
         // 2. Coherence Verification (Formal Verification)
-        if !CoherenceVerifier::verify(depth * 10, t1_limit) { // *10 assuming layers per depth
-             error!("Mgr: Optimization Aborted due to Coherence Physics.");
-             return;
+        let verification = info_span!("verification", hardware = %hardware).entered();
+        // The DD-protected ring ansatz qaoa_strategy will generate, laid out gate by gate
+        let ansatz = QaoaProblem::ring(4, depth).circuit(true);
+        let (layers_1q, layers_2q) = ansatz.critical_layers(&gates);
+        let verdict = CoherenceVerifier::check(layers_1q, layers_2q, t1_limit, t2_limit, &gates);
+        outcome.coherence_ok = verdict.is_ok();
+        self.last_cycle = Some(CycleTrace {
+            step,
+            spot: tick.price,
+            hardware: hardware.clone(),
+            eplg,
+            t1_us: t1_limit,
+            t1_recorded,
+            t2_us: t2_limit,
+            calibrated,
+            strategy: outcome.strategy.clone(),
+            depth,
+            circuit_depth: ansatz.depth(),
+            schedule_us: gates.duration_micros(layers_1q, layers_2q),
+            budget_us: t2_limit.map_or(t1_limit, |t2| t2.min(t1_limit)) * gates.safety_fraction,
+            verdict,
+            qasm: None,
+            preflight: None,
+            outcome: None,
+        });
+        if !outcome.coherence_ok {
+             error!("Mgr: Optimization Aborted due to Coherence Physics ({:?}).", verdict);
+             outcome.error = Some(format!("depth {} exceeds the coherence budget of {} ({:?})", depth, hardware, verdict));
+             return outcome;
+        }
+        drop(verification);
+        if cancel.is_cancelled() {
+            return cancelled(outcome, "verification");
         }
 
         // 3. Execution (Quantum Engine) with Dynamical Decoupling
-        match InteropNexus::generate_qaoa_circuit(depth) {
-            Ok(qasm) => {
-                info!("Mgr: Submitting DD-Protected Circuit to QPU...");
-                self.sre.record_metric("qpu", "latency", 120.0);
-                ledger.record_transaction(price, 0.0, "mgr-job-id");
-            },
-            Err(e) => error!("Mgr: Generation Failed: {}", e)
+        if !self.capabilities.qaoa_strategy {
+            // No circuit to submit: solve the same ring Hamiltonian classically and stop here
+            let (cut, energy) = QaoaProblem::ring(4, depth).greedy_solve();
+            info!("Mgr: qaoa_strategy unavailable, classical greedy cut {:?} (energy {}).", cut, energy);
+            outcome.classical_energy = Some(energy);
+            return outcome;
+        }
+        // Circuit generation is the interop probe of a half-open breaker
+        if !self.sre.check_health("interop") {
+            warn!("Mgr: Interop circuit breaker open, skipping cycle {}.", step);
+            outcome.error = Some("interop circuit breaker open".to_string());
+            return outcome;
+        }
+        // Quota guard: taken before any circuit work, released by the job's watcher
+        let Ok(permit) = self.job_slots.clone().try_acquire_owned() else {
+            self.sre.abandon_probe("interop");
+            self.throttled_cycles += 1;
+            warn!("Mgr: All job slots busy, skipping cycle {} ({} skipped so far).", step, self.throttled_cycles);
+            self.sre.record_metric("qpu", "throttled_cycles", self.throttled_cycles as f64);
+            outcome.error = Some("job concurrency limit reached".to_string());
+            return outcome;
+        };
+        let stage = info_span!("generation", depth);
+        let generation = InteropNexus::generate_qaoa_circuit_async(depth).instrument(stage.clone());
+        let qasm = match cancel.run_until_cancelled(generation).await {
+            None => {
+                self.sre.abandon_probe("interop");
+                return cancelled(outcome, "generation");
+            }
+            Some(Ok(qasm)) => {
+                self.sre.report_success("interop");
+                qasm
+            }
+            Some(Err(e)) => {
+                error!(parent: &stage, "Mgr: Generation Failed: {}", e);
+                self.sre.report_failure("interop", &e.to_string());
+                outcome.error = Some(e.to_string());
+                return outcome;
+            }
+        };
+
+        // Re-check coherence against the circuit actually generated, which may differ from the
+        // expected ansatz (Qiskit emits rzz and barriers)
+        match Circuit::from_qasm(&qasm) {
+            Ok(generated) => {
+                let (layers_1q, layers_2q) = generated.critical_layers(&gates);
+                let verdict = CoherenceVerifier::check(layers_1q, layers_2q, t1_limit, t2_limit, &gates);
+                if let Some(trace) = self.last_cycle.as_mut() {
+                    trace.circuit_depth = generated.depth();
+                    trace.schedule_us = gates.duration_micros(layers_1q, layers_2q);
+                    trace.verdict = verdict;
+                }
+                if !verdict.is_ok() {
+                    error!(parent: &stage, "Mgr: Generated circuit exceeds the coherence budget ({:?}).", verdict);
+                    outcome.coherence_ok = false;
+                    outcome.error = Some(format!("generated circuit (depth {}) exceeds the coherence budget of {} ({:?})",
+                                                 generated.depth(), hardware, verdict));
+                    return outcome;
+                }
+            }
+            Err(e) => warn!(parent: &stage, "Mgr: Generated QASM not parsed ({}), keeping the ansatz estimate.", e),
+        }
+
+        // Local parse before anything touches the (billed) session
+        let stage = info_span!("validation");
+        if self.capabilities.qiskit && !self.sre.check_health("interop") {
+            warn!(parent: &stage, "Mgr: Interop circuit breaker open, not validating cycle {}.", step);
+            outcome.error = Some("interop circuit breaker open".to_string());
+            return outcome;
+        }
+        let validated = if self.capabilities.qiskit {
+            let validation = InteropNexus::validate_qasm_with_qiskit_async(qasm).instrument(stage.clone());
+            let Some(validated) = cancel.run_until_cancelled(validation).await else {
+                self.sre.abandon_probe("interop");
+                return cancelled(outcome, "validation");
+            };
+            if validated.is_ok() {
+                self.sre.report_success("interop");
+            }
+            validated
+        } else {
+            warn!(parent: &stage, "Mgr: Qiskit unavailable, submitting unvalidated QASM.");
+            Ok(true)
+        };
+        let qasm_check = match &validated {
+            Ok(true) if self.capabilities.qiskit => "valid OpenQASM (parsed by Qiskit)".to_string(),
+            Ok(true) => "unvalidated (Qiskit unavailable)".to_string(),
+            Ok(false) => "rejected as invalid OpenQASM".to_string(),
+            Err(e) => format!("validation unavailable ({})", e),
+        };
+        if let Some(trace) = self.last_cycle.as_mut() {
+            trace.qasm = Some(qasm_check);
+        }
+        let rejected = match validated {
+            Ok(true) => None,
+            Ok(false) => Some(format!("generated QAOA circuit (depth {}) is not valid OpenQASM", depth)),
+            Err(e) => Some(format!("QASM validation unavailable: {}", e)),
+        };
+        if let Some(reason) = rejected {
+            error!(parent: &stage, "Mgr: Submission Aborted: {}", reason);
+            self.sre.report_failure("interop", &reason);
+            outcome.error = Some(reason);
+            return outcome;
+        }
+
+        // Everything from pre-flight to submission probes a half-open QPU breaker
+        if !self.sre.check_health("qpu") {
+            warn!("Mgr: QPU circuit breaker open, skipping cycle {}.", step);
+            outcome.error = Some("QPU circuit breaker open".to_string());
+            return outcome;
+        }
+        let backend = self.backend.clone().unwrap_or_else(|| hardware.clone());
+        let preflight = self.queue_saturated(&backend).instrument(info_span!("preflight"));
+        let Some(on_twin) = cancel.run_until_cancelled(preflight).await else {
+            self.sre.abandon_probe("qpu");
+            return cancelled(outcome, "pre-flight");
+        };
+
+        let stage = info_span!("session", on_twin);
+        let target = if on_twin { self.twin.as_mut() } else { self.qpu.as_mut() };
+        match target.prepare(&backend).instrument(stage.clone()).await {
+            Ok(Some(guard)) if !on_twin => self.session = Some(guard),
+            Ok(_) => {}
+            Err(e) => {
+                error!(parent: &stage, "Mgr: Session on '{}' Failed: {}", backend, e);
+                self.sre.report_failure("qpu", &e.to_string());
+                self.abort(step, &tick, format!("session on '{}' failed: {}", backend, e), ledger, &mut outcome);
+                return outcome;
+            }
+        }
+
+        // Session setup is never interrupted halfway (it could leak a billed session), so check here
+        if cancel.is_cancelled() {
+            self.sre.abandon_probe("qpu");
+            self.abort(step, &tick, "cancelled before submission".to_string(), ledger, &mut outcome);
+            return cancelled(outcome, "session");
+        }
+
+        // Market parameter bound into the circuit: log-moneyness against the 100 hedge level
+        let theta = (tick.price / 100.0).ln();
+        let stage = info_span!("submission", theta);
+        info!(parent: &stage, "Mgr: Submitting DD-Protected Circuit to QPU (theta={:.4})...", theta);
+        let started = Instant::now();
+        let job = self.target(on_twin).hedge_job(&self.program_id, theta);
+        let job_id = match self.target(on_twin).submit(job).instrument(stage.clone()).await {
+            Ok(job_id) => {
+                self.sre.report_success("qpu");
+                job_id
+            }
+            Err(e) => {
+                error!(parent: &stage, "Mgr: Submission Failed: {}", e);
+                self.sre.report_failure("qpu", &e.to_string());
+                self.abort(step, &tick, format!("submission failed: {}", e), ledger, &mut outcome);
+                return outcome;
+            }
+        };
+        self.sre.record_metric("qpu", "latency", started.elapsed().as_secs_f64() * 1000.0);
+        // Submission runs to completion so the job id is known and can be cancelled
+        if cancel.is_cancelled() {
+            if let Err(e) = self.target(on_twin).cancel(&job_id).instrument(stage.clone()).await {
+                warn!(parent: &stage, "Mgr: Could not cancel job {}: {}", job_id, e);
+            }
+            self.abort(step, &tick, format!("cancelled after submitting job {}", job_id), ledger, &mut outcome);
+            return cancelled(outcome, "submission");
+        }
+
+        // 4. Ledger: the commit point. Only a recorded job counts as a decision; a job whose entry
+        // could not be kept (written, or buffered for a retry) is cancelled and the cycle rolled back.
+        let stage = info_span!("ledger", job_id = %job_id);
+        if let Err(e) = stage.in_scope(|| ledger.record_transaction(step, &tick, theta, &job_id)) {
+            error!(parent: &stage, "Mgr: Ledger commit for job {} failed: {}", job_id, e);
+            self.sre.report_failure("ledger", &e.to_string());
+            if let Err(cancel) = self.target(on_twin).cancel(&job_id).instrument(stage.clone()).await {
+                warn!(parent: &stage, "Mgr: Could not cancel uncommitted job {}: {}", job_id, cancel);
+            }
+            self.abort(step, &tick, format!("ledger commit for job {} failed: {}", job_id, e), ledger, &mut outcome);
+            return outcome;
+        }
+        if let Some(events) = &self.events {
+            let _ = events.send(SentinelEvent::JobSubmitted(job_id.clone()));
+        }
+        // Without a status endpoint nothing reports completion, so the slot is freed here
+        if let Some(poller) = self.target(on_twin).status_client() {
+            // Twin turnaround is kept apart from hardware so the two distributions can be compared
+            let label = if self.target(on_twin).is_simulated() {
+                "digital_twin".to_string()
+            } else {
+                self.in_flight.insert(job_id.clone(), (hardware, depth));
+                backend
+            };
+            let timer = JobTimer { sre: self.sre.clone(), backend: label, submitted: started };
+            tokio::spawn(watch_job(poller, job_id.clone(), self.events.clone(), timer, permit, self.shutdown.clone()));
+        }
+        outcome.job_id = Some(job_id);
+        outcome
+    }
+}
+
+/// Turnaround of one job, recorded into the SRE's duration histogram when it reaches a terminal state
+struct JobTimer {
+    sre: SentinelSRE,
+    backend: String,
+    submitted: Instant,
+}
+
+/// Marks a cycle stopped by its cancellation token
+fn cancelled(mut outcome: OptimizationOutcome, stage: &str) -> OptimizationOutcome {
+    info!("Mgr: Cycle cancelled during {}.", stage);
+    outcome.cancelled = true;
+    outcome.error.get_or_insert_with(|| format!("cancelled during {}", stage));
+    outcome
+}
+
+/// Polls one job until it reaches a terminal state, then reports it, records its turnaround and
+/// frees its job slot. Exits early if the monitor's channel is gone. Transient polling errors are
+/// logged and retried on the next interval. If `shutdown` fires first the job is cancelled on the
+/// QPU and reported as cancelled.
+async fn watch_job(poller: JobStatusClient, job_id: String, events: Option<UnboundedSender<SentinelEvent>>, timer: JobTimer, _slot: OwnedSemaphorePermit, shutdown: CancellationToken) {
+    loop {
+        let Some(status) = shutdown.run_until_cancelled(poller.status(&job_id)).await else {
+            break;
+        };
+        let event = match status {
+            Ok(JobStatus::Completed) => Some(SentinelEvent::JobFinished(job_id.clone())),
+            Ok(JobStatus::Failed) => Some(SentinelEvent::JobFailed(job_id.clone())),
+            Ok(JobStatus::Cancelled) => Some(SentinelEvent::JobCancelled(job_id.clone())),
+            Ok(JobStatus::Queued | JobStatus::Running) => {
+                if events.as_ref().is_some_and(|e| e.is_closed()) {
+                    return;
+                }
+                None
+            }
+            Err(e) => {
+                warn!("Mgr: Polling job {} failed: {}", job_id, e);
+                None
+            }
+        };
+        if let Some(event) = event {
+            timer.sre.record_job_duration(&timer.backend, timer.submitted.elapsed());
+            if let Some(events) = &events {
+                let _ = events.send(event);
+            }
+            return;
+        }
+        if shutdown.run_until_cancelled(tokio::time::sleep(JOB_POLL_INTERVAL)).await.is_none() {
+            break;
+        }
+    }
+
+    // Cancelled while the job was still in flight
+    match poller.cancel(&job_id).await {
+        Ok(()) => info!("Mgr: Job {} cancelled.", job_id),
+        Err(e) => warn!("Mgr: Could not cancel job {}: {}", job_id, e),
+    }
+    if let Some(events) = &events {
+        let _ = events.send(SentinelEvent::JobCancelled(job_id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interop::stub::StubModule;
+    use crate::qpu::transport::{RuntimeTransport, TransportError};
+    use futures_util::future::BoxFuture;
+    use serde_json::{json, Value};
+    use std::sync::Mutex;
+    use tokio::sync::mpsc;
+
+    /// Runtime whose jobs stay Running until cancelled; records every cancel request
+    #[derive(Default)]
+    struct RunningForever {
+        jobs: Mutex<u64>,
+        cancelled: Mutex<Vec<String>>,
+    }
+
+    impl RuntimeTransport for RunningForever {
+        fn post<'a>(&'a self, path: &'a str, _body: Value) -> BoxFuture<'a, Result<Value, TransportError>> {
+            Box::pin(async move {
+                if let Some(job) = path.strip_prefix("/jobs/").and_then(|p| p.strip_suffix("/cancel")) {
+                    self.cancelled.lock().unwrap().push(job.to_string());
+                    return Ok(Value::Null);
+                }
+                let mut jobs = self.jobs.lock().unwrap();
+                *jobs += 1;
+                Ok(json!({ "id": format!("{}-{}", path.trim_start_matches('/'), jobs) }))
+            })
+        }
+
+        fn get<'a>(&'a self, _path: &'a str) -> BoxFuture<'a, Result<Value, TransportError>> {
+            Box::pin(async { Ok(json!({ "status": "Running" })) })
+        }
+
+        fn delete<'a>(&'a self, _path: &'a str) -> BoxFuture<'a, Result<Value, TransportError>> {
+            Box::pin(async { Ok(Value::Null) })
+        }
+    }
+
+    #[tokio::test]
+    async fn a_committed_job_outlives_the_next_cycle_but_not_shutdown() {
+        let _qaoa = StubModule::install("qaoa_strategy",
+            "def generate_qaoa_circuit(p):\n    return 'OPENQASM 2.0; qreg q[4]; h q[0];'\n");
+        let dir = std::env::temp_dir().join(format!("sentinel-manager-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut ledger = Ledger::new(dir.join("ledger.log").to_str().unwrap()).unwrap();
+
+        let runtime = Arc::new(RunningForever::default());
+        let (events, mut lifecycle) = mpsc::unbounded_channel();
+        let shutdown = CancellationToken::new();
+        let mut manager = QuantumManager::new("/nonexistent/knowledge.json")
+            .with_capabilities(InteropCapabilities { qaoa_strategy: true, ..InteropCapabilities::default() })
+            .with_backend(Box::new(QiskitRuntimeService::digital_twin().with_transport(runtime.clone()).with_rate_limit(0.0)))
+            .with_events(events)
+            .with_shutdown(shutdown.clone());
+        let tick = MarketTick { price: 101.0, vol: 0.2, ts: 1.0 };
+
+        let first = CancellationToken::new();
+        let job_id = manager.run_optimization_cycle(1, tick, &mut ledger, &first).await.job_id.expect("job submitted");
+        // The event loop cancels a cycle's token as the next cycle starts
+        first.cancel();
+        manager.run_optimization_cycle(2, tick, &mut ledger, &CancellationToken::new()).await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(runtime.cancelled.lock().unwrap().is_empty());
+        assert_eq!(lifecycle.try_recv().unwrap(), SentinelEvent::JobSubmitted(job_id.clone()));
+
+        shutdown.cancel();
+        let cancelled = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                match lifecycle.recv().await {
+                    Some(SentinelEvent::JobCancelled(id)) if id == job_id => break,
+                    Some(_) => {}
+                    None => panic!("lifecycle channel closed"),
+                }
+            }
+        });
+        cancelled.await.expect("shutdown cancels the watched job");
+        assert!(runtime.cancelled.lock().unwrap().contains(&job_id));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[tokio::test]
+    async fn each_tick_is_priced_as_its_own_twin_job() {
+        let dir = std::env::temp_dir().join(format!("sentinel-manager-pricing-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut ledger = Ledger::new(dir.join("ledger.log").to_str().unwrap()).unwrap();
+        let manager = QuantumManager::new("/nonexistent/knowledge.json")
+            .with_capabilities(InteropCapabilities::default())
+            .with_simulated_pricing(true);
+        let option = OptionConfig::default();
+
+        let mut job_ids = Vec::new();
+        for ts in [1.0, 2.0, 1.0] {
+            let tick = MarketTick { price: 100.0, vol: 0.2, ts };
+            let result = manager.run_pricing(&option, &tick, 0.2, &mut ledger, &CancellationToken::new()).await.unwrap();
+            job_ids.push(result.job_id);
         }
+        assert_ne!(job_ids[0], job_ids[1]);
+        assert_eq!(job_ids[0], job_ids[2], "a replayed tick reprices identically");
+        std::fs::remove_dir_all(&dir).ok();
     }
 }
@@ -0,0 +1,302 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write as _;
+use std::future::Future;
+use std::net::SocketAddr;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tracing::{info, warn};
+
+// Smoothing of the per-metric EMA: weight of the newest sample
+const EMA_ALPHA: f64 = 0.2;
+// Samples kept per metric for quantiles
+const QUANTILE_WINDOW: usize = 256;
+// Pause after a failed accept, so a persistent error (fd exhaustion) does not spin the loop
+const ACCEPT_BACKOFF: std::time::Duration = std::time::Duration::from_millis(100);
+/// Upper bounds (seconds) of the QPU job turnaround histogram: 1s, 5s, 30s, 2m, 10m
+pub const DEFAULT_JOB_DURATION_BUCKETS: [f64; 5] = [1.0, 5.0, 30.0, 120.0, 600.0];
+
+/// Running aggregate of one (component, metric) series: EMA plus a bounded ring of recent samples
+#[derive(Debug, Default, Clone)]
+pub struct MetricStats {
+    ema: Option<f64>,
+    window: VecDeque<f64>,
+}
+
+impl MetricStats {
+    pub fn observe(&mut self, value: f64) {
+        if !value.is_finite() {
+            return;
+        }
+        self.ema = Some(match self.ema {
+            Some(ema) => EMA_ALPHA * value + (1.0 - EMA_ALPHA) * ema,
+            None => value,
+        });
+        if self.window.len() == QUANTILE_WINDOW {
+            self.window.pop_front();
+        }
+        self.window.push_back(value);
+    }
+
+    pub fn ema(&self) -> Option<f64> {
+        self.ema
+    }
+
+    /// Nearest-rank quantile (q in [0, 1]) over the last QUANTILE_WINDOW samples
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        if self.window.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<f64> = self.window.iter().copied().collect();
+        sorted.sort_by(f64::total_cmp);
+        let rank = (q.clamp(0.0, 1.0) * sorted.len() as f64).ceil() as usize;
+        Some(sorted[rank.saturating_sub(1)])
+    }
+}
+
+/// Cumulative-bucket histogram in the Prometheus sense: `counts[i]` is the number of observations
+/// <= `bounds[i]`, with the implicit +Inf bucket equal to `count`
+#[derive(Debug, Clone)]
+pub struct Histogram {
+    bounds: Vec<f64>,
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    /// Non-finite and duplicate bounds are dropped; the rest are sorted
+    pub fn new(bounds: &[f64]) -> Self {
+        let mut bounds: Vec<f64> = bounds.iter().copied().filter(|b| b.is_finite()).collect();
+        bounds.sort_by(f64::total_cmp);
+        bounds.dedup();
+        Self { counts: vec![0; bounds.len()], bounds, sum: 0.0, count: 0 }
+    }
+
+    pub fn observe(&mut self, value: f64) {
+        if !value.is_finite() {
+            return;
+        }
+        for (bound, n) in self.bounds.iter().zip(self.counts.iter_mut()) {
+            if value <= *bound {
+                *n += 1;
+            }
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn sum(&self) -> f64 {
+        self.sum
+    }
+
+    /// (upper bound, cumulative count) pairs, excluding +Inf
+    pub fn buckets(&self) -> impl Iterator<Item = (f64, u64)> + '_ {
+        self.bounds.iter().copied().zip(self.counts.iter().copied())
+    }
+}
+
+/// Prometheus Registry: counters and gauges keyed by (component, metric).
+/// BTreeMaps keep the exposition output in a stable order between scrapes.
+#[derive(Debug, Default, Clone)]
+pub struct MetricsRegistry {
+    failures_total: BTreeMap<String, u64>,
+    breaker_trips_total: BTreeMap<String, u64>,
+    ltl_violations_total: BTreeMap<String, u64>, // Keyed by property name
+    gauges: BTreeMap<(String, String), f64>,
+    stats: BTreeMap<(String, String), MetricStats>,
+    job_durations: BTreeMap<String, Histogram>, // Submission-to-terminal seconds, keyed by backend
+    job_duration_buckets: Vec<f64>,             // Empty: DEFAULT_JOB_DURATION_BUCKETS
+}
+
+impl MetricsRegistry {
+    pub fn inc_failures(&mut self, component: &str) {
+        *self.failures_total.entry(component.to_string()).or_default() += 1;
+    }
+
+    pub fn inc_breaker_trips(&mut self, component: &str) {
+        *self.breaker_trips_total.entry(component.to_string()).or_default() += 1;
+    }
+
+    pub fn inc_ltl_violations(&mut self, property: &str) {
+        *self.ltl_violations_total.entry(property.to_string()).or_default() += 1;
+    }
+
+    /// Last-value gauge, e.g. ("qpu", "latency")
+    pub fn set_gauge(&mut self, component: &str, metric: &str, value: f64) {
+        self.gauges.insert((component.to_string(), metric.to_string()), value);
+    }
+
+    /// Gauge update that also feeds the series' EMA and quantile window
+    pub fn observe(&mut self, component: &str, metric: &str, value: f64) {
+        self.set_gauge(component, metric, value);
+        self.stats.entry((component.to_string(), metric.to_string())).or_default().observe(value);
+    }
+
+    #[allow(dead_code)]
+    pub fn stats(&self, component: &str, metric: &str) -> Option<&MetricStats> {
+        self.stats.get(&(component.to_string(), metric.to_string()))
+    }
+
+    /// Bucket bounds (seconds) for job duration histograms created from now on
+    pub fn set_job_duration_buckets(&mut self, bounds: &[f64]) {
+        self.job_duration_buckets = bounds.to_vec();
+    }
+
+    pub fn observe_job_duration(&mut self, backend: &str, seconds: f64) {
+        let bounds = if self.job_duration_buckets.is_empty() { &DEFAULT_JOB_DURATION_BUCKETS[..] } else { &self.job_duration_buckets };
+        self.job_durations.entry(backend.to_string()).or_insert_with(|| Histogram::new(bounds)).observe(seconds);
+    }
+
+    #[allow(dead_code)]
+    pub fn job_durations(&self, backend: &str) -> Option<&Histogram> {
+        self.job_durations.get(backend)
+    }
+
+    /// Renders the text exposition format (0.0.4). `breaker_states` are (component, 0/1/2) pairs.
+    pub fn render(&self, breaker_states: &[(String, u8)]) -> String {
+        let mut out = String::new();
+
+        header(&mut out, "sentinel_component_failures_total", "counter", "Failures reported per component.");
+        for (component, n) in &self.failures_total {
+            let _ = writeln!(out, "sentinel_component_failures_total{{component=\"{}\"}} {}", escape(component), n);
+        }
+
+        header(&mut out, "sentinel_breaker_trips_total", "counter", "Times each component's circuit breaker opened.");
+        for (component, n) in &self.breaker_trips_total {
+            let _ = writeln!(out, "sentinel_breaker_trips_total{{component=\"{}\"}} {}", escape(component), n);
+        }
+
+        header(&mut out, "sentinel_ltl_violations_total", "counter", "LTL property violations, per property.");
+        for (property, n) in &self.ltl_violations_total {
+            let _ = writeln!(out, "sentinel_ltl_violations_total{{property=\"{}\"}} {}", escape(property), n);
+        }
+
+        header(&mut out, "sentinel_breaker_state", "gauge", "Circuit breaker state (0=closed, 1=half-open/degraded, 2=open).");
+        for (component, state) in breaker_states {
+            let _ = writeln!(out, "sentinel_breaker_state{{component=\"{}\"}} {}", escape(component), state);
+        }
+
+        header(&mut out, "sentinel_metric", "gauge", "Last value recorded via SentinelSRE::record_metric.");
+        for ((component, metric), value) in &self.gauges {
+            let _ = writeln!(out, "sentinel_metric{{component=\"{}\",metric=\"{}\"}} {}",
+                             escape(component), escape(metric), format_value(*value));
+        }
+
+        header(&mut out, "sentinel_metric_ema", "gauge", "Exponential moving average of each recorded metric.");
+        for ((component, metric), stats) in &self.stats {
+            if let Some(ema) = stats.ema() {
+                let _ = writeln!(out, "sentinel_metric_ema{{component=\"{}\",metric=\"{}\"}} {}",
+                                 escape(component), escape(metric), format_value(ema));
+            }
+        }
+
+        header(&mut out, "sentinel_qpu_job_duration_seconds", "histogram", "QPU job turnaround (queue + execution), submission to terminal state.");
+        for (backend, hist) in &self.job_durations {
+            let backend = escape(backend);
+            for (bound, n) in hist.buckets() {
+                let _ = writeln!(out, "sentinel_qpu_job_duration_seconds_bucket{{backend=\"{}\",le=\"{}\"}} {}", backend, format_value(bound), n);
+            }
+            let _ = writeln!(out, "sentinel_qpu_job_duration_seconds_bucket{{backend=\"{}\",le=\"+Inf\"}} {}", backend, hist.count());
+            let _ = writeln!(out, "sentinel_qpu_job_duration_seconds_sum{{backend=\"{}\"}} {}", backend, format_value(hist.sum()));
+            let _ = writeln!(out, "sentinel_qpu_job_duration_seconds_count{{backend=\"{}\"}} {}", backend, hist.count());
+        }
+
+        header(&mut out, "sentinel_metric_quantile", "gauge", "p50/p95 of each recorded metric over its recent samples.");
+        for ((component, metric), stats) in &self.stats {
+            for q in [0.5, 0.95] {
+                if let Some(v) = stats.quantile(q) {
+                    let _ = writeln!(out, "sentinel_metric_quantile{{component=\"{}\",metric=\"{}\",quantile=\"{}\"}} {}",
+                                     escape(component), escape(metric), q, format_value(v));
+                }
+            }
+        }
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn format_value(v: f64) -> String {
+    if v.is_nan() {
+        "NaN".to_string()
+    } else if v.is_infinite() {
+        if v > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        v.to_string()
+    }
+}
+
+/// Minimal HTTP/1.1 responder: one GET per connection, routed by path to `handler`.
+/// `handler` returns (status, content type, body); enough for scrapers and probes, nothing more.
+pub async fn serve<H, F>(addr: SocketAddr, handler: H) -> std::io::Result<()>
+where
+    H: Fn(String) -> F + Clone + Send + 'static,
+    F: Future<Output = (u16, &'static str, String)> + Send,
+{
+    let listener = TcpListener::bind(addr).await?;
+    info!("HTTP endpoint listening on {}", addr);
+
+    loop {
+        // Accept failures (EMFILE, a connection reset before accept) are transient: the endpoint
+        // must outlive them, so back off briefly instead of returning
+        let (mut socket, peer) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                warn!("HTTP: Accept on {} failed: {}, retrying.", addr, e);
+                tokio::time::sleep(ACCEPT_BACKOFF).await;
+                continue;
+            }
+        };
+        let handler = handler.clone();
+        tokio::spawn(async move {
+            let mut buf = vec![0u8; 8192];
+            let mut len = 0;
+            // Read until the end of the request head (bodies are ignored)
+            while len < buf.len() {
+                match socket.read(&mut buf[len..]).await {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => len += n,
+                }
+                if buf[..len].windows(4).any(|w| w == b"\r\n\r\n") {
+                    break;
+                }
+            }
+
+            let head = String::from_utf8_lossy(&buf[..len]);
+            let mut request_line = head.lines().next().unwrap_or_default().split_whitespace();
+            let (method, path) = (request_line.next().unwrap_or_default(), request_line.next().unwrap_or("/"));
+            let path = path.split('?').next().unwrap_or("/").to_string();
+
+            let (status, content_type, body) = if method == "GET" {
+                handler(path).await
+            } else {
+                (405, "text/plain", "Method Not Allowed\n".to_string())
+            };
+            let reason = match status {
+                200 => "OK",
+                404 => "Not Found",
+                405 => "Method Not Allowed",
+                503 => "Service Unavailable",
+                _ => "",
+            };
+            let response = format!(
+                "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status, reason, content_type, body.len(), body
+            );
+            if let Err(e) = socket.write_all(response.as_bytes()).await {
+                warn!("HTTP: Failed to respond to {}: {}", peer, e);
+            }
+        });
+    }
+}
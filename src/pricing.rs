@@ -0,0 +1,565 @@
+use crate::config::OptionConfig;
+use crate::feed::HestonParams;
+use crate::qpu::digital_twin::DigitalTwin;
+use futures_util::future::BoxFuture;
+use log::{info, warn};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rand_distr::{Binomial, Distribution};
+use std::f64::consts::PI;
+use std::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+/// European call priced by amplitude estimation. The A operator loads a log-normal terminal price
+/// discretized on `2^grid_qubits` points over mean ± 3σ, and rotates the objective qubit by the
+/// normalized payoff, so `P(objective = 1) = E[max(S_T - K, 0)] / payoff_max`. The expectation
+/// is risk-neutral: the log-normal drifts at `risk_free_rate - dividend_yield` and the payoff is
+/// discounted at `risk_free_rate`, matching `black_scholes`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PricingProblem {
+    pub spot: f64,
+    pub strike: f64,
+    pub vol: f64,
+    pub risk_free_rate: f64,
+    pub dividend_yield: f64,
+    pub time_to_maturity: f64,
+    pub grid_qubits: u32,
+}
+
+impl PricingProblem {
+    /// The `[option]` contract at the current spot and volatility, on the 3-qubit grid of quantum_pricing.py
+    pub fn from_config(option: &OptionConfig, spot: f64, vol: f64) -> Self {
+        Self {
+            spot,
+            strike: option.strike,
+            vol,
+            risk_free_rate: option.risk_free_rate,
+            dividend_yield: option.dividend_yield,
+            time_to_maturity: option.time_to_maturity,
+            grid_qubits: 3,
+        }
+    }
+
+    /// Grid points and their (normalized) probabilities
+    fn distribution(&self) -> Vec<(f64, f64)> {
+        let t = self.time_to_maturity;
+        let mu = self.spot.ln() + (self.risk_free_rate - self.dividend_yield - 0.5 * self.vol * self.vol) * t;
+        let sigma = self.vol * t.sqrt();
+        let mean = (mu + sigma * sigma / 2.0).exp();
+        let std = (((sigma * sigma).exp() - 1.0) * (2.0 * mu + sigma * sigma).exp()).sqrt();
+        let (low, high) = ((mean - 3.0 * std).max(f64::EPSILON), mean + 3.0 * std);
+
+        let n = 1usize << self.grid_qubits;
+        let points: Vec<f64> = (0..n).map(|i| low + (high - low) * i as f64 / (n - 1).max(1) as f64).collect();
+        let density: Vec<f64> = points.iter()
+            .map(|&x| (-(x.ln() - mu).powi(2) / (2.0 * sigma * sigma)).exp() / x)
+            .collect();
+        let total: f64 = density.iter().sum();
+        points.into_iter().zip(density.into_iter().map(|d| d / total)).collect()
+    }
+
+    fn payoff_max(&self) -> f64 {
+        self.distribution().last().map(|&(x, _)| (x - self.strike).max(0.0)).unwrap_or(0.0)
+    }
+
+    /// The amplitude `a` that A prepares (exact, for the simulated oracle and as a reference)
+    pub fn amplitude(&self) -> f64 {
+        let payoff_max = self.payoff_max();
+        if payoff_max <= 0.0 {
+            return 0.0; // Strike above the whole grid: worthless on this discretization
+        }
+        self.distribution().iter().map(|&(x, p)| p * (x - self.strike).max(0.0)).sum::<f64>() / payoff_max
+    }
+
+    /// Closed-form reference for this contract (continuous, not discretized)
+    pub fn black_scholes(&self) -> f64 {
+        black_scholes_call_with_dividend(self.spot, self.strike, self.risk_free_rate, self.dividend_yield, self.vol, self.time_to_maturity)
+    }
+
+    /// Discounted option price for an estimated amplitude
+    pub fn price_from_amplitude(&self, amplitude: f64) -> f64 {
+        (-self.risk_free_rate * self.time_to_maturity).exp() * self.payoff_max() * amplitude
+    }
+}
+
+/// Black-Scholes price of a European call (no dividends)
+#[allow(dead_code)]
+pub fn black_scholes_call(spot: f64, strike: f64, rate: f64, vol: f64, t: f64) -> f64 {
+    black_scholes(spot, strike, rate, 0.0, vol, t, true)
+}
+
+/// Black-Scholes price of a European put (no dividends)
+#[allow(dead_code)]
+pub fn black_scholes_put(spot: f64, strike: f64, rate: f64, vol: f64, t: f64) -> f64 {
+    black_scholes(spot, strike, rate, 0.0, vol, t, false)
+}
+
+/// Merton's continuous-dividend extension: the spot is discounted by `e^{-qT}`
+pub fn black_scholes_call_with_dividend(spot: f64, strike: f64, rate: f64, dividend_yield: f64, vol: f64, t: f64) -> f64 {
+    black_scholes(spot, strike, rate, dividend_yield, vol, t, true)
+}
+
+fn black_scholes(spot: f64, strike: f64, rate: f64, dividend_yield: f64, vol: f64, t: f64, call: bool) -> f64 {
+    let forward_spot = spot * (-dividend_yield * t.max(0.0)).exp();
+    let discounted_strike = strike * (-rate * t.max(0.0)).exp();
+    let sign = if call { 1.0 } else { -1.0 };
+    if t <= 0.0 || vol <= 0.0 {
+        return (sign * (forward_spot - discounted_strike)).max(0.0); // Degenerate: no diffusion left
+    }
+    let sigma_sqrt_t = vol * t.sqrt();
+    let d1 = ((spot / strike).ln() + (rate - dividend_yield + 0.5 * vol * vol) * t) / sigma_sqrt_t;
+    let d2 = d1 - sigma_sqrt_t;
+    sign * (forward_spot * normal_cdf(sign * d1) - discounted_strike * normal_cdf(sign * d2))
+}
+
+/// Standard normal CDF via erf
+pub fn normal_cdf(x: f64) -> f64 {
+    0.5 * (1.0 + erf(x / std::f64::consts::SQRT_2))
+}
+
+/// Abramowitz & Stegun 7.1.26 (|error| < 1.5e-7)
+fn erf(x: f64) -> f64 {
+    const P: f64 = 0.3275911;
+    const A: [f64; 5] = [0.254829592, -0.284496736, 1.421413741, -1.453152027, 1.061405429];
+    let t = 1.0 / (1.0 + P * x.abs());
+    let poly = A.iter().rev().fold(0.0, |acc, a| acc * t + a) * t;
+    (1.0 - poly * (-x * x).exp()).copysign(x)
+}
+
+// Fourier integration grid for `heston_call`: the integrands decay like e^{-c u} for T > 0
+#[allow(dead_code)]
+const HESTON_INTEGRATION_LIMIT: f64 = 200.0;
+#[allow(dead_code)]
+const HESTON_INTEGRATION_STEPS: usize = 8_000;
+
+/// Semi-analytic Heston (1993) price of a European call on `params.s0` with variance `params.v0`,
+/// under the risk-neutral measure (drift `risk_free_rate`, whatever `params.measure` says):
+/// `C = S·P1 - K·e^{-rT}·P2`, each `Pj` a Fourier inversion of the log-price characteristic
+/// function integrated by the midpoint rule. The characteristic function uses Albrecher et al.'s
+/// "little trap" form, which stays on the principal branch of the complex log for long maturities.
+/// This is the reference the Monte-Carlo feed and the IQAE estimates should converge to; the
+/// feed's floored Euler variance is biased upward when the Feller condition 2κθ ≥ ξ² fails.
+#[allow(dead_code)]
+pub fn heston_call(params: &HestonParams, strike: f64, maturity: f64) -> f64 {
+    let discount = (-params.risk_free_rate * maturity.max(0.0)).exp();
+    if maturity <= 0.0 || strike <= 0.0 {
+        return (params.s0 - strike * discount).max(0.0);
+    }
+    let log_strike = strike.ln();
+    let forward = heston_cf(params, maturity, Complex::new(0.0, -1.0)); // E[S_T] = S·e^{rT}
+    let du = HESTON_INTEGRATION_LIMIT / HESTON_INTEGRATION_STEPS as f64;
+    let (mut p1, mut p2) = (0.0, 0.0);
+    for n in 0..HESTON_INTEGRATION_STEPS {
+        let u = (n as f64 + 0.5) * du;
+        // e^{-iu ln K} / (iu)
+        let kernel = Complex::new(0.0, -u * log_strike).exp() / Complex::new(0.0, u);
+        p1 += (kernel * heston_cf(params, maturity, Complex::new(u, -1.0)) / forward).re;
+        p2 += (kernel * heston_cf(params, maturity, Complex::new(u, 0.0))).re;
+    }
+    let p1 = 0.5 + p1 * du / PI;
+    let p2 = 0.5 + p2 * du / PI;
+    (params.s0 * p1 - strike * discount * p2).max(0.0)
+}
+
+/// `E[e^{iu ln S_T}]` under risk-neutral Heston dynamics, for complex `u`
+#[allow(dead_code)]
+fn heston_cf(p: &HestonParams, t: f64, u: Complex) -> Complex {
+    let iu = Complex::new(-u.im, u.re);
+    let xi2 = p.xi * p.xi;
+    let beta = Complex::new(p.kappa, 0.0) - iu * (p.rho * p.xi);
+    let d = (beta * beta + (iu + u * u) * xi2).sqrt();
+    let g = (beta - d) / (beta + d);
+    let decay = (-(d * t)).exp();
+    let one = Complex::new(1.0, 0.0);
+    let c = iu * (p.risk_free_rate * t)
+        + ((beta - d) * t - ((one - g * decay) / (one - g)).ln() * 2.0) * (p.kappa * p.theta / xi2);
+    let d_term = (beta - d) / xi2 * ((one - decay) / (one - g * decay));
+    (c + d_term * p.v0 + iu * p.s0.ln()).exp()
+}
+
+/// Just enough complex arithmetic for the characteristic function
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[allow(dead_code)]
+struct Complex {
+    re: f64,
+    im: f64,
+}
+
+impl Complex {
+    #[allow(dead_code)]
+    fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+
+    #[allow(dead_code)]
+    fn exp(self) -> Self {
+        let scale = self.re.exp();
+        Self::new(scale * self.im.cos(), scale * self.im.sin())
+    }
+
+    /// Principal branch
+    #[allow(dead_code)]
+    fn ln(self) -> Self {
+        Self::new(self.re.hypot(self.im).ln(), self.im.atan2(self.re))
+    }
+
+    /// Principal branch (non-negative real part)
+    #[allow(dead_code)]
+    fn sqrt(self) -> Self {
+        let modulus = self.re.hypot(self.im);
+        let re = ((modulus + self.re) / 2.0).sqrt();
+        let im = ((modulus - self.re) / 2.0).sqrt().copysign(self.im);
+        Self::new(re, im)
+    }
+}
+
+impl std::ops::Add for Complex {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.re + rhs.re, self.im + rhs.im)
+    }
+}
+
+impl std::ops::Sub for Complex {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.re - rhs.re, self.im - rhs.im)
+    }
+}
+
+impl std::ops::Neg for Complex {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new(-self.re, -self.im)
+    }
+}
+
+impl std::ops::Mul for Complex {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(self.re * rhs.re - self.im * rhs.im, self.re * rhs.im + self.im * rhs.re)
+    }
+}
+
+impl std::ops::Mul<f64> for Complex {
+    type Output = Self;
+    fn mul(self, rhs: f64) -> Self {
+        Self::new(self.re * rhs, self.im * rhs)
+    }
+}
+
+impl std::ops::Div for Complex {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self {
+        let norm = rhs.re * rhs.re + rhs.im * rhs.im;
+        Self::new((self.re * rhs.re + self.im * rhs.im) / norm, (self.im * rhs.re - self.re * rhs.im) / norm)
+    }
+}
+
+impl std::ops::Div<f64> for Complex {
+    type Output = Self;
+    fn div(self, rhs: f64) -> Self {
+        Self::new(self.re / rhs, self.im / rhs)
+    }
+}
+
+/// Executes `Q^k A` and measures the objective qubit. Implemented by whatever runs the circuits:
+/// a Runtime sampler session on hardware, or `SimulatedOracle` for dry-run and backtests.
+pub trait AmplitudeOracle: Send {
+    /// Number of the `shots` measurements that found the objective qubit in |1>
+    fn sample(&mut self, grover_power: u64, shots: u32) -> BoxFuture<'_, Result<u32, String>>;
+}
+
+/// Shot-noise-only oracle: samples Binomial(shots, sin²((2k+1)·θ_a)) for the problem's exact amplitude
+#[allow(dead_code)]
+pub struct SimulatedOracle {
+    theta: f64, // θ_a with a = sin²(θ_a)
+    rng: StdRng,
+}
+
+impl SimulatedOracle {
+    #[allow(dead_code)]
+    pub fn new(amplitude: f64, seed: u64) -> Self {
+        Self {
+            theta: amplitude.clamp(0.0, 1.0).sqrt().asin(),
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl AmplitudeOracle for SimulatedOracle {
+    fn sample(&mut self, grover_power: u64, shots: u32) -> BoxFuture<'_, Result<u32, String>> {
+        let p = ((2 * grover_power + 1) as f64 * self.theta).sin().powi(2);
+        let hits = Binomial::new(shots as u64, p.clamp(0.0, 1.0))
+            .map(|b| b.sample(&mut self.rng) as u32)
+            .map_err(|e| e.to_string());
+        Box::pin(async move { hits })
+    }
+}
+
+/// Runs the Grover iterates on the digital twin's estimator: the objective qubit's
+/// P(1) = sin²((2k+1)θ_a) is read back from the twin's <Z> = cos(2(2k+1)θ_a), so the loop sees
+/// the twin's finite-shot noise model in place of exact binomial draws
+pub struct TwinOracle {
+    theta: f64, // θ_a with a = sin²(θ_a)
+    twin: DigitalTwin, // Rebuilt when the requested shot count changes, since it sets the noise
+    rng: StdRng,
+}
+
+impl TwinOracle {
+    pub fn new(amplitude: f64, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        Self {
+            theta: amplitude.clamp(0.0, 1.0).sqrt().asin(),
+            twin: DigitalTwin::with_config(IqaeConfig::default().shots, rng.gen()),
+            rng,
+        }
+    }
+
+    /// Synthetic job id for the run, in the twin's `twin-<hex>` form
+    pub fn job_id(&self) -> String {
+        self.twin.synthetic_job_id()
+    }
+}
+
+impl AmplitudeOracle for TwinOracle {
+    fn sample(&mut self, grover_power: u64, shots: u32) -> BoxFuture<'_, Result<u32, String>> {
+        if self.twin.shots() != shots.max(1) {
+            self.twin = DigitalTwin::with_config(shots, self.rng.gen());
+        }
+        let (z, _) = self.twin.estimate(2.0 * (2 * grover_power + 1) as f64 * self.theta);
+        let hits = (((1.0 - z) / 2.0 * shots as f64).round() as u32).min(shots);
+        Box::pin(async move { Ok(hits) })
+    }
+}
+
+/// Loop tuning. `target_epsilon` is the confidence-interval half-width wanted on the price.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IqaeConfig {
+    pub target_epsilon: f64,
+    pub alpha: f64, // 1 - confidence level of the final interval
+    pub shots: u32, // Per oracle call
+    pub max_iterations: usize,
+}
+
+impl Default for IqaeConfig {
+    fn default() -> Self {
+        Self {
+            target_epsilon: 0.01,
+            alpha: 0.05,
+            shots: 100,
+            max_iterations: 32,
+        }
+    }
+}
+
+/// Result of the loop. `converged` is false when the iteration cap stopped it first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceEstimate {
+    pub price: f64,
+    pub confidence_interval: (f64, f64),
+    pub amplitude: f64,
+    pub iterations: usize,
+    pub oracle_queries: u64, // Applications of A, counting each Q as two
+    pub converged: bool,
+}
+
+impl PriceEstimate {
+    /// Half the width of the price confidence interval
+    pub fn half_width(&self) -> f64 {
+        (self.confidence_interval.1 - self.confidence_interval.0) / 2.0
+    }
+}
+
+/// One pricing run end to end: the IQAE estimate, its Black-Scholes reference, and what ran it
+#[derive(Debug, Clone, PartialEq)]
+pub struct PricingResult {
+    pub estimate: PriceEstimate,
+    pub black_scholes: f64,
+    pub job_id: String,
+    /// The generated IQAE circuit (QASM); None when quantum_pricing was unavailable
+    pub circuit: Option<String>,
+    pub latency_ms: f64,
+}
+
+/// Simulate-only pricing: "submits" the problem to the digital twin and runs the IQAE loop against
+/// it, so the whole pricing path runs locally (development, CI) without IBM access. The twin's
+/// amplitude comes from the same parameters the circuit was generated from; `seed` fixes its noise.
+pub async fn price_on_twin(problem: &PricingProblem, circuit: Option<String>, config: &IqaeConfig, seed: u64, cancel: &CancellationToken) -> Result<PricingResult, String> {
+    let started = Instant::now();
+    let mut oracle = TwinOracle::new(problem.amplitude(), seed);
+    let job_id = oracle.job_id();
+    let estimate = price_option(problem, &mut oracle, config, cancel).await?;
+    Ok(PricingResult {
+        estimate,
+        black_scholes: problem.black_scholes(),
+        job_id,
+        circuit,
+        latency_ms: started.elapsed().as_secs_f64() * 1000.0,
+    })
+}
+
+/// Iterative Quantum Amplitude Estimation (Grinko et al., 2021) with Chernoff-Hoeffding intervals.
+/// Each iteration picks the largest Grover power keeping the θ interval inside one half-plane,
+/// samples the oracle, and intersects the new interval with the old one. Stops once the price
+/// interval's half-width is within `target_epsilon` or after `max_iterations` rounds, or errs
+/// as soon as `cancel` fires (e.g. because a newer price made the estimate moot).
+pub async fn price_option(problem: &PricingProblem, oracle: &mut dyn AmplitudeOracle, config: &IqaeConfig, cancel: &CancellationToken) -> Result<PriceEstimate, String> {
+    if !(config.target_epsilon > 0.0 && config.alpha > 0.0 && config.alpha < 1.0 && config.shots > 0) {
+        return Err(format!("invalid IQAE configuration {:?}", config));
+    }
+    let scale = problem.price_from_amplitude(1.0);
+    if scale <= 0.0 {
+        return Ok(PriceEstimate {
+            price: 0.0,
+            confidence_interval: (0.0, 0.0),
+            amplitude: 0.0,
+            iterations: 0,
+            oracle_queries: 0,
+            converged: true,
+        });
+    }
+    // Precision on `a` implied by the price target; the loop cannot usefully go below shot noise
+    let epsilon_a = (config.target_epsilon / scale).min(0.5);
+    let max_rounds = ((2.0 * PI / 8.0 / epsilon_a).ln() / 2f64.ln()).floor().max(0.0) as usize + 1;
+    let chernoff = |shots: u64| (3.0 * (2.0 * max_rounds as f64 / config.alpha).ln() / shots as f64).sqrt();
+
+    // θ in units of a full turn, a = sin²(2π θ), θ ∈ [0, 1/4]
+    let mut interval = (0.0f64, 0.25f64);
+    let mut a_interval = (0.0f64, 1.0f64);
+    let (mut k, mut upper) = (0u64, true);
+    let (mut round_shots, mut round_hits) = (0u64, 0u64);
+    let mut oracle_queries = 0;
+    let mut iterations = 0;
+
+    while (a_interval.1 - a_interval.0) / 2.0 > epsilon_a && iterations < config.max_iterations {
+        iterations += 1;
+        let (next_k, next_upper) = find_next_k(k, upper, interval);
+        if next_k != k {
+            round_shots = 0;
+            round_hits = 0; // Only rounds at the same power can be pooled
+        }
+        (k, upper) = (next_k, next_upper);
+
+        let Some(hits) = cancel.run_until_cancelled(oracle.sample(k, config.shots)).await else {
+            return Err(format!("IQAE cancelled after {} iterations", iterations - 1));
+        };
+        let hits = hits?;
+        oracle_queries += config.shots as u64 * (2 * k + 1);
+        round_shots += config.shots as u64;
+        round_hits += hits as u64;
+
+        let p = round_hits as f64 / round_shots as f64;
+        let eps = chernoff(round_shots);
+        let (a_min, a_max) = ((p - eps).max(0.0), (p + eps).min(1.0));
+        let to_theta = |a: f64| (1.0 - 2.0 * a).clamp(-1.0, 1.0).acos() / (2.0 * PI);
+        let (theta_min, theta_max) = if upper {
+            (to_theta(a_min), to_theta(a_max))
+        } else {
+            (1.0 - to_theta(a_max), 1.0 - to_theta(a_min))
+        };
+
+        let scaling = (4 * k + 2) as f64;
+        let lower = ((scaling * interval.0).floor() + theta_min) / scaling;
+        let upper_bound = ((scaling * interval.1).floor() + theta_max) / scaling;
+        interval = (lower.max(interval.0), upper_bound.min(interval.1));
+        let amp = |theta: f64| (2.0 * PI * theta).sin().powi(2);
+        a_interval = (amp(interval.0), amp(interval.1));
+    }
+
+    let converged = (a_interval.1 - a_interval.0) / 2.0 <= epsilon_a;
+    if !converged {
+        warn!("IQAE: Stopped after {} iterations at half-width {:.4} (target {:.4}).",
+              iterations, scale * (a_interval.1 - a_interval.0) / 2.0, config.target_epsilon);
+    }
+    let amplitude = (a_interval.0 + a_interval.1) / 2.0;
+    let estimate = PriceEstimate {
+        price: problem.price_from_amplitude(amplitude),
+        confidence_interval: (problem.price_from_amplitude(a_interval.0), problem.price_from_amplitude(a_interval.1)),
+        amplitude,
+        iterations,
+        oracle_queries,
+        converged,
+    };
+    info!("IQAE: Price {:.4} in [{:.4}, {:.4}] after {} iterations ({} oracle queries).",
+          estimate.price, estimate.confidence_interval.0, estimate.confidence_interval.1, iterations, oracle_queries);
+    Ok(estimate)
+}
+
+/// Largest power k (K = 4k + 2 at least twice the current one) whose scaled interval stays in a
+/// single half-plane; keeps the current power otherwise
+fn find_next_k(k: u64, upper: bool, (theta_l, theta_u): (f64, f64)) -> (u64, bool) {
+    let old_scaling = 4 * k + 2;
+    let width = theta_u - theta_l;
+    if width <= 0.0 {
+        return (k, upper);
+    }
+    let max_scaling = (1.0 / (2.0 * width)).floor().min(u32::MAX as f64) as u64;
+    if max_scaling < 2 {
+        return (k, upper);
+    }
+    let mut scaling = max_scaling - (max_scaling - 2) % 4;
+    while scaling >= 2 * old_scaling {
+        let theta_min = (scaling as f64 * theta_l).fract();
+        let theta_max = (scaling as f64 * theta_u).fract();
+        if theta_min <= theta_max && theta_max <= 0.5 {
+            return ((scaling - 2) / 4, true);
+        }
+        if theta_min >= 0.5 && theta_max >= theta_min {
+            return ((scaling - 2) / 4, false);
+        }
+        scaling -= 4;
+    }
+    (k, upper)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feed::{Measure, SentinelFeed};
+
+    #[test]
+    fn black_scholes_matches_the_textbook_example() {
+        // S = K = 100, r = 5%, sigma = 20%, T = 1y (Hull)
+        assert!((black_scholes_call(100.0, 100.0, 0.05, 0.2, 1.0) - 10.4506).abs() < 1e-3);
+        assert!((black_scholes_put(100.0, 100.0, 0.05, 0.2, 1.0) - 5.5735).abs() < 1e-3);
+    }
+
+    #[test]
+    fn monte_carlo_feed_agrees_with_the_characteristic_function_price() {
+        // Feller condition holds (2 kappa theta = 0.16 >= xi^2 = 0.01), so the Euler floor adds no bias
+        let params = HestonParams { measure: Measure::RiskNeutral, ..HestonParams::default() };
+        let (strike, steps, paths) = (100.0, 63, 20_000);
+        let maturity = steps as f64 * params.dt;
+        let discount = (-params.risk_free_rate * maturity).exp();
+
+        let payoffs: Vec<f64> = (0..paths)
+            .map(|seed| {
+                let mut feed = SentinelFeed::with_seed(params, seed);
+                let terminal = (0..steps).map(|_| feed.next_tick()).last().unwrap();
+                discount * (terminal - strike).max(0.0)
+            })
+            .collect();
+        let mean = payoffs.iter().sum::<f64>() / paths as f64;
+        let variance = payoffs.iter().map(|p| (p - mean).powi(2)).sum::<f64>() / (paths - 1) as f64;
+        let std_error = (variance / paths as f64).sqrt();
+
+        let exact = heston_call(&params, strike, maturity);
+        assert!((mean - exact).abs() < 3.0 * std_error, "MC {:.4} +/- {:.4} vs CF {:.4}", mean, std_error, exact);
+    }
+
+    #[tokio::test]
+    async fn the_twin_price_brackets_black_scholes_and_each_seed_is_its_own_job() {
+        let option = OptionConfig { strike: 100.0, time_to_maturity: 0.5, ..OptionConfig::default() };
+        let problem = PricingProblem::from_config(&option, 100.0, 0.2);
+        let mut job_ids = std::collections::HashSet::new();
+        for seed in 0..8 {
+            let result = price_on_twin(&problem, None, &IqaeConfig::default(), seed, &CancellationToken::new()).await.unwrap();
+            let (low, high) = result.estimate.confidence_interval;
+            assert!(low <= result.black_scholes && result.black_scholes <= high,
+                    "seed {}: Black-Scholes {:.4} outside [{:.4}, {:.4}]", seed, result.black_scholes, low, high);
+            job_ids.insert(result.job_id);
+        }
+        assert_eq!(job_ids.len(), 8);
+    }
+}
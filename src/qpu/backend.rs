@@ -0,0 +1,204 @@
+use super::neutral_atom::{AnalogHamiltonianProgram, NeutralAtomAdapter, ShotCounts};
+use super::{BackendStatus, JobResult, JobStatus, JobStatusClient, QiskitRuntimeService, SessionGuard};
+use futures_util::future::BoxFuture;
+use std::error::Error;
+
+pub type BackendError = Box<dyn Error + Send + Sync>;
+
+// Atoms in the analog hedge register, one per qubit of the QAOA ring
+const HEDGE_RING_ATOMS: usize = 4;
+
+/// Provider-neutral description of one job
+#[derive(Debug, Clone)]
+pub enum JobSpec {
+    /// Gate-model Runtime program with its bound market parameter, e.g. the DD-protected QAOA hedge
+    Circuit { program_id: String, theta: f64 },
+    /// Rydberg-atom program for analog (neutral-atom) hardware
+    Analog(AnalogHamiltonianProgram),
+}
+
+impl From<AnalogHamiltonianProgram> for JobSpec {
+    fn from(program: AnalogHamiltonianProgram) -> Self {
+        JobSpec::Analog(program)
+    }
+}
+
+/// A QPU provider the manager can dispatch to. Jobs a provider cannot run (an analog program on
+/// a gate-model runtime, say) are rejected by `submit`. Session and cancellation hooks default to
+/// no-ops for providers without those concepts. Boxed futures keep the trait object-safe.
+pub trait QuantumBackend: Send + Sync {
+    /// Provider label for logs, e.g. "ibm"
+    fn provider(&self) -> String;
+
+    /// True when jobs are simulated locally (digital twin, mock, dry-run)
+    fn is_simulated(&self) -> bool;
+
+    /// Hermetic mode: no network calls, deterministic synthetic results
+    fn set_dry_run(&mut self, dry_run: bool);
+
+    /// The hedge job for market parameter `theta`, in the form this provider runs: the Runtime
+    /// program by default
+    fn hedge_job(&self, program_id: &str, theta: f64) -> JobSpec {
+        JobSpec::Circuit { program_id: program_id.to_string(), theta }
+    }
+
+    /// Queues `job` and returns its provider job id
+    fn submit(&self, job: JobSpec) -> BoxFuture<'_, Result<String, BackendError>>;
+
+    /// Status of a job and, once Completed, its measured expectation value
+    #[allow(dead_code)]
+    fn result<'a>(&'a self, job_id: &'a str) -> BoxFuture<'a, Result<JobResult, BackendError>>;
+
+    /// Makes `device` ready for submissions. A guard is returned when this opened something billed.
+    fn prepare<'a>(&'a mut self, _device: &'a str) -> BoxFuture<'a, Result<Option<SessionGuard>, BackendError>> {
+        Box::pin(async { Ok(None) })
+    }
+
+    /// Releases whatever `prepare` opened
+    fn close(&self) -> BoxFuture<'_, ()> {
+        Box::pin(async {})
+    }
+
+    /// Cancels one submitted job, e.g. to roll back a cycle whose ledger commit failed
+    fn cancel<'a>(&'a self, _job_id: &'a str) -> BoxFuture<'a, Result<(), BackendError>> {
+        Box::pin(async move { Err(format!("{} does not support job cancellation", self.provider()).into()) })
+    }
+
+    /// Cancels every job still in flight; returns how many were cancelled
+    fn cancel_outstanding(&self) -> BoxFuture<'_, usize> {
+        Box::pin(async { 0 })
+    }
+
+    /// Poller for job lifecycle watchers; None when the provider has no status endpoint, in
+    /// which case a job's concurrency slot is freed at submission
+    fn status_client(&self) -> Option<JobStatusClient> {
+        None
+    }
+
+    /// Queue depth of `device` before submitting; None when the provider does not report one
+    fn backend_status<'a>(&'a self, _device: &'a str) -> BoxFuture<'a, Result<Option<BackendStatus>, BackendError>> {
+        Box::pin(async { Ok(None) })
+    }
+}
+
+impl QuantumBackend for QiskitRuntimeService {
+    fn provider(&self) -> String {
+        "ibm".to_string()
+    }
+
+    fn is_simulated(&self) -> bool {
+        self.is_digital_twin()
+    }
+
+    fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+
+    fn submit(&self, job: JobSpec) -> BoxFuture<'_, Result<String, BackendError>> {
+        Box::pin(async move {
+            match job {
+                JobSpec::Circuit { program_id, theta } => {
+                    let result = self.run_job(&program_id, theta).await.map_err(|e| e.to_string())?;
+                    Ok(result.job_id)
+                }
+                JobSpec::Analog(_) => Err("Qiskit Runtime cannot run analog Hamiltonian programs".into()),
+            }
+        })
+    }
+
+    fn result<'a>(&'a self, job_id: &'a str) -> BoxFuture<'a, Result<JobResult, BackendError>> {
+        Box::pin(async move { self.get_job_result(job_id).await.map_err(|e| e.to_string().into()) })
+    }
+
+    /// Reattaches to the stored session when possible, otherwise opens one on `device`
+    fn prepare<'a>(&'a mut self, device: &'a str) -> BoxFuture<'a, Result<Option<SessionGuard>, BackendError>> {
+        Box::pin(async move {
+            if self.session_id().is_some() || self.resume_session().await {
+                return Ok(None);
+            }
+            self.open_session(device).await.map(Some).map_err(|e| e.to_string().into())
+        })
+    }
+
+    fn close(&self) -> BoxFuture<'_, ()> {
+        Box::pin(self.close_session())
+    }
+
+    fn cancel<'a>(&'a self, job_id: &'a str) -> BoxFuture<'a, Result<(), BackendError>> {
+        Box::pin(async move { self.cancel_job(job_id).await.map_err(|e| e.to_string().into()) })
+    }
+
+    fn cancel_outstanding(&self) -> BoxFuture<'_, usize> {
+        Box::pin(self.cancel_outstanding_jobs())
+    }
+
+    fn status_client(&self) -> Option<JobStatusClient> {
+        Some(QiskitRuntimeService::status_client(self))
+    }
+
+    fn backend_status<'a>(&'a self, device: &'a str) -> BoxFuture<'a, Result<Option<BackendStatus>, BackendError>> {
+        Box::pin(async move {
+            QiskitRuntimeService::backend_status(self, device).await.map(Some).map_err(|e| e.to_string().into())
+        })
+    }
+}
+
+impl QuantumBackend for NeutralAtomAdapter {
+    fn provider(&self) -> String {
+        self.provider_url.clone()
+    }
+
+    fn is_simulated(&self) -> bool {
+        self.is_mock()
+    }
+
+    fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+
+    /// The 4-atom ring, matching the 4-qubit ring of the gate-model hedge
+    fn hedge_job(&self, _program_id: &str, theta: f64) -> JobSpec {
+        JobSpec::Analog(self.hedge_ring_program(HEDGE_RING_ATOMS, theta))
+    }
+
+    fn submit(&self, job: JobSpec) -> BoxFuture<'_, Result<String, BackendError>> {
+        Box::pin(async move {
+            match job {
+                JobSpec::Analog(program) => Ok(self.submit_analog_program(&program).await?),
+                JobSpec::Circuit { program_id, .. } => {
+                    Err(format!("Neutral-atom providers run analog programs, not gate circuits ('{}')", program_id).into())
+                }
+            }
+        })
+    }
+
+    /// Counts fetched means the job is done; the expectation is the mean Rydberg excitation density
+    fn result<'a>(&'a self, job_id: &'a str) -> BoxFuture<'a, Result<JobResult, BackendError>> {
+        Box::pin(async move {
+            let counts = self.get_analog_result(job_id).await?;
+            Ok(excitation_density(job_id, &counts))
+        })
+    }
+}
+
+/// Fraction of excited atoms per shot, averaged over shots, with its standard error
+#[allow(dead_code)]
+fn excitation_density(job_id: &str, counts: &ShotCounts) -> JobResult {
+    let shots: u64 = counts.values().sum();
+    let density = |bits: &str| bits.chars().filter(|&c| c == '1').count() as f64 / bits.len().max(1) as f64;
+    let (mean, std_error) = if shots == 0 {
+        (None, None)
+    } else {
+        let n = shots as f64;
+        let mean = counts.iter().map(|(bits, &c)| density(bits) * c as f64).sum::<f64>() / n;
+        let var = counts.iter().map(|(bits, &c)| (density(bits) - mean).powi(2) * c as f64).sum::<f64>() / n;
+        (Some(mean), Some((var / n).sqrt()))
+    };
+    JobResult {
+        job_id: job_id.to_string(),
+        status: JobStatus::Completed,
+        expectation: mean,
+        std_error,
+        shots: shots.min(u32::MAX as u64) as u32,
+    }
+}
@@ -0,0 +1,77 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rand_distr::{Distribution, Normal};
+use log::debug;
+use std::sync::Mutex;
+use super::BackendInfo;
+
+/// Sentinel token used when no IBM credentials are configured
+pub const DIGITAL_TWIN_TOKEN: &str = "DIGITAL_TWIN_MOCK_TOKEN";
+
+const DEFAULT_SHOTS: u32 = 4096;
+
+/// Digital Twin: Local stand-in for the Runtime Sampler/Estimator primitives
+/// Models <Z> of a single-parameter ansatz as cos(theta), observed through finite-shot noise.
+///
+/// Noise model: measuring a +/-1 observable `shots` times is a binomial experiment, so the sample
+/// mean has standard error sqrt((1 - <Z>^2) / shots). The twin adds Gaussian noise of exactly that
+/// width (the normal approximation to the binomial), i.e. the error shrinks as 1/sqrt(shots).
+/// With a fixed seed the noise sequence, and the synthetic job IDs, are reproducible.
+pub struct DigitalTwin {
+    shots: u32,
+    rng: Mutex<StdRng>,
+}
+
+impl DigitalTwin {
+    pub fn new() -> Self {
+        Self {
+            shots: DEFAULT_SHOTS,
+            rng: Mutex::new(StdRng::from_entropy()),
+        }
+    }
+
+    /// Reproducible twin: `shots` sets the noise level, `seed` fixes the draw sequence
+    pub fn with_config(shots: u32, seed: u64) -> Self {
+        Self {
+            shots: shots.max(1),
+            rng: Mutex::new(StdRng::seed_from_u64(seed)),
+        }
+    }
+
+    pub fn shots(&self) -> u32 {
+        self.shots
+    }
+
+    /// Noiseless <Z>(theta) = cos(theta) with the standard error a real run would carry (dry-run mode)
+    pub fn ideal(&self, theta: f64) -> (f64, f64) {
+        let ideal = theta.cos();
+        // Binomial variance of a +/-1 observable, floored so the noise never fully vanishes at the poles
+        let std_error = ((1.0 - ideal * ideal).max(1e-6) / self.shots as f64).sqrt();
+        (ideal, std_error)
+    }
+
+    /// Estimator: <Z>(theta) = cos(theta) + e, e ~ N(0, sqrt((1 - <Z>^2) / shots))
+    /// Returns (expectation, standard_error)
+    pub fn estimate(&self, theta: f64) -> (f64, f64) {
+        let (ideal, std_error) = self.ideal(theta);
+        let noise = Normal::new(0.0, std_error).unwrap().sample(&mut *self.rng.lock().unwrap());
+        let expectation = (ideal + noise).clamp(-1.0, 1.0);
+
+        debug!("DigitalTwin: theta={:.4}, <Z>={:.4} (+/- {:.4}, {} shots)", theta, expectation, std_error, self.shots);
+        (expectation, std_error)
+    }
+
+    /// Fixed backend roster served in Digital Twin mode
+    #[allow(dead_code)]
+    pub fn backends() -> Vec<BackendInfo> {
+        vec![
+            BackendInfo { name: "fake_heron".to_string(), n_qubits: 133, simulator: false, operational: true },
+            BackendInfo { name: "fake_eagle".to_string(), n_qubits: 127, simulator: false, operational: false },
+            BackendInfo { name: "twin_simulator".to_string(), n_qubits: 32, simulator: true, operational: true },
+        ]
+    }
+
+    pub fn synthetic_job_id(&self) -> String {
+        format!("twin-{:016x}", self.rng.lock().unwrap().gen::<u64>())
+    }
+}
@@ -1,83 +1,463 @@
-use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
 use std::env;
+use std::path::{Path, PathBuf};
 use std::error::Error;
-use log::{info, error, debug};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use log::{info, warn, error, debug};
+use rand::Rng;
 use std::time::Duration;
 use tokio::time::sleep;
 
-const IBM_QUANTUM_API_URL: &str = "https://api.quantum-computing.ibm.com/runtime";
+const DEFAULT_API_URL: &str = "https://api.quantum-computing.ibm.com/runtime";
+const DEFAULT_INSTANCE: &str = "ibm-q/open/main";
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// Outbound Runtime requests per second unless configured otherwise
+pub const DEFAULT_REQUESTS_PER_SECOND: f64 = 5.0;
 
 #[derive(Serialize)]
+#[allow(dead_code)]
 struct JobParams {
     program_id: String,
     params: Value,
 }
 
 pub mod neutral_atom;
+pub mod digital_twin;
+pub mod session;
+pub mod sigv4;
+pub mod transport;
+pub mod token;
+pub mod backend;
+pub mod rate_limit;
+
+use digital_twin::{DigitalTwin, DIGITAL_TWIN_TOKEN};
+use rate_limit::RateLimiter;
+use transport::{ReqwestTransport, RuntimeTransport, TransportError};
+pub use backend::QuantumBackend;
+pub use session::SessionGuard;
+pub use token::TokenSource;
+
+/// Lifecycle of a Runtime job as reported by the service
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    /// Maps the service's status string ("QUEUED", "Running", "DONE", ...) case-insensitively
+    pub fn parse(status: &str) -> Option<Self> {
+        match status.to_ascii_uppercase().as_str() {
+            "QUEUED" | "INITIALIZING" | "VALIDATING" => Some(JobStatus::Queued),
+            "RUNNING" => Some(JobStatus::Running),
+            "COMPLETED" | "DONE" => Some(JobStatus::Completed),
+            "FAILED" | "ERROR" => Some(JobStatus::Failed),
+            "CANCELLED" | "CANCELED" => Some(JobStatus::Cancelled),
+            _ => None,
+        }
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled)
+    }
+}
+
+/// Outcome of a primitive submission. `expectation` is only populated once the job has Completed.
+#[derive(Debug, Clone, Serialize)]
+pub struct JobResult {
+    pub job_id: String,
+    pub status: JobStatus,
+    pub expectation: Option<f64>,
+    pub std_error: Option<f64>,
+    pub shots: u32,
+}
+
+/// Retry Policy for transient Runtime failures (429 / 502 / 503 / 504)
+/// Delay before attempt n = base_delay * 2^(n-1) + jitter, unless the server sends `Retry-After`
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(500),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Exponential backoff with up to 50% random jitter (avoids thundering herd on 429)
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exp = self.base_delay
+            .checked_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .unwrap_or(MAX_BACKOFF)
+            .min(MAX_BACKOFF);
+        let jitter_ms = rand::thread_rng().gen_range(0..=exp.as_millis() as u64 / 2);
+        exp + Duration::from_millis(jitter_ms)
+    }
+}
+
+/// Backend descriptor as advertised by the Runtime `/backends` endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(dead_code)]
+pub struct BackendInfo {
+    pub name: String,
+    #[serde(alias = "num_qubits", alias = "qubits")]
+    pub n_qubits: u64,
+    #[serde(default)]
+    pub simulator: bool,
+    #[serde(default = "default_operational")]
+    pub operational: bool,
+}
+
+fn default_operational() -> bool {
+    true
+}
+
+/// Live load of one backend (`GET /backends/{name}/status`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackendStatus {
+    /// Jobs queued ahead of a new submission
+    #[serde(alias = "length_queue")]
+    pub pending_jobs: u64,
+    #[serde(alias = "state", default = "default_operational")]
+    pub operational: bool,
+}
+
+// Session states after which a stored session cannot take new jobs
+const SESSION_ENDED_STATES: [&str; 2] = ["closed", "canceled"];
+
+/// Contents of the session file
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredSession {
+    session_id: String,
+    backend: String,
+    api_url: String,
+}
+
+/// Request shape handed to the transport by the retry loop
+enum Method {
+    Post(Value),
+    Get,
+    Delete,
+}
 
 pub struct QiskitRuntimeService {
-    client: Client,
+    transport: Arc<dyn RuntimeTransport>,
+    custom_transport: bool, // Injected via with_transport: used even without credentials
     api_token: String,
+    api_url: String,
+    instance: String,
     active_session: Option<String>,
+    session_armed: Arc<AtomicBool>, // Shared with the open session's guard; cleared by close_session
+    session_file: Option<PathBuf>, // Where the live session id is kept so a restart can reattach
+    outstanding_jobs: Arc<Mutex<HashSet<String>>>, // Shared with JobStatusClient, which retires finished jobs
+    retry_policy: RetryPolicy,
+    rate_limiter: Arc<RateLimiter>, // Shared with JobStatusClient so status polls count against the same budget
+    twin: DigitalTwin,
+    dry_run: bool, // Hermetic mode: never touch the network, deterministic synthetic results
+    dry_run_jobs: AtomicU64,
+    twin_results: Mutex<HashMap<String, JobResult>>, // Twin jobs complete at submission; kept for get_job_result
 }
 
 impl QiskitRuntimeService {
     pub fn new() -> Self {
-        let api_token = env::var("IBM_QUANTUM_API_TOKEN").unwrap_or_else(|_| {
-            info!("QPU: 'IBM_QUANTUM_API_TOKEN' not set. Switching to DIGITAL TWIN mode.");
-            "DIGITAL_TWIN_MOCK_TOKEN".to_string()
+        Self::with_token_sources(&[TokenSource::Env])
+    }
+
+    /// Reads the API token from the first source that yields one (secret file, helper command,
+    /// environment). Digital-twin mode only when none of them does.
+    pub fn with_token_sources(sources: &[TokenSource]) -> Self {
+        let api_token = token::resolve_token(sources).unwrap_or_else(|| {
+            let tried: Vec<String> = sources.iter().map(TokenSource::describe).collect();
+            info!("QPU: No IBM API token from [{}]. Switching to DIGITAL TWIN mode.", tried.join(", "));
+            DIGITAL_TWIN_TOKEN.to_string()
         });
-        
-        let client = Client::builder()
-            .timeout(Duration::from_secs(10))
-            .build()
-            .unwrap();
+        Self::with_token(api_token)
+    }
+
+    /// Digital twin regardless of configured credentials, e.g. the fallback for a swamped device
+    pub fn digital_twin() -> Self {
+        Self::with_token(DIGITAL_TWIN_TOKEN.to_string())
+    }
+
+    fn with_token(api_token: String) -> Self {
+        // Premium / dedicated plans use a different host and hub/group/project instance
+        let api_url = env::var("IBM_QUANTUM_API_URL").unwrap_or_else(|_| DEFAULT_API_URL.to_string());
+        let instance = env::var("IBM_QUANTUM_INSTANCE").unwrap_or_else(|_| DEFAULT_INSTANCE.to_string());
+
         Self {
-            client,
+            transport: Arc::new(ReqwestTransport::new(&api_url, &api_token)),
+            custom_transport: false,
             api_token,
+            api_url,
+            instance,
             active_session: None,
+            session_armed: Arc::new(AtomicBool::new(false)),
+            session_file: None,
+            outstanding_jobs: Arc::new(Mutex::new(HashSet::new())),
+            retry_policy: RetryPolicy::default(),
+            rate_limiter: Arc::new(RateLimiter::new(DEFAULT_REQUESTS_PER_SECOND)),
+            twin: DigitalTwin::new(),
+            dry_run: false,
+            dry_run_jobs: AtomicU64::new(0),
+            twin_results: Mutex::new(HashMap::new()),
         }
     }
 
-    /// Opens a Session (Context Context) on the IBM Quantum Backend
-    pub async fn open_session(&mut self, backend_name: &str) -> Result<(), Box<dyn Error>> {
-        let url = format!("{}/sessions", IBM_QUANTUM_API_URL);
+    /// True when running without IBM credentials (or in dry-run); all primitives are simulated locally
+    pub fn is_digital_twin(&self) -> bool {
+        self.dry_run || (self.api_token == DIGITAL_TWIN_TOKEN && !self.custom_transport)
+    }
+
+    /// Builder: Dry-run keeps every call offline even with credentials, and replaces the twin's
+    /// shot noise and random job IDs with deterministic values
+    #[allow(dead_code)]
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Configures the Digital Twin's statistics: noise scales as 1/sqrt(shots) and is reproducible
+    /// under a fixed seed (see `DigitalTwin` for the noise model). No effect on real QPU jobs.
+    #[allow(dead_code)]
+    pub fn set_twin_config(&mut self, shots: u32, seed: u64) {
+        self.twin = DigitalTwin::with_config(shots, seed);
+    }
+
+    /// Currently open runtime session, if any
+    pub fn session_id(&self) -> Option<&str> {
+        self.active_session.as_deref()
+    }
+
+    /// Builder: Target a specific Runtime host and instance (e.g. `https://api.quantum.ibm.com/runtime`)
+    #[allow(dead_code)]
+    pub fn with_endpoint(mut self, url: &str, instance: &str) -> Self {
+        self.api_url = url.trim_end_matches('/').to_string();
+        self.instance = instance.to_string();
+        if !self.custom_transport {
+            self.transport = Arc::new(ReqwestTransport::new(&self.api_url, &self.api_token));
+        }
+        self
+    }
+
+    /// Builder: Persist the open session id to `path`, so `resume_session` can reattach after a
+    /// restart instead of leaking a billed session. An empty path disables persistence.
+    pub fn with_session_file(mut self, path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        self.session_file = (!path.as_os_str().is_empty()).then(|| path.to_path_buf());
+        self
+    }
+
+    /// Builder: Replace the HTTP layer, e.g. with a fake serving canned session/job responses.
+    /// The injected transport is used even without IBM credentials (dry-run still wins).
+    #[allow(dead_code)]
+    pub fn with_transport(mut self, transport: Arc<dyn RuntimeTransport>) -> Self {
+        self.transport = transport;
+        self.custom_transport = true;
+        self
+    }
+
+    /// Builder: Override the retry policy used for all Runtime HTTP calls
+    #[allow(dead_code)]
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Builder: Cap outbound requests (retries and status polls included) at `requests_per_second`,
+    /// smoothing bursts so the adaptive cadence does not trip IBM's throttling; <= 0 disables the cap
+    pub fn with_rate_limit(mut self, requests_per_second: f64) -> Self {
+        self.rate_limiter = Arc::new(RateLimiter::new(requests_per_second));
+        self
+    }
+
+    /// Sends a request, retrying transient failures with exponential backoff. POSTs carry no
+    /// idempotency key, so they are only retried when the service cannot have acted on them (see
+    /// `TransportError::is_retryable`): a duplicate `/jobs` submission is a second billed job.
+    /// Non-retryable errors (other 4xx/5xx, decode failures) are returned immediately for the caller to inspect.
+    async fn send_with_retry(&self, method: Method, path: &str) -> Result<Value, TransportError> {
+        let max_attempts = self.retry_policy.max_attempts.max(1);
+        let mut attempt = 1;
+        loop {
+            self.rate_limiter.acquire().await;
+            let result = match &method {
+                Method::Post(body) => self.transport.post(path, body.clone()).await,
+                Method::Get => self.transport.get(path).await,
+                Method::Delete => self.transport.delete(path).await,
+            };
+            let idempotent = !matches!(method, Method::Post(_));
+            let delay = match result {
+                Err(e) if e.is_retryable(idempotent) && attempt < max_attempts => {
+                    // Honour Retry-After, but never wait longer than our own backoff ceiling
+                    let delay = match &e {
+                        TransportError::Status { retry_after: Some(d), .. } => (*d).min(MAX_BACKOFF),
+                        _ => self.retry_policy.backoff(attempt),
+                    };
+                    warn!("QiskitRuntime: {} (attempt {}/{}), retrying in {:?}", e, attempt, max_attempts, delay);
+                    delay
+                }
+                other => return other,
+            };
+            sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Reattaches to the session recorded in the session file, if the service still reports it
+    /// usable (`GET /sessions/{id}`). A closed, unknown or unreadable session clears the file;
+    /// an unreachable service leaves it for the next attempt.
+    /// Returns true when a session is now active.
+    pub async fn resume_session(&mut self) -> bool {
+        if self.active_session.is_some() {
+            return true;
+        }
+        if self.is_digital_twin() {
+            return false;
+        }
+        let Some(path) = self.session_file.clone() else {
+            return false;
+        };
+        let stored: Option<StoredSession> = std::fs::read_to_string(&path).ok()
+            .and_then(|text| serde_json::from_str(&text).ok());
+        let Some(stored) = stored else {
+            if path.exists() {
+                warn!("QiskitRuntime: Unreadable session file {}, discarding it", path.display());
+                self.clear_session_file();
+            }
+            return false;
+        };
+        if stored.api_url != self.api_url {
+            info!("QiskitRuntime: Stored session [{}] belongs to {}, not resuming", stored.session_id, stored.api_url);
+            self.clear_session_file();
+            return false;
+        }
+
+        match self.send_with_retry(Method::Get, &format!("/sessions/{}", stored.session_id)).await {
+            Ok(json) if !SESSION_ENDED_STATES.contains(&json["state"].as_str().unwrap_or("open")) => {
+                info!("QiskitRuntime: Resumed Session [{}] on {}", stored.session_id, stored.backend);
+                self.active_session = Some(stored.session_id);
+                true
+            }
+            Ok(json) => {
+                info!("QiskitRuntime: Stored session [{}] is {}, starting fresh", stored.session_id, json["state"]);
+                self.clear_session_file();
+                false
+            }
+            Err(e) => {
+                warn!("QiskitRuntime: Stored session [{}] not resumable: {}", stored.session_id, e);
+                if e.status().is_some() {
+                    self.clear_session_file(); // The service answered (e.g. 404): the session is gone
+                }
+                false
+            }
+        }
+    }
+
+    fn persist_session(&self, session_id: &str, backend: &str) {
+        let Some(path) = &self.session_file else { return };
+        let stored = StoredSession {
+            session_id: session_id.to_string(),
+            backend: backend.to_string(),
+            api_url: self.api_url.clone(),
+        };
+        let written = serde_json::to_string(&stored)
+            .map_err(std::io::Error::other)
+            .and_then(|json| std::fs::write(path, json));
+        if let Err(e) = written {
+            warn!("QiskitRuntime: Could not persist session to {}: {}", path.display(), e);
+        }
+    }
+
+    fn clear_session_file(&self) {
+        if let Some(path) = &self.session_file {
+            match std::fs::remove_file(path) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => warn!("QiskitRuntime: Could not remove {}: {}", path.display(), e),
+            }
+        }
+    }
+
+    /// Opens a Session (Context Context) on the IBM Quantum Backend. Keep the returned guard for
+    /// as long as the session is in use: dropping it before `close_session` deletes the session.
+    pub async fn open_session(&mut self, backend_name: &str) -> Result<SessionGuard, Box<dyn Error>> {
+        if self.is_digital_twin() {
+            let prefix = if self.dry_run { "dryrun" } else { "twin" };
+            let id = format!("{}-session-{}", prefix, backend_name);
+            if self.dry_run {
+                info!(target: "dryrun", "QiskitRuntime: Open session [{}]", id);
+            }
+            info!("QiskitRuntime: Digital Twin Session [{}]", id);
+            let guard = SessionGuard::simulated(&id);
+            self.active_session = Some(id);
+            return Ok(guard);
+        }
+
         let body = json!({
             "backend": backend_name,
-            "instance": "ibm-q/open/main"
+            "instance": self.instance
         });
 
         debug!("QiskitRuntime: Opening Session on {}", backend_name);
-        let resp = self.client.post(&url)
-            .header("Authorization", format!("Bearer {}", self.api_token))
-            .json(&body)
-            .send()
-            .await?;
-
-        if resp.status().is_success() {
-            let json: Value = resp.json().await?;
-            if let Some(id) = json["id"].as_str() {
+        let json = match self.send_with_retry(Method::Post(body), "/sessions").await {
+            Ok(json) => json,
+            Err(e) => {
+                error!("QiskitRuntime: Handshake Failed: {}", e);
+                return Err(format!("Session creation failed: {}", e).into());
+            }
+        };
+
+        match json["id"].as_str() {
+            Some(id) => {
                 self.active_session = Some(id.to_string());
                 info!("QiskitRuntime: Session Established [{}]", id);
-                return Ok(());
-            } else {
+                self.persist_session(id, backend_name);
+                self.session_armed = Arc::new(AtomicBool::new(true));
+                Ok(SessionGuard::new(id, self.transport.clone(), self.session_file.clone(), self.session_armed.clone()))
+            }
+            None => {
                 error!("QiskitRuntime: Session created but ID missing");
-                return Err("Missing Session ID".into());
+                Err("Missing Session ID".into())
             }
         }
-        
-        let err_text = resp.text().await?;
-        error!("QiskitRuntime: Handshake Failed: {:?}", err_text);
-        Err(format!("Session creation failed: {}", err_text).into())
     }
 
     /// Dispatches a 'Sampler' or 'Estimator' primitive job
-    pub async fn run_job(&self, program_id: &str, theta: f64) -> Result<String, Box<dyn Error>> {
+    /// Digital Twin: evaluated locally and returned Completed; Real QPU: returned Queued
+    pub async fn run_job(&self, program_id: &str, theta: f64) -> Result<JobResult, Box<dyn Error>> {
         let session_id = self.active_session.as_ref().ok_or("No active Qiskit Runtime Session")?;
-        let url = format!("{}/jobs", IBM_QUANTUM_API_URL);
-        
+
+        if self.is_digital_twin() {
+            let ((expectation, std_error), job_id) = if self.dry_run {
+                let job_id = format!("dryrun-{:06}", self.dry_run_jobs.fetch_add(1, Ordering::Relaxed));
+                info!(target: "dryrun", "QiskitRuntime: Job {} [{}] theta={:.4}", job_id, program_id, theta);
+                (self.twin.ideal(theta), job_id)
+            } else {
+                (self.twin.estimate(theta), self.twin.synthetic_job_id())
+            };
+            info!("QiskitRuntime: Digital Twin Job {} [{}] -> <Z>={:.4}", job_id, program_id, expectation);
+            let result = JobResult {
+                job_id,
+                status: JobStatus::Completed,
+                expectation: Some(expectation),
+                std_error: Some(std_error),
+                shots: self.twin.shots(),
+            };
+            self.twin_results.lock().unwrap().insert(result.job_id.clone(), result.clone());
+            return Ok(result);
+        }
+
         // JIT Parameter Binding
         let params = json!({
             "market_theta": theta
@@ -100,32 +480,365 @@ impl QiskitRuntimeService {
         });
 
         debug!("QiskitRuntime: Dispatching Job to {}", session_id);
-        let resp = self.client.post(&url)
-            .header("Authorization", format!("Bearer {}", self.api_token))
-            .json(&body)
-            .send()
-            .await?;
-
-        if resp.status().is_success() {
-            let json: Value = resp.json().await?;
-            let job_id = json["id"].as_str().unwrap_or("unknown");
-            info!("QiskitRuntime: Job Submitted -> ID {}", job_id);
-            Ok(job_id.to_string())
+        let json = match self.send_with_retry(Method::Post(body), "/jobs").await {
+            Ok(json) => json,
+            Err(e) => {
+                error!("QiskitRuntime: Job Dispatch Error: {}", e);
+                return Err(format!("Job dispatch failed: {}", e).into());
+            }
+        };
+
+        // Without an id the job can be neither tracked nor cancelled, so the submission is not taken
+        let Some(job_id) = json["id"].as_str() else {
+            error!("QiskitRuntime: Job accepted but ID missing: {}", json);
+            return Err(TransportError::Decode(format!("job response without an id: {}", json)).into());
+        };
+        info!("QiskitRuntime: Job Submitted -> ID {}", job_id);
+        self.outstanding_jobs.lock().unwrap().insert(job_id.to_string());
+        Ok(JobResult {
+            job_id: job_id.to_string(),
+            status: JobStatus::Queued,
+            expectation: None,
+            std_error: None,
+            shots: 0,
+        })
+    }
+
+    /// Status of a submitted job and, once Completed, its estimator result
+    /// (V2 primitives `results[0].data.evs/stds`, or V1 `values` + `metadata.variance`)
+    #[allow(dead_code)]
+    pub async fn get_job_result(&self, job_id: &str) -> Result<JobResult, Box<dyn Error>> {
+        if self.is_digital_twin() {
+            return self.twin_results.lock().unwrap().get(job_id).cloned()
+                .ok_or_else(|| format!("Unknown Digital Twin job {}", job_id).into());
+        }
+
+        let status = self.status_client().status(job_id).await.map_err(|e| e.to_string())?;
+        let mut result = JobResult { job_id: job_id.to_string(), status, expectation: None, std_error: None, shots: 0 };
+        if status != JobStatus::Completed {
+            return Ok(result);
+        }
+
+        let json = self.send_with_retry(Method::Get, &format!("/jobs/{}/results", job_id)).await?;
+        let first = |v: &Value| v.as_f64().or_else(|| v.get(0).and_then(Value::as_f64));
+        if let Some(pub_result) = json["results"].get(0) {
+            result.expectation = first(&pub_result["data"]["evs"]);
+            result.std_error = first(&pub_result["data"]["stds"]);
+            result.shots = pub_result["metadata"]["shots"].as_u64().unwrap_or(0) as u32;
         } else {
-            let err = resp.text().await?;
-            error!("QiskitRuntime: Job Dispatch Error: {}", err);
-            Err(format!("Job dispatch failed: {}", err).into())
+            result.expectation = first(&json["values"]);
+            let meta = &json["metadata"][0];
+            result.shots = meta["shots"].as_u64().unwrap_or(0) as u32;
+            result.std_error = meta["variance"].as_f64()
+                .filter(|_| result.shots > 0)
+                .map(|var| (var / result.shots as f64).sqrt());
+        }
+        if result.expectation.is_none() {
+            return Err(format!("Job {} results carry no expectation value: {}", job_id, json).into());
+        }
+        Ok(result)
+    }
+
+    /// Cancels an in-flight job. A job that already reached a terminal state (409) or that the
+    /// service no longer knows (404) is treated as success. A failed cancel leaves the job
+    /// outstanding, so `cancel_outstanding_jobs` tries it again.
+    pub async fn cancel_job(&self, job_id: &str) -> Result<(), Box<dyn Error>> {
+        if self.is_digital_twin() {
+            self.outstanding_jobs.lock().unwrap().remove(job_id);
+            if self.dry_run {
+                info!(target: "dryrun", "QiskitRuntime: Cancel {}", job_id);
+            }
+            info!("QiskitRuntime: Digital Twin Job {} Cancelled", job_id);
+            return Ok(());
+        }
+
+        match self.send_with_retry(Method::Post(Value::Null), &format!("/jobs/{}/cancel", job_id)).await {
+            Ok(_) => {
+                self.outstanding_jobs.lock().unwrap().remove(job_id);
+                info!("QiskitRuntime: Job {} Cancelled", job_id);
+                Ok(())
+            }
+            Err(e) if is_gone(&e) => {
+                self.outstanding_jobs.lock().unwrap().remove(job_id);
+                info!("QiskitRuntime: Job {} already finished, nothing to cancel", job_id);
+                Ok(())
+            }
+            Err(e) => {
+                error!("QiskitRuntime: Cancel Failed for {}: {}", job_id, e);
+                Err(format!("Job cancel failed: {}", e).into())
+            }
+        }
+    }
+
+    /// Cancels every job submitted by this service that hasn't been cancelled yet.
+    /// Returns how many cancellations succeeded.
+    pub async fn cancel_outstanding_jobs(&self) -> usize {
+        let jobs: Vec<String> = self.outstanding_jobs.lock().unwrap().iter().cloned().collect();
+        let mut cancelled = 0;
+        for id in jobs {
+            match self.cancel_job(&id).await {
+                Ok(()) => cancelled += 1,
+                Err(e) => warn!("QiskitRuntime: Could not cancel {}: {}", id, e),
+            }
         }
+        cancelled
+    }
+
+    /// Handle for polling job state from a spawned task, independent of this service's borrow
+    pub fn status_client(&self) -> JobStatusClient {
+        JobStatusClient {
+            transport: self.transport.clone(),
+            simulated: self.is_digital_twin(),
+            outstanding_jobs: self.outstanding_jobs.clone(),
+            rate_limiter: self.rate_limiter.clone(),
+        }
+    }
+
+    /// Queue depth and availability of `backend_name` (Digital Twin: always idle and operational)
+    pub async fn backend_status(&self, backend_name: &str) -> Result<BackendStatus, Box<dyn Error>> {
+        if self.is_digital_twin() {
+            return Ok(BackendStatus { pending_jobs: 0, operational: true });
+        }
+
+        let json = match self.send_with_retry(Method::Get, &format!("/backends/{}/status", backend_name)).await {
+            Ok(json) => json,
+            Err(e) => {
+                error!("QiskitRuntime: Status of {} unavailable: {}", backend_name, e);
+                return Err(format!("Backend status failed: {}", e).into());
+            }
+        };
+        let status: BackendStatus = serde_json::from_value(json)?;
+        debug!("QiskitRuntime: {} has {} pending jobs (operational: {})", backend_name, status.pending_jobs, status.operational);
+        Ok(status)
+    }
+
+    /// Lists the backends visible to this token (Digital Twin: a fixed Heron-like roster)
+    #[allow(dead_code)]
+    pub async fn list_backends(&self) -> Result<Vec<BackendInfo>, Box<dyn Error>> {
+        if self.is_digital_twin() {
+            if self.dry_run {
+                info!(target: "dryrun", "QiskitRuntime: Serving the Digital Twin backend roster");
+            }
+            return Ok(DigitalTwin::backends());
+        }
+
+        let json = match self.send_with_retry(Method::Get, "/backends").await {
+            Ok(json) => json,
+            Err(e) => {
+                error!("QiskitRuntime: Backend Listing Failed: {}", e);
+                return Err(format!("Backend listing failed: {}", e).into());
+            }
+        };
+
+        // The API has served both a bare array and a `{"backends": [...]}` envelope
+        let list = match json {
+            Value::Array(_) => json,
+            mut obj => obj["backends"].take(),
+        };
+        let backends: Vec<BackendInfo> = serde_json::from_value(list)?;
+        debug!("QiskitRuntime: {} backends visible", backends.len());
+        Ok(backends)
     }
 
     pub async fn close_session(&self) {
         if let Some(id) = &self.active_session {
-            let url = format!("{}/sessions/{}", IBM_QUANTUM_API_URL, id);
-            let _ = self.client.delete(&url)
-                .header("Authorization", format!("Bearer {}", self.api_token))
-                .send()
-                .await;
+            if self.is_digital_twin() {
+                if self.dry_run {
+                    info!(target: "dryrun", "QiskitRuntime: Close session [{}]", id);
+                }
+                info!("QiskitRuntime: Digital Twin Session Closed [{}]", id);
+                return;
+            }
+            // On failure the session file stays, so the next start can resume (or retire) the session
+            if let Err(e) = self.send_with_retry(Method::Delete, &format!("/sessions/{}", id)).await {
+                warn!("QiskitRuntime: Session close for [{}] failed: {}", id, e);
+                return;
+            }
+            self.session_armed.store(false, Ordering::SeqCst);
+            self.clear_session_file();
             info!("QiskitRuntime: Session Closed [{}]", id);
         }
     }
 }
+
+/// Polls `GET /jobs/{id}`. Cloneable and Send, so lifecycle watchers can run as their own tasks.
+#[derive(Clone)]
+pub struct JobStatusClient {
+    transport: Arc<dyn RuntimeTransport>,
+    simulated: bool, // Digital Twin / dry-run jobs complete at submission
+    outstanding_jobs: Arc<Mutex<HashSet<String>>>,
+    rate_limiter: Arc<RateLimiter>,
+}
+
+impl JobStatusClient {
+    /// Current status of a job; terminal jobs are dropped from the service's outstanding set
+    pub async fn status(&self, job_id: &str) -> Result<JobStatus, Box<dyn Error + Send + Sync>> {
+        let status = if self.simulated {
+            JobStatus::Completed
+        } else {
+            self.rate_limiter.acquire().await;
+            let json = self.transport.get(&format!("/jobs/{}", job_id)).await?;
+            let raw = json["status"].as_str().or_else(|| json["state"]["status"].as_str()).unwrap_or("");
+            JobStatus::parse(raw).ok_or_else(|| format!("Unrecognised status '{}' for job {}", raw, job_id))?
+        };
+
+        if status.is_terminal() {
+            self.outstanding_jobs.lock().unwrap().remove(job_id);
+        }
+        Ok(status)
+    }
+
+    /// Cancels a job from its watcher, as `QiskitRuntimeService::cancel_job` does: the job stays
+    /// outstanding unless the cancel succeeded or it had already finished (409) or gone (404)
+    pub async fn cancel(&self, job_id: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if !self.simulated {
+            self.rate_limiter.acquire().await;
+            match self.transport.post(&format!("/jobs/{}/cancel", job_id), Value::Null).await {
+                Ok(_) => {}
+                Err(e) if is_gone(&e) => {}
+                Err(e) => return Err(format!("Job cancel failed: {}", e).into()),
+            }
+        }
+        self.outstanding_jobs.lock().unwrap().remove(job_id);
+        Ok(())
+    }
+}
+
+/// The job has nothing left to cancel: it already finished (409) or the service no longer knows it (404)
+fn is_gone(e: &TransportError) -> bool {
+    matches!(e.status(), Some(404 | 409))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::future::BoxFuture;
+    use std::time::Instant;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use transport::NetworkFailure;
+
+    /// Answers every request with the same canned result
+    struct FixedTransport(Result<Value, TransportError>);
+
+    impl RuntimeTransport for FixedTransport {
+        fn post<'a>(&'a self, _path: &'a str, _body: Value) -> BoxFuture<'a, Result<Value, TransportError>> {
+            Box::pin(async move { self.0.clone() })
+        }
+
+        fn get<'a>(&'a self, _path: &'a str) -> BoxFuture<'a, Result<Value, TransportError>> {
+            Box::pin(async move { self.0.clone() })
+        }
+
+        fn delete<'a>(&'a self, _path: &'a str) -> BoxFuture<'a, Result<Value, TransportError>> {
+            Box::pin(async move { self.0.clone() })
+        }
+    }
+
+    fn service_answering(response: Result<Value, TransportError>) -> QiskitRuntimeService {
+        let service = QiskitRuntimeService::digital_twin()
+            .with_transport(Arc::new(FixedTransport(response)))
+            .with_rate_limit(0.0);
+        service.outstanding_jobs.lock().unwrap().insert("job-1".to_string());
+        service
+    }
+
+    fn http(status: u16) -> TransportError {
+        TransportError::Status { status, retry_after: None, body: String::new() }
+    }
+
+    #[tokio::test]
+    async fn cancelling_a_digital_twin_job_succeeds() {
+        let mut service = QiskitRuntimeService::digital_twin();
+        let _session = service.open_session("ibm_torino").await.unwrap();
+        let job = service.run_job("hedge_qaoa_v1", 0.5).await.unwrap();
+        assert!(service.cancel_job(&job.job_id).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn failed_cancel_keeps_the_job_outstanding() {
+        let service = service_answering(Err(http(500)));
+        assert!(service.cancel_job("job-1").await.is_err());
+        assert!(service.outstanding_jobs.lock().unwrap().contains("job-1"));
+        assert!(service.status_client().cancel("job-1").await.is_err());
+        assert!(service.outstanding_jobs.lock().unwrap().contains("job-1"));
+    }
+
+    #[tokio::test]
+    async fn finished_or_unknown_jobs_count_as_cancelled() {
+        for status in [404, 409] {
+            let service = service_answering(Err(http(status)));
+            assert!(service.cancel_job("job-1").await.is_ok());
+            assert!(service.outstanding_jobs.lock().unwrap().is_empty());
+        }
+    }
+
+    #[test]
+    fn posts_are_only_resent_when_the_service_cannot_have_acted_on_them() {
+        let deferred = |status| TransportError::Status { status, retry_after: Some(Duration::from_secs(1)), body: String::new() };
+        let network = |failure| TransportError::Network { message: String::new(), failure };
+        for status in [429, 502, 503, 504] {
+            assert!(http(status).is_retryable(true), "GET {}", status);
+        }
+        for status in [429, 502, 503, 504] {
+            assert!(!http(status).is_retryable(false), "POST {}", status);
+        }
+        assert!(deferred(429).is_retryable(false));
+        assert!(deferred(503).is_retryable(false));
+        assert!(!deferred(502).is_retryable(false));
+        assert!(network(NetworkFailure::Connect).is_retryable(false));
+        assert!(!network(NetworkFailure::Timeout).is_retryable(false));
+    }
+
+    #[tokio::test]
+    async fn a_job_response_without_an_id_is_an_error() {
+        let mut service = service_answering(Ok(json!({ "status": "Queued" })));
+        service.active_session = Some("session-1".to_string());
+        let error = service.run_job("hedge_qaoa_v1", 0.5).await.unwrap_err();
+        assert!(error.to_string().contains("without an id"), "{}", error);
+        assert_eq!(*service.outstanding_jobs.lock().unwrap(), HashSet::from(["job-1".to_string()]));
+    }
+
+    /// Local HTTP server answering every request with `response`; returns its URL and the arrival time of each request
+    async fn mock_runtime(response: &'static str) -> (String, Arc<Mutex<Vec<Instant>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let arrivals = Arc::new(Mutex::new(Vec::new()));
+        let seen = arrivals.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match stream.read(&mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                seen.lock().unwrap().push(Instant::now());
+                stream.write_all(response.as_bytes()).await.ok();
+                stream.shutdown().await.ok();
+            }
+        });
+        (url, arrivals)
+    }
+
+    #[tokio::test]
+    async fn retry_after_is_waited_out_until_the_attempts_run_out() {
+        let (url, arrivals) = mock_runtime(
+            "HTTP/1.1 503 Service Unavailable\r\nRetry-After: 1\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").await;
+        // A 1 ms backoff: any gap near a second can only come from Retry-After
+        let service = QiskitRuntimeService::digital_twin()
+            .with_transport(Arc::new(ReqwestTransport::new(&url, "token")))
+            .with_retry_policy(RetryPolicy { max_attempts: 3, base_delay: Duration::from_millis(1) })
+            .with_rate_limit(0.0);
+
+        let result = service.send_with_retry(Method::Get, "/jobs/job-1").await;
+        assert_eq!(result.unwrap_err().status(), Some(503));
+        let arrivals = arrivals.lock().unwrap();
+        assert_eq!(arrivals.len(), 3, "one request per attempt, none after the budget");
+        for gap in arrivals.windows(2).map(|w| w[1] - w[0]) {
+            assert!(gap >= Duration::from_secs(1), "resent after {:?}", gap);
+        }
+    }
+}
@@ -1,4 +1,17 @@
+use log::{debug, info};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::env;
+use std::sync::Mutex;
+use std::time::Duration;
+use thiserror::Error;
+use crate::sre::T1_SAFETY_FRACTION;
+use super::sigv4::{self, AwsCredentials, SignableRequest};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AtomCoordinates {
@@ -21,40 +34,469 @@ pub struct AnalogHamiltonianProgram {
     pub pulses: Vec<RydbergPulse>,
 }
 
+/// Measured register configurations ("0110" = atoms 1 and 2 excited) mapped to their counts
+pub type ShotCounts = HashMap<String, u64>;
+
+const MOCK_SHOTS: u64 = 1000;
+const AQUILA_ARN: &str = "arn:aws:braket:us-east-1::device/qpu/quera/Aquila";
+const BRAKET_DEFAULT_REGION: &str = "us-east-1"; // Aquila's home region
+const DRY_RUN_SEED: u64 = 42;
+/// Environment variable holding Pasqal's bearer token
+pub const PASQAL_API_KEY_ENV: &str = "PASQAL_API_KEY";
+
+/// Trap geometry constraints enforced by the provider (micrometres)
+#[derive(Debug, Clone, Copy)]
+pub struct RegisterLimits {
+    pub min_spacing: f64,
+    pub max_extent: f64, // Side of the square field of view
+}
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum RegisterError {
+    #[error("atom register is empty")]
+    Empty,
+    #[error("atoms {a} and {b} are {distance:.2}um apart (minimum {min:.2}um)")]
+    SpacingTooSmall { a: usize, b: usize, distance: f64, min: f64 },
+    #[error("register spans {width:.2}um x {height:.2}um (field of view {max:.2}um)")]
+    OutOfFieldOfView { width: f64, height: f64, max: f64 },
+}
+
+/// Drive constraints: Rabi frequency ceiling (rad/us) and total sequence budget (us)
+#[derive(Debug, Clone, Copy)]
+pub struct PulseLimits {
+    pub max_omega: f64,
+    pub coherence_budget: f64,
+}
+
+impl PulseLimits {
+    /// Budget derived from T1 with the same safety fraction CoherenceVerifier applies to gate circuits
+    pub fn from_t1(max_omega: f64, t1_micros: f64) -> Self {
+        Self { max_omega, coherence_budget: t1_micros * T1_SAFETY_FRACTION }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum PulseError {
+    #[error("pulse {index} has invalid duration {duration}")]
+    InvalidDuration { index: usize, duration: f64 },
+    #[error("pulse {index} drives omega={omega:.3} rad/us above the {max:.3} rad/us limit")]
+    OmegaTooHigh { index: usize, omega: f64, max: f64 },
+    #[error("sequence lasts {total:.3}us, exceeding the {budget:.3}us coherence budget")]
+    ExceedsCoherenceBudget { total: f64, budget: f64 },
+}
+
+/// Amazon Braket submission target for QuEra. Braket writes results to S3, so a bucket is required.
+/// Populated from BRAKET_REGION (else AWS_REGION), BRAKET_DEVICE_ARN, BRAKET_S3_BUCKET,
+/// BRAKET_S3_PREFIX and BRAKET_SHOTS.
+#[derive(Debug, Clone)]
+pub struct BraketTarget {
+    pub region: String,
+    pub device_arn: String,
+    pub s3_bucket: String,
+    pub s3_prefix: String,
+    pub shots: u32,
+}
+
+impl BraketTarget {
+    pub fn from_env() -> Self {
+        let var = |k: &str| env::var(k).ok().filter(|v| !v.is_empty());
+        Self {
+            region: var("BRAKET_REGION").or_else(|| var("AWS_REGION")).unwrap_or_else(|| BRAKET_DEFAULT_REGION.to_string()),
+            device_arn: var("BRAKET_DEVICE_ARN").unwrap_or_else(|| AQUILA_ARN.to_string()),
+            s3_bucket: var("BRAKET_S3_BUCKET").unwrap_or_default(),
+            s3_prefix: var("BRAKET_S3_PREFIX").unwrap_or_else(|| "sentinel".to_string()),
+            shots: var("BRAKET_SHOTS").and_then(|n| n.parse().ok()).unwrap_or(100),
+        }
+    }
+
+    pub fn host(&self) -> String {
+        format!("braket.{}.amazonaws.com", self.region)
+    }
+
+    pub fn endpoint(&self) -> String {
+        format!("https://{}", self.host())
+    }
+}
+
+/// Braket AHS IR (`braket.ir.ahs.program` v1) in SI units: metres, seconds, rad/s.
+/// Each piecewise-constant pulse becomes a waveform sample at its midpoint; amplitude is pinned to
+/// zero at both ends, as Braket requires, while detuning and phase hold their first/last values.
+pub fn to_braket_ahs(program: &AnalogHamiltonianProgram) -> Value {
+    const UM: f64 = 1e-6; // um -> m
+    const US: f64 = 1e-6; // us -> s
+    const RAD_PER_US: f64 = 1e6; // rad/us -> rad/s
+
+    let sites: Vec<[f64; 2]> = program.atoms.iter().map(|a| [a.x * UM, a.y * UM]).collect();
+    let total: f64 = program.pulses.iter().map(|p| p.duration.max(0.0)).sum();
+
+    let mut times = vec![0.0];
+    let (mut amplitude, mut detuning, mut phase) = (vec![0.0], Vec::new(), Vec::new());
+    let mut start = 0.0;
+    for pulse in &program.pulses {
+        let duration = pulse.duration.max(0.0);
+        times.push((start + duration / 2.0) * US);
+        amplitude.push(pulse.omega * RAD_PER_US);
+        detuning.push(pulse.delta * RAD_PER_US);
+        phase.push(pulse.phase);
+        start += duration;
+    }
+    times.push(total * US);
+    amplitude.push(0.0);
+    let edge = |v: &Vec<f64>| -> Vec<f64> {
+        let (first, last) = (v.first().copied().unwrap_or(0.0), v.last().copied().unwrap_or(0.0));
+        std::iter::once(first).chain(v.iter().copied()).chain(std::iter::once(last)).collect()
+    };
+    let series = |values: Vec<f64>| json!({ "time_series": { "times": times, "values": values }, "pattern": "uniform" });
+
+    json!({
+        "braketSchemaHeader": { "name": "braket.ir.ahs.program", "version": "1" },
+        "setup": { "ahs_register": { "sites": sites, "filling": vec![1; sites.len()] } },
+        "hamiltonian": {
+            "drivingFields": [{
+                "amplitude": series(amplitude),
+                "phase": series(edge(&phase)),
+                "detuning": series(edge(&detuning)),
+            }],
+            "localDetuning": [],
+        },
+    })
+}
+
 /// Adapter for Neutral Atom Architectures (Pasqal / QuEra)
 pub struct NeutralAtomAdapter {
-    provider_url: String,
+    client: Client,
+    pub(super) provider_url: String,
     api_key: String,
+    limits: RegisterLimits,
+    pulse_limits: PulseLimits,
+    mock_jobs: Mutex<HashMap<String, AnalogHamiltonianProgram>>, // Programs accepted by the localhost mock
+    pub(super) dry_run: bool, // Hermetic mode: serve every provider from the mock, deterministically
+    braket: Option<BraketTarget>, // QuEra: SigV4-signed Braket path instead of bearer auth
 }
 
 impl NeutralAtomAdapter {
+    /// `api_key` is Pasqal's bearer token; QuEra goes through Braket with AWS credentials from the
+    /// environment (AWS_ACCESS_KEY_ID / AWS_SECRET_ACCESS_KEY / AWS_SESSION_TOKEN) instead.
     pub fn new(provider: &str, api_key: &str) -> Self {
-        let url = match provider {
-            "pasqal" => "https://api.pasqal.com",
-            "quera" => "https://api.amazon.com/braket", // Simplified
-            _ => "https://localhost:8080",
+        let braket = (provider == "quera").then(BraketTarget::from_env);
+        let url = match (provider, &braket) {
+            ("pasqal", _) => "https://api.pasqal.com".to_string(),
+            (_, Some(target)) => target.endpoint(),
+            _ => "https://localhost:8080".to_string(),
+        };
+        // Fresnel: 5um / ~70um field; Aquila: 4um / 75um field
+        let limits = match provider {
+            "pasqal" => RegisterLimits { min_spacing: 5.0, max_extent: 70.0 },
+            "quera" => RegisterLimits { min_spacing: 4.0, max_extent: 75.0 },
+            _ => RegisterLimits { min_spacing: 4.0, max_extent: 100.0 },
+        };
+        // Nominal Rydberg-state lifetimes
+        let pulse_limits = match provider {
+            "pasqal" => PulseLimits::from_t1(12.6, 12.0),
+            "quera" => PulseLimits::from_t1(15.8, 8.0),
+            _ => PulseLimits::from_t1(15.8, 10.0),
         };
         
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap();
+
         Self {
-            provider_url: url.to_string(),
+            client,
+            provider_url: url,
             api_key: api_key.to_string(),
+            limits,
+            pulse_limits,
+            mock_jobs: Mutex::new(HashMap::new()),
+            dry_run: false,
+            braket,
         }
     }
 
-    pub fn submit_analog_program(&self, program: &AnalogHamiltonianProgram) -> Result<String, String> {
-        println!("[NeutralAtom] Submitting Analog Hamiltonian Program to {}", self.provider_url);
-        println!("[NeutralAtom] Register Configuration: {} atoms", program.atoms.len());
-        println!("[NeutralAtom] Pulse Sequence Length: {} steps", program.pulses.len());
+    /// Adapter for `provider` with Pasqal's token read from `PASQAL_API_KEY` (QuEra's AWS
+    /// credentials are read per request)
+    pub fn from_env(provider: &str) -> Self {
+        Self::new(provider, &env::var(PASQAL_API_KEY_ENV).unwrap_or_default())
+    }
 
-        // In a real implementation, this would use reqwest to POST to the endpoint.
-        // For now, we simulate the submission.
-        
-        if program.atoms.is_empty() {
-            return Err("Atom register cannot be empty".to_string());
+    /// Local endpoints (and dry-run) are served by the built-in mock instead of the network
+    pub(super) fn is_mock(&self) -> bool {
+        self.dry_run || self.provider_url.contains("localhost") || self.provider_url.contains("127.0.0.1")
+    }
+
+    /// Checks trap geometry against the provider limits, reporting every violation found
+    pub fn validate_register(&self, program: &AnalogHamiltonianProgram) -> Result<(), Vec<RegisterError>> {
+        let atoms = &program.atoms;
+        if atoms.is_empty() {
+            return Err(vec![RegisterError::Empty]);
+        }
+
+        let mut errors = Vec::new();
+        for a in 0..atoms.len() {
+            for b in (a + 1)..atoms.len() {
+                let distance = (atoms[a].x - atoms[b].x).hypot(atoms[a].y - atoms[b].y);
+                if distance < self.limits.min_spacing {
+                    errors.push(RegisterError::SpacingTooSmall { a, b, distance, min: self.limits.min_spacing });
+                }
+            }
         }
 
-        // Simulate Job ID return
-        Ok(format!("job_{}_{}", self.provider_url, 12345))
+        let (min_x, max_x) = atoms.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), p| (lo.min(p.x), hi.max(p.x)));
+        let (min_y, max_y) = atoms.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), p| (lo.min(p.y), hi.max(p.y)));
+        let (width, height) = (max_x - min_x, max_y - min_y);
+        if width > self.limits.max_extent || height > self.limits.max_extent {
+            errors.push(RegisterError::OutOfFieldOfView { width, height, max: self.limits.max_extent });
+        }
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    /// Checks the drive sequence against hardware bounds, reporting every violation found
+    pub fn validate_pulses(&self, pulses: &[RydbergPulse]) -> Result<(), Vec<PulseError>> {
+        let mut errors = Vec::new();
+        for (index, pulse) in pulses.iter().enumerate() {
+            if !(pulse.duration >= 0.0 && pulse.duration.is_finite()) {
+                errors.push(PulseError::InvalidDuration { index, duration: pulse.duration });
+            }
+            if pulse.omega.abs() > self.pulse_limits.max_omega {
+                errors.push(PulseError::OmegaTooHigh { index, omega: pulse.omega, max: self.pulse_limits.max_omega });
+            }
+        }
+
+        let total: f64 = pulses.iter().map(|p| p.duration.max(0.0)).sum();
+        if total > self.pulse_limits.coherence_budget {
+            errors.push(PulseError::ExceedsCoherenceBudget { total, budget: self.pulse_limits.coherence_budget });
+        }
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    pub async fn submit_analog_program(&self, program: &AnalogHamiltonianProgram) -> Result<String, String> {
+        // Validate before spending a network round-trip
+        if let Err(errors) = self.validate_register(program) {
+            let reasons: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            return Err(format!("Invalid atom register: {}", reasons.join("; ")));
+        }
+        if let Err(errors) = self.validate_pulses(&program.pulses) {
+            let reasons: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            return Err(format!("Invalid pulse sequence: {}", reasons.join("; ")));
+        }
+
+        info!("NeutralAtom: Submitting Analog Hamiltonian Program to {}", self.provider_url);
+        debug!("NeutralAtom: Register of {} atoms, {} pulse steps", program.atoms.len(), program.pulses.len());
+
+        if self.is_mock() {
+            // Simulate Job ID return
+            let mut jobs = self.mock_jobs.lock().unwrap();
+            let job_id = if self.dry_run {
+                let id = format!("dryrun_{}_{:06}", self.provider_url, jobs.len());
+                info!(target: "dryrun", "NeutralAtom: Accepted {} ({} atoms)", id, program.atoms.len());
+                id
+            } else {
+                format!("job_{}_{}", self.provider_url, rand::thread_rng().gen_range(10000..100000))
+            };
+            jobs.insert(job_id.clone(), program.clone());
+            return Ok(job_id);
+        }
+
+        if let Some(target) = &self.braket {
+            return self.submit_braket(target, program).await;
+        }
+
+        let resp = self.client.post(&self.provider_url)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .json(program)
+            .send()
+            .await
+            .map_err(|e| format!("Submission to {} failed: {}", self.provider_url, e))?;
+
+        let status = resp.status();
+        let body = resp.text().await.map_err(|e| format!("Failed to read provider response: {}", e))?;
+        if !status.is_success() {
+            return Err(format!("Provider rejected program (HTTP {}): {}", status, body));
+        }
+
+        // Pasqal returns `id`, Braket-style APIs return `jobId` / `job_id`
+        let json: Value = serde_json::from_str(&body).map_err(|e| format!("Malformed provider response: {}", e))?;
+        let job_id = ["id", "job_id", "jobId"].iter()
+            .find_map(|k| json[*k].as_str())
+            .ok_or_else(|| format!("Provider response missing job ID: {}", body))?;
+
+        info!("NeutralAtom: Job Accepted -> ID {}", job_id);
+        Ok(job_id.to_string())
+    }
+
+    /// Fetches measured bitstring counts for a finished job
+    pub async fn get_analog_result(&self, job_id: &str) -> Result<ShotCounts, String> {
+        if self.is_mock() {
+            let program = self.mock_jobs.lock().unwrap().get(job_id).cloned()
+                .ok_or_else(|| format!("Unknown mock job {}", job_id))?;
+            if self.dry_run {
+                info!(target: "dryrun", "NeutralAtom: Results for {}", job_id);
+                return Ok(self.synthesize_counts(&program, &mut StdRng::seed_from_u64(DRY_RUN_SEED)));
+            }
+            return Ok(self.synthesize_counts(&program, &mut rand::thread_rng()));
+        }
+
+        if let Some(target) = &self.braket {
+            return self.get_braket_result(target, job_id).await;
+        }
+
+        let url = format!("{}/jobs/{}/results", self.provider_url, job_id);
+        let resp = self.client.get(&url)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .send()
+            .await
+            .map_err(|e| format!("Result fetch for {} failed: {}", job_id, e))?;
+
+        let status = resp.status();
+        let body = resp.text().await.map_err(|e| format!("Failed to read provider response: {}", e))?;
+        if !status.is_success() {
+            return Err(format!("Result fetch rejected (HTTP {}): {}", status, body));
+        }
+
+        // Counts arrive either at the top level or nested under `counts` / `result`
+        let json: Value = serde_json::from_str(&body).map_err(|e| format!("Malformed provider response: {}", e))?;
+        let counts = ["counts", "result"].iter()
+            .find_map(|k| json.get(*k).filter(|v| v.is_object()))
+            .unwrap_or(&json)
+            .as_object()
+            .ok_or_else(|| format!("Provider response has no counts: {}", body))?;
+
+        counts.iter()
+            .map(|(bits, n)| n.as_u64().map(|n| (bits.clone(), n)).ok_or_else(|| format!("Non-integer count for {}", bits)))
+            .collect()
+    }
+
+    /// Sends a SigV4-signed request; `path` is as it goes on the wire (already URI-encoded)
+    async fn braket_request(&self, service: &str, region: &str, host: &str, method: &str, path: &str, body: Vec<u8>) -> Result<Value, String> {
+        let creds = AwsCredentials::from_env()?;
+        let signable = SignableRequest { method, host, path, query: &[], payload: &body };
+        let headers = sigv4::sign(&signable, service, region, &creds, chrono::Utc::now());
+
+        let url = format!("https://{}{}", host, path);
+        let mut req = match method {
+            "POST" => self.client.post(&url).header("content-type", "application/json").body(body),
+            _ => self.client.get(&url),
+        };
+        for (name, value) in headers {
+            req = req.header(name, value);
+        }
+        let resp = req.send().await.map_err(|e| format!("{} {} failed: {}", method, url, e))?;
+
+        let status = resp.status();
+        let text = resp.text().await.map_err(|e| format!("Failed to read {} response: {}", service, e))?;
+        if !status.is_success() {
+            return Err(format!("{} rejected request (HTTP {}): {}", service, status, text));
+        }
+        serde_json::from_str(&text).map_err(|e| format!("Malformed {} response: {}", service, e))
+    }
+
+    /// CreateQuantumTask with the AHS IR; the task ARN is the job ID
+    async fn submit_braket(&self, target: &BraketTarget, program: &AnalogHamiltonianProgram) -> Result<String, String> {
+        if target.s3_bucket.is_empty() {
+            return Err("Braket needs an output bucket (BRAKET_S3_BUCKET)".to_string());
+        }
+        let body = json!({
+            "action": to_braket_ahs(program).to_string(),
+            "deviceArn": target.device_arn,
+            "outputS3Bucket": target.s3_bucket,
+            "outputS3KeyPrefix": target.s3_prefix,
+            "shots": target.shots,
+            "clientToken": format!("sentinel-{}-{}", chrono::Utc::now().timestamp_millis(), rand::thread_rng().gen::<u32>()),
+        });
+        let resp = self.braket_request("braket", &target.region, &target.host(), "POST", "/quantum-task", body.to_string().into_bytes()).await?;
+        let arn = resp["quantumTaskArn"].as_str()
+            .ok_or_else(|| format!("Braket response missing quantumTaskArn: {}", resp))?;
+
+        info!("NeutralAtom: Braket Task Accepted -> {}", arn);
+        Ok(arn.to_string())
+    }
+
+    /// GetQuantumTask, then the AHS result object from S3. Post-sequence 0 means the atom left the
+    /// ground state (Rydberg-excited), reported as '1' to match the mock's bitstrings.
+    async fn get_braket_result(&self, target: &BraketTarget, task_arn: &str) -> Result<ShotCounts, String> {
+        let path = format!("/quantum-task/{}", sigv4::uri_encode(task_arn, true));
+        let task = self.braket_request("braket", &target.region, &target.host(), "GET", &path, Vec::new()).await?;
+        match task["status"].as_str() {
+            Some("COMPLETED") => {}
+            Some(status) => return Err(format!("Braket task {} is {}", task_arn, status)),
+            None => return Err(format!("Braket task {} has no status", task_arn)),
+        }
+
+        let bucket = task["outputS3Bucket"].as_str().unwrap_or(&target.s3_bucket);
+        let directory = task["outputS3Directory"].as_str()
+            .ok_or_else(|| format!("Braket task {} has no output directory", task_arn))?;
+        let host = format!("{}.s3.{}.amazonaws.com", bucket, target.region);
+        let key = format!("/{}/results.json", sigv4::uri_encode(directory.trim_matches('/'), false));
+        let result = self.braket_request("s3", &target.region, &host, "GET", &key, Vec::new()).await?;
+
+        let mut counts = ShotCounts::new();
+        for shot in result["measurements"].as_array().into_iter().flatten() {
+            if shot["shotMetadata"]["shotStatus"].as_str() != Some("Success") {
+                continue; // Failed or partial shots (e.g. atom loss before the drive)
+            }
+            let bits: String = shot["shotResult"]["postSequence"].as_array().into_iter().flatten()
+                .map(|v| if v.as_u64() == Some(0) { '1' } else { '0' })
+                .collect();
+            *counts.entry(bits).or_default() += 1;
+        }
+        Ok(counts)
+    }
+
+    /// Mock sampler: mostly random maximal independent sets of the blockade graph (what an adiabatic
+    /// MIS sweep prepares), plus ~10% uniformly random readout noise
+    fn synthesize_counts<R: Rng>(&self, program: &AnalogHamiltonianProgram, rng: &mut R) -> ShotCounts {
+        let n = program.atoms.len();
+        let rabi = program.pulses.iter().map(|p| p.omega.abs()).fold(0.0, f64::max);
+        let edges = self.blockade_graph(program, rabi).unwrap_or_default();
+
+        let mut neighbours = vec![Vec::new(); n];
+        for &(a, b) in &edges {
+            neighbours[a].push(b);
+            neighbours[b].push(a);
+        }
+
+        let mut counts = ShotCounts::new();
+        for _ in 0..MOCK_SHOTS {
+            let mut excited = vec![false; n];
+            if rng.gen_bool(0.9) {
+                let mut order: Vec<usize> = (0..n).collect();
+                order.shuffle(rng);
+                for atom in order {
+                    if !neighbours[atom].iter().any(|&nb| excited[nb]) {
+                        excited[atom] = true;
+                    }
+                }
+            } else {
+                excited.iter_mut().for_each(|e| *e = rng.gen_bool(0.5));
+            }
+            let bits: String = excited.iter().map(|&e| if e { '1' } else { '0' }).collect();
+            *counts.entry(bits).or_default() += 1;
+        }
+        counts
+    }
+
+    /// The hedge ring as an adiabatic MIS sweep: `atoms` sites on a circle at the provider's
+    /// minimum spacing, driven at full Rabi frequency while the detuning sweeps from -omega to
+    /// +omega, in three equal segments filling 3/4 of the coherence budget. The market parameter
+    /// `theta` is bound as the drive phase, as it is bound into the gate-model circuit.
+    pub fn hedge_ring_program(&self, atoms: usize, theta: f64) -> AnalogHamiltonianProgram {
+        let radius = self.limits.min_spacing / (2.0 * (std::f64::consts::PI / atoms.max(3) as f64).sin());
+        let sites = (0..atoms)
+            .map(|i| {
+                let angle = 2.0 * std::f64::consts::PI * i as f64 / atoms as f64;
+                AtomCoordinates { x: radius * (1.0 + angle.cos()), y: radius * (1.0 + angle.sin()) }
+            })
+            .collect();
+        let omega = self.pulse_limits.max_omega;
+        let duration = self.pulse_limits.coherence_budget / 4.0;
+        let pulses = [-omega, 0.0, omega]
+            .into_iter()
+            .map(|delta| RydbergPulse { duration, omega, delta, phase: theta })
+            .collect();
+        AnalogHamiltonianProgram { register_name: format!("hedge_ring_{}", atoms), atoms: sites, pulses }
     }
 
     pub fn estimate_blockade_radius(&self, rabi_freq: f64) -> f64 {
@@ -63,4 +505,53 @@ impl NeutralAtomAdapter {
         // Rb = (C6 / Omega)^(1/6)
         (c6 / rabi_freq).powf(1.0 / 6.0)
     }
+
+    /// Rydberg interaction graph: pairs of atoms closer than the blockade radius at `rabi_freq`.
+    /// These are the edges of the induced Maximum Independent Set problem.
+    pub fn blockade_graph(&self, program: &AnalogHamiltonianProgram, rabi_freq: f64) -> Result<Vec<(usize, usize)>, String> {
+        if !(rabi_freq > 0.0 && rabi_freq.is_finite()) {
+            return Err(format!("Rabi frequency must be positive, got {}", rabi_freq));
+        }
+        let radius = self.estimate_blockade_radius(rabi_freq);
+        let atoms = &program.atoms;
+
+        let mut edges = Vec::new();
+        for a in 0..atoms.len() {
+            for b in (a + 1)..atoms.len() {
+                if (atoms[a].x - atoms[b].x).hypot(atoms[a].y - atoms[b].y) < radius {
+                    edges.push((a, b));
+                }
+            }
+        }
+        Ok(edges)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn atoms_one_micrometre_apart_violate_the_minimum_spacing() {
+        let adapter = NeutralAtomAdapter::new("quera", "");
+        let program = AnalogHamiltonianProgram {
+            register_name: "pair".to_string(),
+            atoms: vec![AtomCoordinates { x: 0.0, y: 0.0 }, AtomCoordinates { x: 1.0, y: 0.0 }],
+            pulses: Vec::new(),
+        };
+        let errors = adapter.validate_register(&program).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0], RegisterError::SpacingTooSmall { a: 0, b: 1, min, .. } if min == 4.0));
+    }
+
+    #[test]
+    fn the_hedge_ring_fits_each_providers_limits() {
+        for provider in ["pasqal", "quera", "mock"] {
+            let adapter = NeutralAtomAdapter::new(provider, "");
+            let program = adapter.hedge_ring_program(4, 0.1);
+            assert_eq!(program.atoms.len(), 4);
+            assert!(adapter.validate_register(&program).is_ok(), "{} register", provider);
+            assert!(adapter.validate_pulses(&program.pulses).is_ok(), "{} pulses", provider);
+        }
+    }
 }
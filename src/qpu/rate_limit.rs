@@ -0,0 +1,76 @@
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::{sleep, Instant};
+
+/// Token bucket shared by every outbound Runtime request. The bucket holds up to one second of
+/// tokens (at least one), so short bursts pass untouched and sustained load is smoothed to
+/// `requests_per_second`. An empty bucket makes callers wait for their turn instead of failing.
+/// Waiters reserve their token up front, so they are served in arrival order.
+pub struct RateLimiter {
+    rate: f64,     // Tokens added per second; <= 0 disables limiting
+    capacity: f64, // Burst size
+    bucket: Mutex<Bucket>,
+}
+
+struct Bucket {
+    tokens: f64, // Negative while callers are waiting on reserved tokens
+    refilled: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(requests_per_second: f64) -> Self {
+        let capacity = requests_per_second.max(1.0);
+        Self {
+            rate: requests_per_second,
+            capacity,
+            bucket: Mutex::new(Bucket { tokens: capacity, refilled: Instant::now() }),
+        }
+    }
+
+    /// No limit: every call proceeds at once
+    #[allow(dead_code)]
+    pub fn unlimited() -> Self {
+        Self::new(0.0)
+    }
+
+    #[allow(dead_code)]
+    pub fn requests_per_second(&self) -> f64 {
+        self.rate
+    }
+
+    /// Waits until a request may be sent
+    pub async fn acquire(&self) {
+        if self.rate <= 0.0 || !self.rate.is_finite() {
+            return;
+        }
+        let wait = {
+            let mut bucket = self.bucket.lock().unwrap();
+            let now = Instant::now();
+            let elapsed = now.duration_since(bucket.refilled).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.capacity);
+            bucket.refilled = now;
+            bucket.tokens -= 1.0;
+            (bucket.tokens < 0.0).then(|| Duration::from_secs_f64(-bucket.tokens / self.rate))
+        };
+        if let Some(wait) = wait {
+            sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::future::join_all;
+
+    #[tokio::test]
+    async fn twenty_calls_at_five_per_second_wait_out_the_bucket() {
+        let limiter = RateLimiter::new(5.0);
+        let started = Instant::now();
+        join_all((0..20).map(|_| limiter.acquire())).await;
+        // A burst of 5 passes at once; the other 15 are paced at 5/s
+        let elapsed = started.elapsed();
+        assert!(elapsed >= Duration::from_millis(2900), "20 calls took {:?}", elapsed);
+        assert!(elapsed < Duration::from_secs(4), "20 calls took {:?}", elapsed);
+    }
+}
@@ -0,0 +1,90 @@
+use super::transport::RuntimeTransport;
+use log::{info, warn};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// RAII handle for a billed Runtime session, returned by `open_session`. If it is dropped while
+/// still armed (an early return or a panic unwound past the owner before `close_session` ran),
+/// it spawns `DELETE /sessions/{id}` on the current Tokio runtime and clears the session file
+/// once the delete succeeds.
+///
+/// The delete is fire-and-forget: `Drop` cannot await, so the request may still be in flight
+/// (or never run) if the runtime shuts down right after the drop. Outside a runtime the guard
+/// only logs a warning; the session then expires server-side and the session file lets the next
+/// start retire it. A successful `close_session` disarms the guard, so explicit closes stay the
+/// primary path and the guard is the safety net.
+#[must_use = "dropping the guard closes the session"]
+pub struct SessionGuard {
+    session_id: String,
+    cleanup: Option<Cleanup>, // None for Digital Twin / dry-run sessions, which cost nothing
+    armed: Arc<AtomicBool>,
+}
+
+struct Cleanup {
+    transport: Arc<dyn RuntimeTransport>,
+    session_file: Option<PathBuf>,
+}
+
+impl SessionGuard {
+    pub(super) fn new(session_id: &str, transport: Arc<dyn RuntimeTransport>, session_file: Option<PathBuf>, armed: Arc<AtomicBool>) -> Self {
+        Self {
+            session_id: session_id.to_string(),
+            cleanup: Some(Cleanup { transport, session_file }),
+            armed,
+        }
+    }
+
+    /// Guard for a simulated session: nothing to release on drop
+    pub(super) fn simulated(session_id: &str) -> Self {
+        Self {
+            session_id: session_id.to_string(),
+            cleanup: None,
+            armed: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// False once the session was closed explicitly (or is simulated)
+    #[allow(dead_code)]
+    pub fn is_armed(&self) -> bool {
+        self.armed.load(Ordering::SeqCst)
+    }
+
+    /// Keeps the session open past the guard, e.g. to resume it after a planned restart
+    #[allow(dead_code)]
+    pub fn disarm(&self) {
+        self.armed.store(false, Ordering::SeqCst);
+    }
+}
+
+impl Drop for SessionGuard {
+    fn drop(&mut self) {
+        if !self.armed.swap(false, Ordering::SeqCst) {
+            return;
+        }
+        let Some(Cleanup { transport, session_file }) = self.cleanup.take() else { return };
+        let id = std::mem::take(&mut self.session_id);
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                warn!("QiskitRuntime: Session [{}] dropped without close_session, deleting it", id);
+                handle.spawn(async move {
+                    match transport.delete(&format!("/sessions/{}", id)).await {
+                        Ok(_) => {
+                            if let Some(path) = session_file {
+                                let _ = std::fs::remove_file(path);
+                            }
+                            info!("QiskitRuntime: Session Closed [{}] by guard", id);
+                        }
+                        Err(e) => warn!("QiskitRuntime: Guard could not close session [{}]: {}", id, e),
+                    }
+                });
+            }
+            Err(_) => warn!("QiskitRuntime: Session [{}] dropped outside the runtime; it stays open until it expires", id),
+        }
+    }
+}
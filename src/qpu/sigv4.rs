@@ -0,0 +1,144 @@
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use std::env;
+
+const ALGORITHM: &str = "AWS4-HMAC-SHA256";
+const SHA256_BLOCK: usize = 64;
+
+/// AWS credentials, from the standard environment variables
+#[derive(Debug, Clone)]
+pub struct AwsCredentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub session_token: Option<String>, // Set for STS / SSO credentials
+}
+
+impl AwsCredentials {
+    pub fn from_env() -> Result<Self, String> {
+        let access_key_id = env::var("AWS_ACCESS_KEY_ID").map_err(|_| "AWS_ACCESS_KEY_ID not set".to_string())?;
+        let secret_access_key = env::var("AWS_SECRET_ACCESS_KEY").map_err(|_| "AWS_SECRET_ACCESS_KEY not set".to_string())?;
+        Ok(Self {
+            access_key_id,
+            secret_access_key,
+            session_token: env::var("AWS_SESSION_TOKEN").ok().filter(|t| !t.is_empty()),
+        })
+    }
+}
+
+/// A request to be signed. `path` is the path as sent on the wire (already URI-encoded);
+/// `query` pairs are unencoded.
+pub struct SignableRequest<'a> {
+    pub method: &'a str,
+    pub host: &'a str,
+    pub path: &'a str,
+    pub query: &'a [(&'a str, &'a str)],
+    pub payload: &'a [u8],
+}
+
+/// Signature Version 4: returns the headers to attach (Authorization, x-amz-date,
+/// x-amz-content-sha256 and, for temporary credentials, x-amz-security-token).
+/// S3 canonicalizes the path as sent; every other service encodes it a second time.
+pub fn sign(req: &SignableRequest, service: &str, region: &str, creds: &AwsCredentials, now: DateTime<Utc>) -> Vec<(String, String)> {
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let payload_hash = hex::encode(Sha256::digest(req.payload));
+
+    let mut headers: Vec<(String, String)> = vec![
+        ("host".to_string(), req.host.to_string()),
+        ("x-amz-content-sha256".to_string(), payload_hash.clone()),
+        ("x-amz-date".to_string(), amz_date.clone()),
+    ];
+    if let Some(token) = &creds.session_token {
+        headers.push(("x-amz-security-token".to_string(), token.clone()));
+    }
+    headers.sort();
+    let canonical_headers: String = headers.iter().map(|(k, v)| format!("{}:{}\n", k, v.trim())).collect();
+    let signed_headers = headers.iter().map(|(k, _)| k.as_str()).collect::<Vec<_>>().join(";");
+
+    let canonical_uri = if service == "s3" { req.path.to_string() } else { uri_encode(req.path, false) };
+    let mut query: Vec<(String, String)> = req.query.iter().map(|(k, v)| (uri_encode(k, true), uri_encode(v, true))).collect();
+    query.sort();
+    let canonical_query = query.iter().map(|(k, v)| format!("{}={}", k, v)).collect::<Vec<_>>().join("&");
+
+    let canonical_request = format!("{}\n{}\n{}\n{}\n{}\n{}",
+        req.method, canonical_uri, canonical_query, canonical_headers, signed_headers, payload_hash);
+    let scope = format!("{}/{}/{}/aws4_request", date, region, service);
+    let string_to_sign = format!("{}\n{}\n{}\n{}",
+        ALGORITHM, amz_date, scope, hex::encode(Sha256::digest(canonical_request.as_bytes())));
+
+    let k_date = hmac_sha256(format!("AWS4{}", creds.secret_access_key).as_bytes(), date.as_bytes());
+    let k_region = hmac_sha256(&k_date, region.as_bytes());
+    let k_service = hmac_sha256(&k_region, service.as_bytes());
+    let k_signing = hmac_sha256(&k_service, b"aws4_request");
+    let signature = hex::encode(hmac_sha256(&k_signing, string_to_sign.as_bytes()));
+
+    let mut out: Vec<(String, String)> = headers.into_iter().filter(|(k, _)| k != "host").collect();
+    out.push(("authorization".to_string(), format!("{} Credential={}/{}, SignedHeaders={}, Signature={}",
+        ALGORITHM, creds.access_key_id, scope, signed_headers, signature)));
+    out
+}
+
+/// RFC 2104 HMAC over SHA-256
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut block = [0u8; SHA256_BLOCK];
+    if key.len() > SHA256_BLOCK {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let ipad: Vec<u8> = block.iter().map(|b| b ^ 0x36).collect();
+    let opad: Vec<u8> = block.iter().map(|b| b ^ 0x5c).collect();
+
+    let inner = Sha256::new().chain_update(&ipad).chain_update(message).finalize();
+    Sha256::new().chain_update(&opad).chain_update(inner).finalize().into()
+}
+
+/// SigV4 URI encoding: everything but unreserved characters, optionally keeping '/'
+pub fn uri_encode(input: &str, encode_slash: bool) -> String {
+    let mut out = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => out.push(byte as char),
+            b'/' if !encode_slash => out.push('/'),
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn hmac_matches_rfc_4231() {
+        let mac = hmac_sha256(b"Jefe", b"what do ya want for nothing?");
+        assert_eq!(hex::encode(mac), "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843");
+    }
+
+    #[test]
+    fn a_braket_submission_is_signed_over_host_hash_and_date() {
+        let creds = AwsCredentials {
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+            session_token: None,
+        };
+        let req = SignableRequest {
+            method: "POST",
+            host: "braket.us-east-1.amazonaws.com",
+            path: "/quantum-task",
+            query: &[],
+            payload: br#"{"shots":100}"#,
+        };
+        let now = Utc.with_ymd_and_hms(2015, 8, 30, 12, 36, 0).unwrap();
+        let headers = sign(&req, "braket", "us-east-1", &creds, now);
+
+        let authorization = &headers.iter().find(|(k, _)| k == "authorization").unwrap().1;
+        assert_eq!(authorization, "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/braket/aws4_request, \
+            SignedHeaders=host;x-amz-content-sha256;x-amz-date, \
+            Signature=0aac61d5729d5ac2fa208004b7666bf5b9be6deae916d3fd4e9b611dc3a9601e");
+        assert!(headers.iter().any(|(k, v)| k == "x-amz-date" && v == "20150830T123600Z"));
+        assert!(!headers.iter().any(|(k, _)| k == "host" || k == "x-amz-security-token"));
+    }
+}
@@ -0,0 +1,70 @@
+use log::{debug, warn};
+use serde::Deserialize;
+use std::path::PathBuf;
+use std::process::Command;
+
+const TOKEN_ENV_VAR: &str = "IBM_QUANTUM_API_TOKEN";
+
+/// Where the IBM Quantum API token comes from. Sources are tried in order; the first non-empty
+/// one wins. In sentinel.toml:
+///
+/// ```toml
+/// [runtime]
+/// token_sources = [{ file = "/run/secrets/ibm_token" }, { command = "pass show ibm/quantum" }, "env"]
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenSource {
+    /// `IBM_QUANTUM_API_TOKEN`
+    Env,
+    /// A mounted secret; surrounding whitespace and newlines are trimmed
+    File(PathBuf),
+    /// A helper run through `sh -c`, whose trimmed stdout is the token
+    Command(String),
+}
+
+impl TokenSource {
+    /// The token, or None when this source yields nothing. Never logs the token itself.
+    pub fn resolve(&self) -> Option<String> {
+        let token = match self {
+            TokenSource::Env => std::env::var(TOKEN_ENV_VAR).ok(),
+            TokenSource::File(path) => match std::fs::read_to_string(path) {
+                Ok(text) => Some(text),
+                Err(e) => {
+                    warn!("QPU: Token file {} unreadable: {}", path.display(), e);
+                    None
+                }
+            },
+            TokenSource::Command(cmd) => match Command::new("sh").arg("-c").arg(cmd).output() {
+                Ok(out) if out.status.success() => String::from_utf8(out.stdout).ok(),
+                Ok(out) => {
+                    warn!("QPU: Token command exited with {}", out.status);
+                    None
+                }
+                Err(e) => {
+                    warn!("QPU: Token command could not run: {}", e);
+                    None
+                }
+            },
+        };
+        let token = token.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+        if token.is_some() {
+            debug!("QPU: API token loaded from {}", self.describe());
+        }
+        token
+    }
+
+    /// For logs: the source, never its contents
+    pub fn describe(&self) -> String {
+        match self {
+            TokenSource::Env => format!("${}", TOKEN_ENV_VAR),
+            TokenSource::File(path) => format!("file {}", path.display()),
+            TokenSource::Command(_) => "token command".to_string(),
+        }
+    }
+}
+
+/// First token any source yields, tried in order
+pub fn resolve_token(sources: &[TokenSource]) -> Option<String> {
+    sources.iter().find_map(TokenSource::resolve)
+}
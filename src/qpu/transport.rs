@@ -0,0 +1,145 @@
+use futures_util::future::BoxFuture;
+use reqwest::{Client, RequestBuilder, StatusCode};
+use serde_json::Value;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Failure of a single Runtime HTTP exchange. `Status` carries non-2xx responses so the retry
+/// layer can honour `Retry-After` and callers can special-case codes such as 409.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum TransportError {
+    #[error("HTTP {status}: {body}")]
+    Status { status: u16, retry_after: Option<Duration>, body: String },
+    #[error("transport error: {message}")]
+    Network { message: String, failure: NetworkFailure },
+    #[error("malformed response: {0}")]
+    Decode(String),
+}
+
+/// How a request failed below HTTP, which decides whether resending it is safe
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetworkFailure {
+    /// No connection was made, so the server never saw the request
+    Connect,
+    /// The request may have been received and acted on before the response was lost
+    Timeout,
+    Other,
+}
+
+impl TransportError {
+    /// 429 / 502 / 503 / 504 and transient network failures. A non-`idempotent` request (a POST
+    /// with no idempotency key) may already have created a (billed) job when a gateway error or
+    /// timeout came back, so it is only resent when it never reached the service (connect error)
+    /// or the service explicitly deferred it: 429 / 503 carrying `Retry-After`.
+    pub fn is_retryable(&self, idempotent: bool) -> bool {
+        match self {
+            TransportError::Status { status, retry_after, .. } => match status {
+                429 | 503 => idempotent || retry_after.is_some(),
+                502 | 504 => idempotent,
+                _ => false,
+            },
+            TransportError::Network { failure, .. } => match failure {
+                NetworkFailure::Connect => true,
+                NetworkFailure::Timeout => idempotent,
+                NetworkFailure::Other => false,
+            },
+            TransportError::Decode(_) => false,
+        }
+    }
+
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            TransportError::Status { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+}
+
+/// The HTTP layer under QiskitRuntimeService. Paths are relative to the Runtime base URL
+/// (e.g. "/sessions"); a 2xx body is returned as JSON (Null when empty). Boxed futures keep the
+/// trait object-safe, so tests can inject a fake returning canned responses.
+pub trait RuntimeTransport: Send + Sync {
+    fn post<'a>(&'a self, path: &'a str, body: Value) -> BoxFuture<'a, Result<Value, TransportError>>;
+    fn get<'a>(&'a self, path: &'a str) -> BoxFuture<'a, Result<Value, TransportError>>;
+    fn delete<'a>(&'a self, path: &'a str) -> BoxFuture<'a, Result<Value, TransportError>>;
+}
+
+/// Bearer-token transport over reqwest, the production implementation
+pub struct ReqwestTransport {
+    client: Client,
+    base_url: String,
+    token: String,
+}
+
+impl ReqwestTransport {
+    pub fn new(base_url: &str, token: &str) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap();
+        Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token: token.to_string(),
+        }
+    }
+
+    async fn send(&self, req: RequestBuilder) -> Result<Value, TransportError> {
+        let resp = req
+            .header("Authorization", format!("Bearer {}", self.token))
+            .send()
+            .await
+            .map_err(|e| {
+                let failure = if e.is_connect() {
+                    NetworkFailure::Connect
+                } else if e.is_timeout() {
+                    NetworkFailure::Timeout
+                } else {
+                    NetworkFailure::Other
+                };
+                TransportError::Network { message: e.to_string(), failure }
+            })?;
+
+        let status = resp.status();
+        let retry_after = retry_after(&resp);
+        let text = resp.text().await
+            .map_err(|e| TransportError::Network { message: e.to_string(), failure: NetworkFailure::Other })?;
+        if !status.is_success() {
+            return Err(TransportError::Status { status: status.as_u16(), retry_after, body: text });
+        }
+        if text.trim().is_empty() || status == StatusCode::NO_CONTENT {
+            return Ok(Value::Null);
+        }
+        serde_json::from_str(&text).map_err(|e| TransportError::Decode(e.to_string()))
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+}
+
+impl RuntimeTransport for ReqwestTransport {
+    fn post<'a>(&'a self, path: &'a str, body: Value) -> BoxFuture<'a, Result<Value, TransportError>> {
+        Box::pin(self.send(self.client.post(self.url(path)).json(&body)))
+    }
+
+    fn get<'a>(&'a self, path: &'a str) -> BoxFuture<'a, Result<Value, TransportError>> {
+        Box::pin(self.send(self.client.get(self.url(path))))
+    }
+
+    fn delete<'a>(&'a self, path: &'a str) -> BoxFuture<'a, Result<Value, TransportError>> {
+        Box::pin(self.send(self.client.delete(self.url(path))))
+    }
+}
+
+/// Parses `Retry-After` in its delta-seconds form (the form IBM Runtime emits)
+fn retry_after(resp: &reqwest::Response) -> Option<Duration> {
+    resp.headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
@@ -0,0 +1,76 @@
+use crate::config::CadenceConfig;
+use crate::feed::MarketTick;
+use log::info;
+
+/// Decides which ticks run an optimization cycle: every `max_interval` ticks at the latest, and
+/// immediately when volatility crosses above `vol_threshold`. The crossing is edge-triggered, so a
+/// regime that stays volatile re-hedges once on entry and then on the interval, not on every tick.
+/// Pure tick-in / bool-out, so a scripted vol sequence replays to the same schedule.
+#[derive(Debug, Clone)]
+pub struct CycleScheduler {
+    max_interval: u64,
+    vol_threshold: Option<f64>,
+    since_last: u64, // Ticks seen since the last cycle
+    above: bool,     // Vol was above the threshold on the previous tick
+}
+
+impl CycleScheduler {
+    /// Fixed cadence: one cycle every `max_interval` ticks
+    pub fn new(max_interval: u64) -> Self {
+        Self {
+            max_interval: max_interval.max(1),
+            vol_threshold: None,
+            since_last: 0,
+            above: false,
+        }
+    }
+
+    /// `[cadence] optimize_every` as the interval, `vol_trigger` as the threshold
+    pub fn from_config(cadence: &CadenceConfig) -> Self {
+        let scheduler = Self::new(cadence.optimize_every);
+        match cadence.vol_trigger {
+            Some(threshold) => scheduler.with_vol_threshold(threshold),
+            None => scheduler,
+        }
+    }
+
+    /// Builder: Also trigger when `tick.vol` rises above `threshold`
+    pub fn with_vol_threshold(mut self, threshold: f64) -> Self {
+        self.vol_threshold = Some(threshold);
+        self
+    }
+
+    /// Feeds one tick; true when a cycle should run on it
+    pub fn should_run(&mut self, tick: &MarketTick) -> bool {
+        self.since_last += 1;
+        let above = self.vol_threshold.is_some_and(|t| tick.vol > t);
+        let crossed = above && !self.above;
+        self.above = above;
+
+        if crossed {
+            info!("Scheduler: Vol {:.3} crossed {:.3}, running an early cycle.", tick.vol, self.vol_threshold.unwrap_or_default());
+        }
+        if crossed || self.since_last >= self.max_interval {
+            self.since_last = 0;
+            return true;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripted_vol_sequence_runs_on_crossings_and_the_interval() {
+        let mut scheduler = CycleScheduler::new(5).with_vol_threshold(0.3);
+        let vols = [0.1, 0.1, 0.4, 0.5, 0.5, 0.5, 0.5, 0.5, 0.2, 0.35];
+        let runs: Vec<usize> = vols.iter().enumerate()
+            .filter(|(_, &vol)| scheduler.should_run(&MarketTick { price: 100.0, vol, ts: 0.0 }))
+            .map(|(i, _)| i + 1)
+            .collect();
+        // Tick 3 crosses, tick 8 is the interval within the volatile regime, tick 10 re-crosses
+        assert_eq!(runs, vec![3, 8, 10]);
+    }
+}
@@ -1,56 +1,242 @@
 use tracing::{info, warn, error};
-use std::sync::{Arc, Mutex};
-use std::time::{Duration, Instant};
+use crate::clock::{Clock, SystemClock};
+use crate::metrics::{self, MetricsRegistry};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
 
-use serde_json::json;
+/// Fraction of T1 a schedule may consume (Formal Standard); shared by every coherence check
+pub const T1_SAFETY_FRACTION: f64 = 0.5;
+
+/// Gate durations and the T1 budget a schedule may use. `Default` is the legacy model
+/// (50ns for every layer, 50% of T1); real backends should supply their own two-qubit time.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct GateModel {
+    pub single_qubit_ns: f64,
+    pub two_qubit_ns: f64,
+    pub safety_fraction: f64,
+}
+
+impl Default for GateModel {
+    fn default() -> Self {
+        Self {
+            single_qubit_ns: 50.0,
+            two_qubit_ns: 50.0,
+            safety_fraction: T1_SAFETY_FRACTION,
+        }
+    }
+}
+
+impl GateModel {
+    /// Schedule length in microseconds, treating each layer as one gate time deep
+    pub fn duration_micros(&self, layers_1q: usize, layers_2q: usize) -> f64 {
+        (layers_1q as f64 * self.single_qubit_ns + layers_2q as f64 * self.two_qubit_ns) / 1000.0
+    }
+}
 
 /// SRE: Formal Checks
 pub struct CoherenceVerifier;
 
 impl CoherenceVerifier {
     /// Verifies if a quantum circuit can physically run on the target hardware
-    /// Rejects if Estimate Duration > 0.5 * T1 (Safety Margin)
+    /// Rejects if Estimate Duration > 0.5 * T1 (Safety Margin), every layer costing 50ns
+    #[allow(dead_code)]
     pub fn verify(depth: usize, t1_micros: f64) -> bool {
-        // Model: Gate Time ~ 50ns per depth layer
-        // Total Duration (us) = depth * 0.05
-        let duration_us = depth as f64 * 0.05;
-        let limit = t1_micros * 0.5; // Conservative 50% safety margin (Formal Standard)
-        
+        Self::verify_with_model(depth, 0, t1_micros, &GateModel::default())
+    }
+
+    /// Rejects if the schedule's duration under `model` exceeds `safety_fraction * T1`
+    #[allow(dead_code)]
+    pub fn verify_with_model(layers_1q: usize, layers_2q: usize, t1_micros: f64, model: &GateModel) -> bool {
+        Self::check(layers_1q, layers_2q, t1_micros, None, model).is_ok()
+    }
+
+    /// Full check against both relaxation (T1) and dephasing (T2, when known). The schedule must
+    /// fit within `safety_fraction` of the tighter limit; the verdict names the one that was hit.
+    pub fn check(layers_1q: usize, layers_2q: usize, t1_micros: f64, t2_micros: Option<f64>, model: &GateModel) -> CoherenceVerdict {
+        let duration_us = model.duration_micros(layers_1q, layers_2q);
+        let (verdict, coherence) = match t2_micros {
+            Some(t2) if t2 < t1_micros => (CoherenceVerdict::T2Limited, t2),
+            _ => (CoherenceVerdict::T1Limited, t1_micros),
+        };
+        let limit = coherence * model.safety_fraction;
+
         if duration_us > limit {
-            warn!("COHERENCE VIOLATION: Circuit Depth {} (~{:.3}us) exceeds T1 Safety Limit ({:.3}us).", 
-                  depth, duration_us, limit);
-            false
+            warn!("COHERENCE VIOLATION: {} 1q + {} 2q layers (~{:.3}us) exceed {:?} Safety Limit ({:.3}us).",
+                  layers_1q, layers_2q, duration_us, verdict, limit);
+            verdict
         } else {
-            true
+            CoherenceVerdict::Ok
         }
     }
 }
 
+/// Outcome of a coherence check; the limited variants name the binding physics limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoherenceVerdict {
+    Ok,
+    T1Limited, // Relaxation budget exceeded
+    T2Limited, // Dephasing budget exceeded (T2 tighter than T1)
+}
+
+impl CoherenceVerdict {
+    pub fn is_ok(&self) -> bool {
+        *self == CoherenceVerdict::Ok
+    }
+}
+
 /// Circuit Breaker State
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum HealthState {
     Healthy,
+    #[allow(dead_code)]
     Degraded,
     Open, // Circuit Open (Stop Requests)
+    HalfOpen, // Cooldown elapsed, a single probe request is in flight
+}
+
+impl HealthState {
+    /// Prometheus encoding: 0 = closed, 1 = half-open/degraded, 2 = open
+    pub fn as_gauge(&self) -> u8 {
+        match self {
+            HealthState::Healthy => 0,
+            HealthState::Degraded | HealthState::HalfOpen => 1,
+            HealthState::Open => 2,
+        }
+    }
+}
+
+/// Circuit Breaker Tuning: open when more than `error_threshold` failures land within `window`,
+/// stay open for `cooldown` before allowing traffic again
+#[derive(Debug, Clone, Copy)]
+pub struct BreakerConfig {
+    pub error_threshold: usize,
+    pub window: Duration,
+    pub cooldown: Duration,
+}
+
+impl Default for BreakerConfig {
+    fn default() -> Self {
+        Self {
+            error_threshold: 5,
+            window: Duration::from_secs(60),
+            cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Breaker bookkeeping for a single component
+#[derive(Debug, Clone)]
+pub struct BreakerState {
+    pub state: HealthState,
+    pub failures: VecDeque<Instant>, // Failure timestamps inside the rolling window
+    pub last_failure: Option<Instant>,
+}
+
+impl Default for BreakerState {
+    fn default() -> Self {
+        Self {
+            state: HealthState::Healthy,
+            failures: VecDeque::new(),
+            last_failure: None,
+        }
+    }
+}
+
+impl BreakerState {
+    pub fn error_count(&self) -> usize {
+        self.failures.len()
+    }
+
+    /// Forget failures older than the rolling window
+    fn prune(&mut self, now: Instant, window: Duration) {
+        while self.failures.front().is_some_and(|t| now.duration_since(*t) > window) {
+            self.failures.pop_front();
+        }
+    }
 }
 
+/// Locks `mutex`, recovering the guard if a panicking holder poisoned it. Every critical section
+/// here leaves its data consistent between statements, so the inner value stays usable and one
+/// panicked task must not take the breakers (and with them the whole hypervisor) down.
+fn locked<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Callback fired with the component name when its circuit opens
+pub type BreakerHook = Arc<dyn Fn(&str) + Send + Sync>;
+
 /// SRE Monitor: Tracks System Health, Metrics, and Safety
+/// Each component ("qpu", "interop", ...) has an independent breaker, so one flaky path fails in isolation.
+/// Cloning yields another handle onto the same shared breaker state.
+///
+/// Locking order: at most one of the mutexes below is held at a time. Each component's breaker
+/// bookkeeping lives in one `BreakerState` under the single `breakers` lock, and nothing else is
+/// acquired while it (or any other lock) is held, so no two tasks can wait on each other.
+#[derive(Clone)]
 pub struct SentinelSRE {
-    pub state: Arc<Mutex<HealthState>>,
-    pub error_count: Arc<Mutex<u32>>,
-    pub last_failure: Arc<Mutex<Option<Instant>>>,
+    config: BreakerConfig,
+    clock: Arc<dyn Clock>, // Drives failure windows, cooldowns and uptime
+    breakers: Arc<Mutex<HashMap<String, BreakerState>>>,
+    on_open: Arc<Mutex<Vec<BreakerHook>>>,
+    metrics_registry: Arc<Mutex<MetricsRegistry>>,
+    status: Arc<Mutex<StatusBoard>>,
+}
+
+/// Hypervisor state published by the main loop for the `/status` endpoint
+struct StatusBoard {
+    started: Instant,
+    last_price: Option<f64>,
+    ltl: serde_json::Value, // Latest MonitorState snapshot
 }
 
 impl SentinelSRE {
     pub fn new() -> Self {
+        Self::with_config(BreakerConfig::default())
+    }
+
+    pub fn with_config(config: BreakerConfig) -> Self {
         Self {
-            state: Arc::new(Mutex::new(HealthState::Healthy)),
-            error_count: Arc::new(Mutex::new(0)),
-            last_failure: Arc::new(Mutex::new(None)),
+            config,
+            clock: Arc::new(SystemClock),
+            breakers: Arc::new(Mutex::new(HashMap::new())),
+            on_open: Arc::new(Mutex::new(Vec::new())),
+            metrics_registry: Arc::new(Mutex::new(MetricsRegistry::default())),
+            status: Arc::new(Mutex::new(StatusBoard {
+                started: Instant::now(),
+                last_price: None,
+                ltl: serde_json::Value::Null,
+            })),
         }
     }
 
+    /// Builder: Read time from `clock` instead of the system clock, e.g. a `MockClock` a test
+    /// advances past the cooldown
+    #[allow(dead_code)]
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        locked(&self.status).started = clock.now();
+        self.clock = clock;
+        self
+    }
+
+    /// Builder: Bucket bounds (seconds) of the QPU job duration histogram
+    pub fn with_job_duration_buckets(self, bounds: &[f64]) -> Self {
+        locked(&self.metrics_registry).set_job_duration_buckets(bounds);
+        self
+    }
+
+    /// Registers a mitigation hook (e.g. cancelling in-flight QPU jobs) run when the circuit opens
+    pub fn on_circuit_open<F>(&self, hook: F)
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        locked(&self.on_open).push(Arc::new(hook));
+    }
+
     /// Records an event with structured logging
     pub fn record_metric(&self, component: &str, metric: &str, value: f64) {
         // Structured Log for ingestion
@@ -60,52 +246,300 @@ impl SentinelSRE {
             value = %value,
             timestamp = %chrono::Utc::now().to_rfc3339()
         );
+        locked(&self.metrics_registry).observe(component, metric, value);
+    }
+
+    /// Exponential moving average of a recorded metric, e.g. ("qpu", "latency")
+    #[allow(dead_code)]
+    pub fn get_ema(&self, component: &str, metric: &str) -> Option<f64> {
+        locked(&self.metrics_registry).stats(component, metric)?.ema()
     }
 
-    /// Report a failure and potentially trip the breaker
+    /// Median over the metric's recent samples
+    #[allow(dead_code)]
+    pub fn get_p50(&self, component: &str, metric: &str) -> Option<f64> {
+        locked(&self.metrics_registry).stats(component, metric)?.quantile(0.5)
+    }
+
+    /// 95th percentile over the metric's recent samples; the early signal of slow degradation
+    #[allow(dead_code)]
+    pub fn get_p95(&self, component: &str, metric: &str) -> Option<f64> {
+        locked(&self.metrics_registry).stats(component, metric)?.quantile(0.95)
+    }
+
+    /// Records one job's submission-to-terminal turnaround on `backend`
+    /// (`sentinel_qpu_job_duration_seconds{backend}`)
+    pub fn record_job_duration(&self, backend: &str, duration: Duration) {
+        locked(&self.metrics_registry).observe_job_duration(backend, duration.as_secs_f64());
+    }
+
+    /// Counts a violation of the named LTL property (`sentinel_ltl_violations_total{property}`)
+    /// and reports it as an "ltl" failure
+    pub fn record_violation(&self, property: &str, detail: &str) {
+        locked(&self.metrics_registry).inc_ltl_violations(property);
+        self.report_failure("ltl", &format!("'{}' violated: {}", property, detail));
+    }
+
+    /// Report a failure and potentially trip the component's breaker
     pub fn report_failure(&self, component: &str, error_msg: &str) {
-        let mut err_count = self.error_count.lock().unwrap();
-        let mut state = self.state.lock().unwrap();
-        let mut last_fail = self.last_failure.lock().unwrap();
+        let opened = {
+            let mut breakers = locked(&self.breakers);
+            let breaker = breakers.entry(component.to_string()).or_default();
 
-        *err_count += 1;
-        *last_fail = Some(Instant::now());
+            let now = self.clock.now();
+            breaker.prune(now, self.config.window);
+            breaker.failures.push_back(now);
+            breaker.last_failure = Some(now);
 
-        error!(target: "sre_alert",
-            component = %component,
-            error = %error_msg,
-            total_errors = %*err_count,
-            action = "investigate"
-        );
+            error!(target: "sre_alert",
+                component = %component,
+                error = %error_msg,
+                window_errors = %breaker.error_count(),
+                action = "investigate"
+            );
+
+            // A failed half-open probe re-opens immediately for another cooldown
+            let tripped = breaker.error_count() > self.config.error_threshold && breaker.state != HealthState::Open;
+            if tripped || breaker.state == HealthState::HalfOpen {
+                breaker.state = HealthState::Open;
+                true
+            } else {
+                false
+            }
+        };
 
-        if *err_count > 5 {
-            *state = HealthState::Open;
+        // Counters and hooks run outside the breaker lock so hooks may query or report back into the SRE
+        locked(&self.metrics_registry).inc_failures(component);
+        if opened {
             warn!(target: "circuit_breaker", "CIRCUIT OPENED: Too many failures in {}", component);
+            locked(&self.metrics_registry).inc_breaker_trips(component);
+            let hooks: Vec<BreakerHook> = locked(&self.on_open).clone(); // Snapshot: hooks run lock-free
+            for hook in &hooks {
+                hook(component);
+            }
         }
     }
 
-    /// Check if we can proceed (Circuit Breaker Logic)
-    pub fn check_health(&self) -> bool {
-        let state = *self.state.lock().unwrap();
-        if state == HealthState::Open {
-            // Simple Half-Open logic: Reset after 30 seconds
-            let last = *self.last_failure.lock().unwrap();
-            if let Some(t) = last {
-                if t.elapsed() > Duration::from_secs(30) {
-                    self.reset();
-                    return true;
+    /// Check if a component can proceed (Circuit Breaker Logic). Unknown components are healthy.
+    /// Once the cooldown has elapsed an Open breaker admits exactly one probe (-> HalfOpen);
+    /// the caller must then report the probe's outcome via `report_success` / `report_failure`
+    /// (or `abandon_probe` when it was cancelled before producing one).
+    pub fn check_health(&self, component: &str) -> bool {
+        let mut breakers = locked(&self.breakers);
+        let Some(breaker) = breakers.get_mut(component) else {
+            return true;
+        };
+        match breaker.state {
+            HealthState::Healthy | HealthState::Degraded => true,
+            HealthState::HalfOpen => false, // Probe already in flight
+            HealthState::Open => {
+                if self.cooled_down(breaker) {
+                    breaker.state = HealthState::HalfOpen;
+                    info!(target: "circuit_breaker", "{} HALF-OPEN: admitting a single probe.", component);
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    }
+
+    /// Closes a half-open breaker after its probe succeeded
+    pub fn report_success(&self, component: &str) {
+        let mut breakers = locked(&self.breakers);
+        if let Some(breaker) = breakers.get_mut(component) {
+            if breaker.state == HealthState::HalfOpen {
+                breaker.failures.clear();
+                breaker.state = HealthState::Healthy;
+                info!(target: "circuit_breaker", "{} Recovered. Circuit CLOSED (Healthy).", component);
+            }
+        }
+    }
+
+    /// Re-opens a half-open breaker whose probe was cancelled before it succeeded or failed. The
+    /// cooldown has already elapsed, so the next `check_health` admits a fresh probe.
+    pub fn abandon_probe(&self, component: &str) {
+        let mut breakers = locked(&self.breakers);
+        if let Some(breaker) = breakers.get_mut(component) {
+            if breaker.state == HealthState::HalfOpen {
+                breaker.state = HealthState::Open;
+            }
+        }
+    }
+
+    /// Coarse gate: true unless some component is still cooling down or has a probe in flight.
+    /// Read-only, so it never consumes a component's half-open probe.
+    pub fn check_health_all(&self) -> bool {
+        locked(&self.breakers).values().all(|breaker| match breaker.state {
+            HealthState::Healthy | HealthState::Degraded => true,
+            HealthState::HalfOpen => false,
+            HealthState::Open => self.cooled_down(breaker),
+        })
+    }
+
+    fn cooled_down(&self, breaker: &BreakerState) -> bool {
+        breaker.last_failure.is_some_and(|t| self.clock.elapsed_since(t) > self.config.cooldown)
+    }
+
+    /// Snapshot of every component's breaker
+    #[allow(dead_code)]
+    pub fn breaker_states(&self) -> HashMap<String, BreakerState> {
+        locked(&self.breakers).clone()
+    }
+
+    /// Prometheus text exposition of failures, breaker trips/states and recorded gauges
+    pub fn render_metrics(&self) -> String {
+        let mut states: Vec<(String, u8)> = locked(&self.breakers)
+            .iter()
+            .map(|(c, b)| (c.clone(), b.state.as_gauge()))
+            .collect();
+        states.sort();
+        locked(&self.metrics_registry).render(&states)
+    }
+
+    /// Records the latest price and LTL monitor snapshot for `/status`
+    pub fn publish_status(&self, price: f64, ltl: &impl Serialize) {
+        let mut status = locked(&self.status);
+        status.last_price = Some(price);
+        status.ltl = serde_json::to_value(ltl).unwrap_or(serde_json::Value::Null);
+    }
+
+    /// Readiness: false while any component's breaker is Open
+    pub fn is_ready(&self) -> bool {
+        locked(&self.breakers).values().all(|b| b.state != HealthState::Open)
+    }
+
+    /// `{"ready": true, "uptime_secs": 12.5, "last_price": 101.2, "ltl": {...}, "breakers": {"qpu": {...}}}`
+    pub fn status_json(&self) -> serde_json::Value {
+        let breakers: serde_json::Map<String, serde_json::Value> = locked(&self.breakers)
+            .iter()
+            .map(|(component, b)| (component.clone(), json!({
+                "state": format!("{:?}", b.state),
+                "errors_in_window": b.error_count(),
+            })))
+            .collect();
+        let ready = self.is_ready();
+        let status = locked(&self.status);
+        json!({
+            "ready": ready,
+            "uptime_secs": self.clock.elapsed_since(status.started).as_secs_f64(),
+            "last_price": status.last_price,
+            "ltl": status.ltl,
+            "breakers": breakers,
+        })
+    }
+
+    /// Serves `/metrics` for Prometheus scrapes; run it alongside the main loop via `tokio::spawn`
+    #[allow(dead_code)]
+    pub async fn serve_metrics(self, addr: SocketAddr) -> std::io::Result<()> {
+        metrics::serve(addr, move |path| {
+            let sre = self.clone();
+            async move {
+                match path.as_str() {
+                    "/metrics" => (200, "text/plain; version=0.0.4", sre.render_metrics()),
+                    _ => (404, "text/plain", "Not Found\n".to_string()),
                 }
             }
-            return false;
+        }).await
+    }
+}
+
+/// Operator endpoint: `/healthz` (200 unless a breaker is Open, else 503), `/status` (JSON, see
+/// `status_json`) and `/metrics`. Run it alongside the main loop via `tokio::spawn`.
+pub async fn serve_status(sre: Arc<SentinelSRE>, addr: SocketAddr) -> std::io::Result<()> {
+    metrics::serve(addr, move |path| {
+        let sre = sre.clone();
+        async move {
+            match path.as_str() {
+                "/healthz" if sre.is_ready() => (200, "text/plain", "ok\n".to_string()),
+                "/healthz" => (503, "text/plain", "breaker open\n".to_string()),
+                "/status" => (200, "application/json", sre.status_json().to_string()),
+                "/metrics" => (200, "text/plain; version=0.0.4", sre.render_metrics()),
+                _ => (404, "text/plain", "Not Found\n".to_string()),
+            }
         }
-        true
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+
+    fn breaker(clock: &MockClock) -> SentinelSRE {
+        let config = BreakerConfig { error_threshold: 2, window: Duration::from_secs(10), cooldown: Duration::from_secs(30) };
+        SentinelSRE::with_config(config).with_clock(Arc::new(clock.clone()))
     }
 
-    fn reset(&self) {
-        let mut count = self.error_count.lock().unwrap();
-        let mut state = self.state.lock().unwrap();
-        *count = 0;
-        *state = HealthState::Healthy;
-        info!(target: "circuit_breaker", "System Recovered. Circuit CLOSED (Healthy).");
+    #[test]
+    fn failures_spaced_beyond_the_window_never_open_the_breaker() {
+        let clock = MockClock::new();
+        let sre = breaker(&clock);
+        for _ in 0..20 {
+            sre.report_failure("qpu", "sporadic");
+            clock.advance(Duration::from_secs(11));
+        }
+        assert!(sre.check_health("qpu"));
+        assert_eq!(sre.breaker_states()["qpu"].state, HealthState::Healthy);
+        assert_eq!(sre.breaker_states()["qpu"].error_count(), 1);
+    }
+
+    #[test]
+    fn low_t2_with_high_t1_is_t2_limited() {
+        // 100 layers x 50ns = 5us: well inside half of T1 = 300us, past half of T2 = 8us
+        let model = GateModel::default();
+        assert_eq!(CoherenceVerifier::check(100, 0, 300.0, Some(8.0), &model), CoherenceVerdict::T2Limited);
+        assert_eq!(CoherenceVerifier::check(100, 0, 300.0, None, &model), CoherenceVerdict::Ok);
+    }
+
+    #[test]
+    fn an_open_breaker_turns_half_open_once_the_mock_clock_passes_cooldown() {
+        let clock = MockClock::new();
+        let sre = breaker(&clock);
+        for _ in 0..3 {
+            sre.report_failure("qpu", "down"); // One past the threshold of 2
+        }
+        assert_eq!(sre.breaker_states()["qpu"].state, HealthState::Open);
+
+        clock.advance(Duration::from_secs(29));
+        assert!(!sre.check_health("qpu"));
+        assert_eq!(sre.breaker_states()["qpu"].state, HealthState::Open);
+
+        clock.advance(Duration::from_secs(2));
+        assert!(sre.check_health("qpu"), "the probe should be admitted");
+        assert_eq!(sre.breaker_states()["qpu"].state, HealthState::HalfOpen);
+        assert!(!sre.check_health("qpu"), "only one probe at a time");
+    }
+
+    #[test]
+    fn a_successful_probe_closes_the_breaker() {
+        let clock = MockClock::new();
+        let sre = breaker(&clock);
+        for _ in 0..3 {
+            sre.report_failure("qpu", "down");
+        }
+        assert!(!sre.is_ready());
+
+        clock.advance(Duration::from_secs(31));
+        assert!(sre.check_health("qpu"));
+        sre.report_success("qpu");
+        assert_eq!(sre.breaker_states()["qpu"].state, HealthState::Healthy);
+        assert_eq!(sre.breaker_states()["qpu"].error_count(), 0);
+        assert!(sre.check_health_all());
+        assert!(sre.is_ready());
+    }
+
+    #[test]
+    fn an_abandoned_probe_is_admitted_again() {
+        let clock = MockClock::new();
+        let sre = breaker(&clock);
+        for _ in 0..3 {
+            sre.report_failure("qpu", "down");
+        }
+        clock.advance(Duration::from_secs(31));
+        assert!(sre.check_health("qpu"));
+        sre.abandon_probe("qpu");
+        assert_eq!(sre.breaker_states()["qpu"].state, HealthState::Open);
+        assert!(sre.check_health("qpu"));
     }
 }
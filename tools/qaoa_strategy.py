@@ -1,6 +1,10 @@
 import sys
 import numpy as np
 
+import json
+
+RING_4 = [(0, 1, 1.0), (1, 2, 1.0), (2, 3, 1.0), (3, 0, 1.0)]
+
 def generate_qaoa_circuit(steps: int, use_dd: bool = True) -> str:
     """
     Generates a REAL QAOA Ansatz with DYNAMICAL DECOUPLING (DD).
@@ -10,13 +14,28 @@ def generate_qaoa_circuit(steps: int, use_dd: bool = True) -> str:
     - This cancels out low-frequency noise and extends T2 coherence times.
     - Crucial for Deep QAOA on superconducting hardware.
     """
+    # topology: Ring (0,1), (1,2), (2,3), (3,0), unit weights
+    return _build_qaoa(4, RING_4, steps, use_dd)
+
+def generate_qaoa_from_problem(problem_json: str, use_dd: bool = True) -> str:
+    """
+    QAOA Ansatz for an explicit weighted cost Hamiltonian sum(w_uv Z_u Z_v), as serialized by the
+    Rust QaoaProblem: {"num_qubits": n, "edges": [[u, v, w], ...], "p": depth}.
+    """
+    problem = json.loads(problem_json)
+    n_qubits = int(problem["num_qubits"])
+    edges = [(int(u), int(v), float(w)) for (u, v, w) in problem["edges"]]
+    for (u, v, _) in edges:
+        if not (0 <= u < n_qubits and 0 <= v < n_qubits) or u == v:
+            raise ValueError(f"edge ({u},{v}) invalid for {n_qubits} qubits")
+    return _build_qaoa(n_qubits, edges, int(problem["p"]), use_dd)
+
+def _build_qaoa(n_qubits: int, edges, steps: int, use_dd: bool) -> str:
     try:
         from qiskit import QuantumCircuit
         from qiskit.circuit import Parameter
-        from qiskit.transpiler import PassManager
         # In a full impl, we'd use qiskit-ibm-provider's PadDynamicalDecoupling
         
-        n_qubits = 4
         qc = QuantumCircuit(n_qubits)
         gammas = [Parameter(f'gamma_{i}') for i in range(steps)]
         betas = [Parameter(f'beta_{i}') for i in range(steps)]
@@ -25,13 +44,12 @@ def generate_qaoa_circuit(steps: int, use_dd: bool = True) -> str:
         qc.h(range(n_qubits))
         
         for step in range(steps):
-            # Cost Hamiltonian (Rzz = CX; Rz; CX)
-            # topology: Ring (0,1), (1,2), (2,3), (3,0)
-            for (u, v) in [(0,1), (1,2), (2,3), (3,0)]:
-                 qc.rzz(gammas[step], u, v)
+            # Cost Hamiltonian (Rzz = CX; Rz; CX), angle scaled by the edge weight
+            for (u, v, w) in edges:
+                 qc.rzz(w * gammas[step], u, v)
                  
                  # Optimization: Manual DD Insertion on idle neighbors
-                 # If we are acting on (0,1), qubits 2 and 3 are idle.
+                 # If we are acting on (0,1), the other qubits are idle.
                  # Insert X gates to refocus spins.
                  if use_dd:
                      idle = [q for q in range(n_qubits) if q not in (u,v)]
@@ -49,26 +67,26 @@ def generate_qaoa_circuit(steps: int, use_dd: bool = True) -> str:
         
     except ImportError:
         # Fallback Compiler compatible with DD concept
-        qasm = ["OPENQASM 2.0;", 'include "qelib1.inc";', "qreg q[4];", "creg meas[4];"]
-        for i in range(4): qasm.append(f"h q[{i}];")
+        qasm = ["OPENQASM 2.0;", 'include "qelib1.inc";', f"qreg q[{n_qubits}];", f"creg meas[{n_qubits}];"]
+        for i in range(n_qubits): qasm.append(f"h q[{i}];")
         
         for step in range(steps):
             gamma = f"gamma_{step}"
             beta = f"beta_{step}"
-            for (u, v) in [(0,1), (1,2), (2,3), (3,0)]:
+            for (u, v, w) in edges:
                 qasm.append(f"// Gate({u},{v})")
                 qasm.append(f"cx q[{u}], q[{v}];")
-                qasm.append(f"rz({gamma}) q[{v}];")
+                qasm.append(f"rz({gamma}) q[{v}];" if w == 1.0 else f"rz({w!r}*{gamma}) q[{v}];")
                 qasm.append(f"cx q[{u}], q[{v}];")
                 
                 # Manual DD in QASM
                 if use_dd:
-                    others = [x for x in [0,1,2,3] if x != u and x != v]
+                    others = [x for x in range(n_qubits) if x != u and x != v]
                     for o in others:
                         qasm.append(f"x q[{o}]; // DD Sequence")
                         qasm.append(f"x q[{o}];")
             
-            for i in range(4):
+            for i in range(n_qubits):
                 qasm.append(f"rx(2*{beta}) q[{i}];")
                 
         qasm.append("measure q -> meas;")